@@ -12,10 +12,68 @@ use std::path::PathBuf;
 use anyhow::Context as _;
 use anyhow::Error;
 use anyhow::Result;
-#[cfg(feature = "deploy")]
 use anyhow::anyhow;
 
 
+/// The lint categories, as named in lint queries.
+const CATEGORIES: [&str; 7] = [
+    "correctness",
+    "deprecated",
+    "modernization",
+    "portability",
+    "performance",
+    "security",
+    "style",
+];
+
+
+/// Extract the value of a `; <key>: <value>` comment in a lint's query.
+fn lint_attribute<'src>(lint_src: &'src str, key: &str) -> Option<&'src str> {
+    lint_src
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(';'))
+        .find_map(|comment| comment.trim().strip_prefix(key)?.strip_prefix(':'))
+        .map(str::trim)
+}
+
+
+/// Extract the category of a lint from a `; category: <name>` comment
+/// in its query, returning the corresponding `Category` variant.
+fn lint_category(lint_path: &Path, lint_src: &str) -> Result<String> {
+    let category = lint_attribute(lint_src, "category").with_context(|| {
+        format!(
+            "lint `{}` does not specify a category (`; category: <name>`)",
+            lint_path.display()
+        )
+    })?;
+    if !CATEGORIES.contains(&category) {
+        return Err(anyhow!(
+            "lint `{}` has unknown category `{category}`",
+            lint_path.display()
+        ))
+    }
+
+    let mut variant = category.to_string();
+    // SANITY: We checked that the category is one of the known and
+    //         non-empty ones.
+    let () = variant[..1].make_ascii_uppercase();
+    Ok(variant)
+}
+
+/// Check whether a lint is enabled by default, as determined by an
+/// optional `; default: on|off` comment in its query.
+fn lint_default_enabled(lint_path: &Path, lint_src: &str) -> Result<bool> {
+    match lint_attribute(lint_src, "default") {
+        None | Some("on") => Ok(true),
+        Some("off") => Ok(false),
+        Some(default) => Err(anyhow!(
+            "lint `{}` has invalid default `{default}`; expected `on` or `off`",
+            lint_path.display()
+        )),
+    }
+}
+
+
 fn generate_lints(manifest_dir: &Path) -> Result<()> {
     let out_dir =
         env::var_os("OUT_DIR").context("failed to find `OUT_DIR` environment variable")?;
@@ -54,11 +112,13 @@ fn generate_lints(manifest_dir: &Path) -> Result<()> {
             )
         })?;
         let lint_name = lint_name.trim_end_matches(".scm");
+        let category = lint_category(&lint_path, &lint_src)?;
+        let default_enabled = lint_default_enabled(&lint_path, &lint_src)?;
         let lint_name_upper = lint_name.to_ascii_uppercase().replace('-', "_");
         let lint_var = format!("LINT_{lint_name_upper}_SRC");
         writeln!(
             &mut lints_rs_file,
-            r####"pub static {lint_var}: (&str, &str, &str) = (r###"{lint_name}"###, r###"{lint_src}"###, r###"{}"###);"####,
+            r####"pub static {lint_var}: (&str, &str, &str, super::Category, bool) = (r###"{lint_name}"###, r###"{lint_src}"###, r###"{}"###, super::Category::{category}, {default_enabled});"####,
             lint_msg.trim_end_matches('\n'),
         )?;
         let () = lint_vars.push(lint_var);
@@ -66,7 +126,7 @@ fn generate_lints(manifest_dir: &Path) -> Result<()> {
 
     writeln!(
         &mut lints_rs_file,
-        r#"pub static LINTS: [(&str, &str, &str); {}] = ["#,
+        r#"pub static LINTS: [(&str, &str, &str, super::Category, bool); {}] = ["#,
         lint_vars.len()
     )?;
     for lint_var in lint_vars {
@@ -1,13 +1,23 @@
+use std::env::args_os;
+use std::env::var_os;
+use std::ffi::OsStr;
+use std::ffi::OsString;
 use std::fs::File;
+use std::fs::read_to_string;
+use std::io;
 use std::io::BufRead as _;
 use std::io::BufReader;
+use std::io::IsTerminal as _;
+use std::path::Path;
 use std::path::PathBuf;
 
 use anyhow::Context as _;
 use anyhow::Result;
+use anyhow::anyhow;
 
 use clap::ArgAction;
 use clap::Parser;
+use clap::ValueEnum;
 
 
 fn parse_files(s: &str) -> Result<Vec<PathBuf>> {
@@ -55,9 +65,152 @@ fn parse_context_line_count(s: &str) -> Result<u8> {
     Ok(line_count)
 }
 
+/// Parse a lint category name for CLI arguments.
+fn parse_category(s: &str) -> Result<bpflint::Category> {
+    s.parse()
+}
+
+/// Parse the name of a built-in lint for CLI arguments.
+fn parse_lint_name(s: &str) -> Result<String> {
+    if bpflint::builtin_lints().any(|lint| lint.name == s) {
+        Ok(s.to_string())
+    } else {
+        Err(anyhow!("unknown lint `{s}`"))
+    }
+}
+
+/// Insert the arguments contained in the configuration file at `path`
+/// into `args`, right after the program name.
+///
+/// Each non-empty line of the file that does not start with `#`
+/// contains an option, optionally followed by its value, e.g.,
+/// `--enable bpf-printk`. Because the configuration precedes the
+/// actual command line arguments, the latter take precedence.
+fn merge_config(mut args: Vec<OsString>, path: &Path) -> Result<Vec<OsString>> {
+    let config = read_to_string(path)
+        .with_context(|| format!("failed to read configuration file `{}`", path.display()))?;
+    let mut config_args = Vec::new();
+    for line in config.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue
+        }
+
+        match line.split_once(char::is_whitespace) {
+            Some((option, value)) => {
+                let () = config_args.push(OsString::from(option));
+                let () = config_args.push(OsString::from(value.trim()));
+            },
+            None => config_args.push(OsString::from(line)),
+        }
+    }
+
+    let idx = args.len().min(1);
+    let () = args.splice(idx..idx, config_args).for_each(drop);
+    Ok(args)
+}
+
+/// Check whether to color output.
+///
+/// - `color` is the user's choice
+/// - `is_terminal` indicates whether output goes to a terminal
+/// - `no_color` is the value of the `NO_COLOR` environment variable,
+///   which, if set to a non-empty value, disables automatic coloring
+fn use_color(color: Color, is_terminal: bool, no_color: Option<&OsStr>) -> bool {
+    match color {
+        Color::Always => true,
+        Color::Never => false,
+        Color::Auto => is_terminal && no_color.is_none_or(OsStr::is_empty),
+    }
+}
+
+/// Check whether the terminal we are running in likely supports OSC 8
+/// hyperlinks, based on the environment variables reported by `var`.
+///
+/// Detection can be overridden by setting `FORCE_HYPERLINK` to `1` (or
+/// any non-empty value other than `0`) or `0`.
+fn supports_hyperlinks<F>(var: F) -> bool
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return !force.is_empty() && force != "0"
+    }
+
+    if ["WT_SESSION", "DOMTERM", "KONSOLE_VERSION"]
+        .iter()
+        .any(|name| var(name).is_some())
+    {
+        return true
+    }
+
+    // VTE based terminals support hyperlinks since version 0.50.
+    if let Some(version) = var("VTE_VERSION") {
+        if version
+            .to_str()
+            .and_then(|version| version.parse::<u32>().ok())
+            .is_some_and(|version| version >= 5000)
+        {
+            return true
+        }
+    }
+
+    if let Some(program) = var("TERM_PROGRAM") {
+        if ["iTerm.app", "WezTerm", "vscode", "ghostty"]
+            .contains(&program.to_str().unwrap_or_default())
+        {
+            return true
+        }
+    }
+
+    var("TERM").is_some_and(|term| {
+        ["xterm-kitty", "alacritty", "foot", "xterm-ghostty"]
+            .contains(&term.to_str().unwrap_or_default())
+    })
+}
+
+/// When to color terminal reports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Color {
+    /// Color reports if standard output is a terminal and the `NO_COLOR`
+    /// environment variable is not set.
+    #[default]
+    Auto,
+    /// Always color reports.
+    Always,
+    /// Never color reports.
+    Never,
+}
+
+/// The format in which to report lint matches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable reports including source code snippets.
+    #[default]
+    Terminal,
+    /// A JSON object per match, one per line (JSON Lines).
+    Json,
+    /// A SARIF 2.1.0 log, including matches suppressed by comments.
+    Sarif,
+    /// A checkstyle XML report.
+    Checkstyle,
+    /// A `JUnit` XML report, with a test case per file.
+    Junit,
+    /// GitHub Actions workflow commands annotating each match.
+    Github,
+    /// A GitLab Code Quality report.
+    Gitlab,
+    /// Compiler style one-line reports: `file:line:col: severity:
+    /// message [lint]`.
+    Short,
+    /// Compiler style one-line reports including the end position:
+    /// `file:line.col-end-line.end-col: severity: message [lint]`.
+    ShortRange,
+}
+
 /// A command line interface for bpflint.
 #[derive(Debug, Parser)]
-#[command(version = env!("VERSION"))]
+#[command(version = env!("VERSION"), args_override_self = true)]
 pub struct Args {
     /// The BPF C source files to lint.
     ///
@@ -74,16 +227,76 @@ pub struct Args {
     /// Number of lines to show before and after the lint match.
     #[arg(short = 'C', long = "context", value_parser = parse_context_line_count, conflicts_with_all = ["before", "after"])]
     pub context: Option<u8>,
-    /// Print a list of available lints.
+    /// The format in which to report lint matches.
+    #[arg(long, value_enum, default_value_t = Format::Terminal)]
+    pub format: Format,
+    /// When to color terminal reports, including syntax highlighting
+    /// of source code snippets.
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = Color::Auto)]
+    pub color: Color,
+    /// The stack size, in bytes, above which programs are reported.
+    #[arg(long, value_name = "BYTES")]
+    pub stack_limit: Option<u64>,
+    /// Enable lints of the given category, in addition to those enabled
+    /// by default (can be supplied multiple times).
+    ///
+    /// Lints of the `security` category are disabled by default.
+    #[arg(long, value_name = "CATEGORY", value_parser = parse_category)]
+    pub enable_category: Vec<bpflint::Category>,
+    /// Disable lints of the given category (can be supplied multiple
+    /// times).
+    #[arg(long, value_name = "CATEGORY", value_parser = parse_category)]
+    pub disable_category: Vec<bpflint::Category>,
+    /// Enable the lint with the given name, even if it is disabled by
+    /// default (can be supplied multiple times).
+    #[arg(long, value_name = "LINT", value_parser = parse_lint_name)]
+    pub enable: Vec<String>,
+    /// Read additional arguments from the given configuration file.
+    ///
+    /// Each line contains an option, optionally followed by its value
+    /// (e.g., `--enable bpf-printk`). Empty lines and lines starting
+    /// with '#' are ignored. Arguments provided on the command line
+    /// take precedence.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Print a table of available lints, along with their category,
+    /// severity, and whether they are enabled by default.
     #[arg(long, exclusive = true)]
     pub print_lints: bool,
+    /// Print the call graph of each source file as JSON, one object per
+    /// line, instead of linting.
+    #[arg(long, conflicts_with_all = [
+        "before",
+        "after",
+        "context",
+        "format",
+        "color",
+        "stack_limit",
+        "enable_category",
+        "disable_category",
+        "enable",
+        "config",
+    ])]
+    pub print_call_graph: bool,
     /// Increase verbosity (can be supplied multiple times).
     #[arg(short = 'v', long = "verbose", global = true, action = ArgAction::Count)]
     pub verbosity: u8,
 }
 
 impl Args {
-    /// Calculate the effective context configuration.
+    /// Parse arguments from the command line, taking into account an
+    /// optionally provided configuration file.
+    pub fn parse_with_config() -> Result<Self> {
+        let args = Self::parse();
+        if let Some(config) = &args.config {
+            let args = merge_config(args_os().collect(), config)?;
+            Ok(Self::parse_from(args))
+        } else {
+            Ok(args)
+        }
+    }
+
+    /// Calculate the effective reporting configuration.
     pub fn additional_options(&self) -> bpflint::Opts {
         let mut opts = bpflint::Opts::default();
         if let Some(before) = self.before.or(self.context) {
@@ -92,6 +305,28 @@ impl Args {
         if let Some(after) = self.after.or(self.context) {
             opts.extra_lines.1 = after;
         }
+        // Terminal reports embedded in other formats are never colored.
+        if self.format == Format::Terminal {
+            let no_color = var_os("NO_COLOR");
+            opts.color = use_color(self.color, io::stdout().is_terminal(), no_color.as_deref());
+            opts.hyperlinks = opts.color && supports_hyperlinks(|name| var_os(name));
+            if opts.color {
+                opts.highlight_query = Some(bpflint::HIGHLIGHT_QUERY.to_string());
+            }
+        }
+        opts
+    }
+
+    /// Calculate the effective lint configuration.
+    pub fn lint_options(&self) -> bpflint::LintOpts {
+        let mut opts = bpflint::LintOpts::default();
+        if let Some(stack_limit) = self.stack_limit {
+            opts.stack_limit = stack_limit;
+        }
+        opts.enabled_categories = self.enable_category.clone();
+        opts.disabled_categories = self.disable_category.clone();
+        opts.enabled_lints = self.enable.clone();
+        opts.report_suppressed = self.format == Format::Sarif;
         opts
     }
 }
@@ -101,7 +336,6 @@ impl Args {
 mod tests {
     use super::*;
 
-    use std::ffi::OsString;
     use std::io::Write as _;
 
     use tempfile::NamedTempFile;
@@ -193,6 +427,202 @@ mod tests {
         assert!(try_parse(["test.c", "-B", "2", "-A", "4"]).is_ok());
     }
 
+    /// Make sure that `--print-call-graph` can't be combined with
+    /// options it would ignore.
+    #[test]
+    fn print_call_graph_conflicts() {
+        assert!(try_parse(["test.c", "--print-call-graph"]).is_ok());
+        assert!(try_parse(["test.c", "-v", "--print-call-graph"]).is_ok());
+        assert!(try_parse(["test.c", "--print-call-graph", "--format", "json"]).is_err());
+        assert!(try_parse(["test.c", "--print-call-graph", "--enable", "bpf-printk"]).is_err());
+        assert!(try_parse(["--print-call-graph"]).is_err());
+    }
+
+    /// Test stack limit argument parsing and effective values.
+    #[test]
+    fn stack_limit_argument_parsing() {
+        let args = try_parse(["test.c"]).unwrap();
+        let opts = args.lint_options();
+        assert_eq!(opts.stack_limit, 512);
+
+        let args = try_parse(["test.c", "--stack-limit", "256"]).unwrap();
+        let opts = args.lint_options();
+        assert_eq!(opts.stack_limit, 256);
+
+        assert!(try_parse(["test.c", "--stack-limit", "abc"]).is_err());
+    }
+
+    /// Test lint category argument parsing and effective values.
+    #[test]
+    fn category_argument_parsing() {
+        let args = try_parse(["test.c"]).unwrap();
+        let opts = args.lint_options();
+        assert_eq!(opts.enabled_categories, Vec::new());
+
+        let args = try_parse(["test.c", "--enable-category", "security"]).unwrap();
+        let opts = args.lint_options();
+        assert_eq!(opts.enabled_categories, vec![bpflint::Category::Security]);
+
+        let args = try_parse([
+            "test.c",
+            "--disable-category",
+            "style",
+            "--disable-category",
+            "deprecated",
+        ])
+        .unwrap();
+        let opts = args.lint_options();
+        assert_eq!(
+            opts.disabled_categories,
+            vec![bpflint::Category::Style, bpflint::Category::Deprecated]
+        );
+
+        assert!(try_parse(["test.c", "--enable-category", "foobar"]).is_err());
+    }
+
+    /// Test lint enabling argument parsing and effective values.
+    #[test]
+    fn enable_argument_parsing() {
+        let args = try_parse(["test.c", "--enable", "bpf-printk"]).unwrap();
+        let opts = args.lint_options();
+        assert_eq!(opts.enabled_lints, vec!["bpf-printk".to_string()]);
+
+        assert!(try_parse(["test.c", "--enable", "foobar"]).is_err());
+    }
+
+    /// Check that arguments from a configuration file are merged with
+    /// those provided on the command line.
+    #[test]
+    fn config_merging() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(&mut file, "# Enable opt-in lints.").unwrap();
+        writeln!(&mut file, "--enable bpf-printk").unwrap();
+        writeln!(&mut file).unwrap();
+        writeln!(&mut file, "--stack-limit 256").unwrap();
+        writeln!(&mut file, "--enable-category=security").unwrap();
+        let () = file.flush().unwrap();
+
+        let config = file.path().display().to_string();
+        let args = [
+            "executable",
+            "test.c",
+            "--config",
+            &config,
+            "--stack-limit",
+            "128",
+        ]
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+        let args = merge_config(args, file.path()).unwrap();
+        let args = Args::try_parse_from(args).unwrap();
+        let opts = args.lint_options();
+        assert_eq!(opts.enabled_lints, vec!["bpf-printk".to_string()]);
+        assert_eq!(opts.enabled_categories, vec![bpflint::Category::Security]);
+        // The command line takes precedence.
+        assert_eq!(opts.stack_limit, 128);
+
+        let result = merge_config(Vec::new(), Path::new("/does/not/exist"));
+        assert!(result.is_err());
+    }
+
+    /// Test `--color` argument parsing.
+    #[test]
+    fn color_argument_parsing() {
+        let args = try_parse(["test.c"]).unwrap();
+        assert_eq!(args.color, Color::Auto);
+
+        let args = try_parse(["test.c", "--color", "always"]).unwrap();
+        assert_eq!(args.color, Color::Always);
+        let opts = args.additional_options();
+        assert!(opts.color);
+        assert!(opts.highlight_query.is_some());
+
+        let args = try_parse(["test.c", "--color", "never"]).unwrap();
+        assert_eq!(args.color, Color::Never);
+        let opts = args.additional_options();
+        assert!(!opts.color);
+        assert_eq!(opts.highlight_query, None);
+
+        // Reports embedded in other formats are never colored.
+        let args = try_parse(["test.c", "--color", "always", "--format", "junit"]).unwrap();
+        assert!(!args.additional_options().color);
+
+        assert!(try_parse(["test.c", "--color", "sometimes"]).is_err());
+    }
+
+    /// Check that we decide on whether to color output as expected.
+    #[test]
+    fn color_detection() {
+        let empty = OsStr::new("");
+        let one = OsStr::new("1");
+        assert!(use_color(Color::Auto, true, None));
+        assert!(use_color(Color::Auto, true, Some(empty)));
+        assert!(!use_color(Color::Auto, true, Some(one)));
+        assert!(!use_color(Color::Auto, false, None));
+        assert!(use_color(Color::Always, false, Some(one)));
+        assert!(!use_color(Color::Never, true, None));
+    }
+
+    /// Check that we detect hyperlink support as expected.
+    #[test]
+    fn hyperlink_detection() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| OsString::from(value))
+            }
+        };
+
+        assert!(!supports_hyperlinks(env(&[])));
+        assert!(!supports_hyperlinks(env(&[("TERM", "xterm-256color")])));
+        assert!(supports_hyperlinks(env(&[("TERM", "xterm-kitty")])));
+        assert!(supports_hyperlinks(env(&[("TERM_PROGRAM", "WezTerm")])));
+        assert!(supports_hyperlinks(env(&[("VTE_VERSION", "6003")])));
+        assert!(!supports_hyperlinks(env(&[("VTE_VERSION", "4803")])));
+        assert!(supports_hyperlinks(env(&[("FORCE_HYPERLINK", "1")])));
+        assert!(!supports_hyperlinks(env(&[
+            ("FORCE_HYPERLINK", "0"),
+            ("TERM", "xterm-kitty")
+        ])));
+    }
+
+    /// Test output format argument parsing.
+    #[test]
+    fn format_argument_parsing() {
+        let args = try_parse(["test.c"]).unwrap();
+        assert_eq!(args.format, Format::Terminal);
+
+        let args = try_parse(["test.c", "--format", "json"]).unwrap();
+        assert_eq!(args.format, Format::Json);
+        assert!(!args.lint_options().report_suppressed);
+
+        let args = try_parse(["test.c", "--format", "sarif"]).unwrap();
+        assert_eq!(args.format, Format::Sarif);
+        assert!(args.lint_options().report_suppressed);
+
+        let args = try_parse(["test.c", "--format", "checkstyle"]).unwrap();
+        assert_eq!(args.format, Format::Checkstyle);
+
+        let args = try_parse(["test.c", "--format", "junit"]).unwrap();
+        assert_eq!(args.format, Format::Junit);
+
+        let args = try_parse(["test.c", "--format", "github"]).unwrap();
+        assert_eq!(args.format, Format::Github);
+
+        let args = try_parse(["test.c", "--format", "gitlab"]).unwrap();
+        assert_eq!(args.format, Format::Gitlab);
+
+        let args = try_parse(["test.c", "--format", "short"]).unwrap();
+        assert_eq!(args.format, Format::Short);
+
+        let args = try_parse(["test.c", "--format", "short-range"]).unwrap();
+        assert_eq!(args.format, Format::ShortRange);
+
+        assert!(try_parse(["test.c", "--format", "foobar"]).is_err());
+    }
+
     /// Test `parse_context_line_count` function directly.
     #[test]
     fn parse_context_line_count_validation() {
@@ -0,0 +1,225 @@
+//! Report formats for annotating findings in CI systems.
+
+use std::borrow::Cow;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+use serde_json::json;
+
+use bpflint::Fingerprints;
+use bpflint::LintMatch;
+use bpflint::Severity;
+
+
+/// Escape `s` for usage as data in a GitHub workflow command.
+fn escape_data(s: &str) -> Cow<'_, str> {
+    if !s.contains(['%', '\r', '\n']) {
+        return Cow::Borrowed(s)
+    }
+    Cow::Owned(
+        s.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A"),
+    )
+}
+
+/// Escape `s` for usage as property value in a GitHub workflow command.
+fn escape_property(s: &str) -> Cow<'_, str> {
+    let s = escape_data(s);
+    if !s.contains([':', ',']) {
+        return s
+    }
+    Cow::Owned(s.replace(':', "%3A").replace(',', "%2C"))
+}
+
+
+/// Report a lint match as a GitHub Actions workflow command, creating
+/// an annotation.
+///
+/// Line and column numbers are one-based and columns count bytes, with
+/// the end column being exclusive. Matches not covering any code
+/// annotate the file as a whole.
+pub fn report_github(
+    r#match: &LintMatch,
+    path: &Path,
+    writer: &mut dyn io::Write,
+) -> io::Result<()> {
+    let LintMatch {
+        lint_name,
+        severity,
+        message,
+        range,
+        ..
+    } = r#match;
+
+    let command = match severity {
+        Severity::Error => "error",
+        Severity::Note => "notice",
+        _ => "warning",
+    };
+    let path = path.to_string_lossy();
+    write!(writer, "::{command} file={}", escape_property(&path))?;
+    if !range.bytes.is_empty() {
+        write!(
+            writer,
+            ",line={},col={},endLine={},endColumn={}",
+            range.start_point.row + 1,
+            range.start_point.col + 1,
+            range.end_point.row + 1,
+            range.end_point.col + 1,
+        )?;
+    }
+    let title = format!("[{lint_name}]");
+    writeln!(
+        writer,
+        ",title={}::{}",
+        escape_property(&title),
+        escape_data(message)
+    )
+}
+
+
+/// A GitLab Code Quality report, collecting the lint matches of a run.
+#[derive(Debug, Default)]
+pub struct GitlabReport {
+    /// The reported issues.
+    issues: Vec<Value>,
+    /// The generator of issue fingerprints.
+    fingerprints: Fingerprints,
+}
+
+impl GitlabReport {
+    /// Add a lint match to the report.
+    ///
+    /// Line and column numbers are one-based and columns count bytes,
+    /// with the end column being exclusive.
+    pub fn add(&mut self, r#match: &LintMatch, code: &[u8], path: &Path) {
+        let LintMatch {
+            lint_name,
+            severity,
+            message,
+            range,
+            ..
+        } = r#match;
+
+        let severity = match severity {
+            Severity::Error => "major",
+            Severity::Note => "info",
+            _ => "minor",
+        };
+        let fingerprint = self.fingerprints.fingerprint(r#match, code, path);
+        let issue = json!({
+            "type": "issue",
+            "check_name": lint_name,
+            "description": format!("[{lint_name}] {message}"),
+            "severity": severity,
+            "fingerprint": fingerprint,
+            "location": {
+                "path": path.to_string_lossy(),
+                "positions": {
+                    "begin": {
+                        "line": range.start_point.row + 1,
+                        "column": range.start_point.col + 1,
+                    },
+                    "end": {
+                        "line": range.end_point.row + 1,
+                        "column": range.end_point.col + 1,
+                    },
+                },
+            },
+        });
+        let () = self.issues.push(issue);
+    }
+
+    /// Write the report, as JSON, to `writer`.
+    pub fn write(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(writer, "{:#}", Value::from(self.issues.clone()))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bpflint::Point;
+    use bpflint::Range;
+
+
+    fn probe_read_match() -> LintMatch {
+        LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is deprecated, 100%\nreally".to_string(),
+            range: Range {
+                bytes: 17..31,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 1, col: 18 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        }
+    }
+
+
+    /// Check that we create the expected GitHub workflow commands.
+    #[test]
+    fn github_reporting() {
+        let mut output = Vec::new();
+        let () = report_github(&probe_read_match(), Path::new("a,b.bpf.c"), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            "::warning file=a%2Cb.bpf.c,line=2,col=5,endLine=2,endColumn=19,title=[probe-read]::bpf_probe_read() is deprecated, 100%25%0Areally\n"
+        );
+
+        let m = LintMatch {
+            severity: Severity::Note,
+            range: Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            },
+            ..probe_read_match()
+        };
+        let mut output = Vec::new();
+        let () = report_github(&m, Path::new("test.c"), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.starts_with("::notice file=test.c,title=[probe-read]::"),
+            "{output}"
+        );
+    }
+
+    /// Check that we create the expected GitLab Code Quality report.
+    #[test]
+    fn gitlab_reporting() {
+        let code = b"int main() {\n    bpf_probe_read(a, 1, b);\n}\n";
+        let path = Path::new("test.bpf.c");
+        let mut report = GitlabReport::default();
+        let () = report.add(&probe_read_match(), code, path);
+        let () = report.add(&probe_read_match(), code, path);
+
+        let mut output = Vec::new();
+        let () = report.write(&mut output).unwrap();
+        let output = serde_json::from_slice::<Value>(&output).unwrap();
+        let issues = output.as_array().unwrap();
+        assert_eq!(issues.len(), 2);
+
+        let issue = &issues[0];
+        assert_eq!(issue["check_name"], "probe-read");
+        assert_eq!(issue["severity"], "minor");
+        assert_eq!(
+            issue["location"],
+            json!({
+                "path": "test.bpf.c",
+                "positions": {
+                    "begin": { "line": 2, "column": 5 },
+                    "end": { "line": 2, "column": 19 },
+                },
+            })
+        );
+        assert_ne!(issues[0]["fingerprint"], issues[1]["fingerprint"]);
+    }
+}
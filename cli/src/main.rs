@@ -1,6 +1,9 @@
 //! A linter for BPF C code.
 
 mod args;
+mod ci;
+mod short;
+mod xml;
 
 use std::env::var_os;
 use std::fmt::Debug;
@@ -19,8 +22,6 @@ use anyhow::Context as _;
 use anyhow::Error;
 use anyhow::Result;
 
-use clap::Parser as _;
-
 use tracing::Level;
 use tracing::subscriber::set_global_default as set_global_subscriber;
 use tracing_subscriber::FmtSubscriber;
@@ -30,10 +31,23 @@ use tracing_subscriber::fmt::time::ChronoLocal;
 use bpflint::LintMatch;
 use bpflint::Point;
 use bpflint::Range;
+use bpflint::SarifLog;
+use bpflint::Severity;
 use bpflint::builtin_lints;
-use bpflint::lint;
+use bpflint::call_graph_json;
+use bpflint::lint_opts;
+use bpflint::report_json;
+use bpflint::report_terminal_all;
 use bpflint::report_terminal_opts;
 
+use crate::args::Format;
+use crate::ci::GitlabReport;
+use crate::ci::report_github;
+use crate::short::report_short;
+use crate::xml::FileMatches;
+use crate::xml::write_checkstyle;
+use crate::xml::write_junit;
+
 
 fn has_bpf_c_ext(path: &Path) -> bool {
     if let Some(file_name) = path.file_name() {
@@ -49,6 +63,36 @@ fn has_bpf_c_ext(path: &Path) -> bool {
 }
 
 
+/// Print a table of the built-in lints to `writer`.
+fn print_lint_table(writer: &mut dyn io::Write) -> io::Result<()> {
+    let mut lints = builtin_lints().collect::<Vec<_>>();
+    let () = lints.sort_by(|lint1, lint2| lint1.name.cmp(&lint2.name));
+    let width = lints
+        .iter()
+        .map(|lint| lint.name.len())
+        .chain(["NAME".len()])
+        .max()
+        .unwrap_or_default();
+
+    writeln!(
+        writer,
+        "{:width$}  {:13}  {:8}  DEFAULT",
+        "NAME", "CATEGORY", "SEVERITY"
+    )?;
+    for lint in lints {
+        let default = if lint.default_enabled { "on" } else { "off" };
+        writeln!(
+            writer,
+            "{:width$}  {:13}  {:8}  {default}",
+            lint.name,
+            lint.category.name(),
+            lint.severity.name()
+        )?;
+    }
+    Ok(())
+}
+
+
 enum ExitError {
     Anyhow(Error),
     ExitCode(ExitCode),
@@ -74,15 +118,18 @@ where
 
 
 fn main_impl() -> Result<(), ExitError> {
-    let args = args::Args::parse();
+    let args = args::Args::parse_with_config()?;
     let args::Args {
         srcs,
         print_lints,
+        print_call_graph,
+        format,
         verbosity,
         ..
     } = &args;
 
     let additional_opts = args.additional_options();
+    let lint_options = args.lint_options();
 
     let level = match verbosity {
         0 => Level::WARN,
@@ -113,41 +160,115 @@ fn main_impl() -> Result<(), ExitError> {
 
     let m_ext_is_c = LintMatch {
         lint_name: "bogus-file-extension".to_string(),
+        severity: Severity::Warning,
         message: "by convention BPF C code should use the file extension '.bpf.c'".to_string(),
         range: Range {
             bytes: 0..0,
             start_point: Point { row: 0, col: 0 },
             end_point: Point { row: 0, col: 0 },
         },
+        labels: Vec::new(),
+        suppression: None,
     };
 
     if *print_lints {
-        for lint in builtin_lints() {
-            writeln!(&mut stdout, "{}", lint.name)?;
+        let () = print_lint_table(&mut stdout)?;
+        Ok(())
+    } else if *print_call_graph {
+        for src_path in srcs.iter().flatten() {
+            let code = read(src_path)
+                .with_context(|| format!("failed to read `{}`", src_path.display()))?;
+            let graph = call_graph_json(&code, src_path).with_context(|| {
+                format!("failed to build call graph of `{}`", src_path.display())
+            })?;
+            writeln!(&mut stdout, "{graph}")?;
         }
         Ok(())
     } else {
         let mut result = Ok(());
+        let mut sarif = SarifLog::new(builtin_lints());
+        let mut files = Vec::new();
+        let mut gitlab = GitlabReport::default();
         for src_path in srcs.iter().flatten() {
             let code = read(src_path)
                 .with_context(|| format!("failed to read `{}`", src_path.display()))?;
-
-            let mut first = true;
+            let mut file = FileMatches {
+                path: src_path.clone(),
+                matches: Vec::new(),
+            };
+
+            // Terminal reports are created for all matches of a file
+            // at once, so that nearby matches can be grouped.
+            let mut terminal = Vec::new();
             let match_ext = has_bpf_c_ext(src_path).not().then_some(&m_ext_is_c);
-            let matches =
-                lint(&code).with_context(|| format!("failed to lint `{}`", src_path.display()))?;
+            let matches = lint_opts(&code, &lint_options)
+                .with_context(|| format!("failed to lint `{}`", src_path.display()))?;
             for m in match_ext.into_iter().chain(matches.iter()) {
-                if !first {
-                    writeln!(&mut stdout)?;
-                } else {
-                    first = false;
+                match format {
+                    Format::Terminal => {
+                        let () = terminal.push(m.clone());
+                    },
+                    Format::Json => {
+                        let () = report_json(m, &code, src_path, &mut stdout)?;
+                    },
+                    Format::Sarif => {
+                        let () = sarif.add(m, &code, src_path);
+                    },
+                    Format::Github => {
+                        let () = report_github(m, src_path, &mut stdout)?;
+                    },
+                    Format::Gitlab => {
+                        let () = gitlab.add(m, &code, src_path);
+                    },
+                    Format::Short | Format::ShortRange => {
+                        let range = *format == Format::ShortRange;
+                        let () = report_short(m, src_path, range, &mut stdout)?;
+                    },
+                    Format::Checkstyle | Format::Junit => {
+                        let mut report = Vec::new();
+                        let () = report_terminal_opts(
+                            m,
+                            &code,
+                            src_path,
+                            &additional_opts,
+                            &mut report,
+                        )?;
+                        let report = String::from_utf8_lossy(&report).into_owned();
+                        let () = file.matches.push((m.clone(), report));
+                    },
                 }
 
-                let () = report_terminal_opts(m, &code, src_path, &additional_opts, &mut stdout)?;
-                if result.is_ok() {
+                // Suppressed matches are informational only.
+                if m.suppression.is_none() && result.is_ok() {
                     result = Err(ExitError::ExitCode(ExitCode::FAILURE));
                 }
             }
+
+            if *format == Format::Terminal {
+                let () =
+                    report_terminal_all(&terminal, &code, src_path, &additional_opts, &mut stdout)?;
+            }
+            let () = files.push(file);
+        }
+
+        match format {
+            Format::Terminal
+            | Format::Json
+            | Format::Github
+            | Format::Short
+            | Format::ShortRange => (),
+            Format::Sarif => {
+                let () = sarif.write(&mut stdout)?;
+            },
+            Format::Checkstyle => {
+                let () = write_checkstyle(&files, &mut stdout)?;
+            },
+            Format::Junit => {
+                let () = write_junit(&files, &mut stdout)?;
+            },
+            Format::Gitlab => {
+                let () = gitlab.write(&mut stdout)?;
+            },
         }
         result
     }
@@ -201,4 +322,44 @@ mod tests {
         assert!(!has_bpf_c_ext(Path::new("file.bpf.h")));
         assert!(!has_bpf_c_ext(Path::new("filebpfc")));
     }
+
+    /// Check that we print lints as a table.
+    #[test]
+    fn lint_table_printing() {
+        let mut output = Vec::new();
+        let () = print_lint_table(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+
+        let header = lines.next().unwrap();
+        assert!(header.starts_with("NAME "), "{header}");
+        assert!(
+            header.ends_with("  CATEGORY       SEVERITY  DEFAULT"),
+            "{header}"
+        );
+
+        let probe_read = lines
+            .clone()
+            .find(|line| line.starts_with("probe-read "))
+            .unwrap();
+        assert!(
+            probe_read.ends_with("  deprecated     warning   on"),
+            "{probe_read}"
+        );
+
+        let bpf_printk = lines
+            .clone()
+            .find(|line| line.starts_with("bpf-printk "))
+            .unwrap();
+        assert!(
+            bpf_printk.ends_with("  performance    warning   off"),
+            "{bpf_printk}"
+        );
+
+        let lsm_deny = lines.find(|line| line.starts_with("lsm-deny ")).unwrap();
+        assert!(
+            lsm_deny.ends_with("  security       note      off"),
+            "{lsm_deny}"
+        );
+    }
 }
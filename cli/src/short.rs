@@ -0,0 +1,130 @@
+//! Compiler style one-line report formats, as understood by editors.
+
+use std::io;
+use std::path::Path;
+
+use bpflint::LintMatch;
+
+
+/// Report a lint match on a single line, in the format used by GCC and
+/// Clang diagnostics:
+/// ```text
+/// <file>:<line>:<col>: <severity>: <message> [<lint>]
+/// ```
+///
+/// If `range` is `true`, the end position is included as well, as per
+/// the GNU coding standards:
+/// ```text
+/// <file>:<line>.<col>-<end-line>.<end-col>: <severity>: <message> [<lint>]
+/// ```
+///
+/// Line and column numbers are one-based and columns count bytes. The
+/// end column is exclusive, as it is for the GitHub and GitLab formats,
+/// so a match ending at the start of a line ends in column 1 of it.
+/// Matches not covering any code are reported without a position.
+pub fn report_short(
+    r#match: &LintMatch,
+    path: &Path,
+    range: bool,
+    writer: &mut dyn io::Write,
+) -> io::Result<()> {
+    let LintMatch {
+        lint_name,
+        severity,
+        message,
+        range: match_range,
+        ..
+    } = r#match;
+
+    let path = path.display();
+    let start = match_range.start_point;
+    let end = match_range.end_point;
+    if match_range.bytes.is_empty() {
+        write!(writer, "{path}:")?;
+    } else if range {
+        write!(
+            writer,
+            "{path}:{}.{}-{}.{}:",
+            start.row + 1,
+            start.col + 1,
+            end.row + 1,
+            end.col + 1,
+        )?;
+    } else {
+        write!(writer, "{path}:{}:{}:", start.row + 1, start.col + 1)?;
+    }
+    // Messages are not expected to span multiple lines, but make sure
+    // to keep the report on a single one regardless.
+    let message = message.replace('\n', " ");
+    writeln!(writer, " {severity}: {message} [{lint_name}]")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bpflint::Point;
+    use bpflint::Range;
+    use bpflint::Severity;
+
+
+    fn report(r#match: &LintMatch, range: bool) -> String {
+        let mut output = Vec::new();
+        let () = report_short(r#match, Path::new("test.bpf.c"), range, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+
+    /// Check that we create the expected one-line reports.
+    #[test]
+    fn short_reporting() {
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is deprecated".to_string(),
+            range: Range {
+                bytes: 17..52,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 2, col: 18 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+        assert_eq!(
+            report(&m, false),
+            "test.bpf.c:2:5: warning: bpf_probe_read() is deprecated [probe-read]\n"
+        );
+        assert_eq!(
+            report(&m, true),
+            "test.bpf.c:2.5-3.19: warning: bpf_probe_read() is deprecated [probe-read]\n"
+        );
+
+        let m = LintMatch {
+            range: Range {
+                bytes: 17..40,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 2, col: 0 },
+            },
+            ..m
+        };
+        assert_eq!(
+            report(&m, true),
+            "test.bpf.c:2.5-3.1: warning: bpf_probe_read() is deprecated [probe-read]\n"
+        );
+
+        let m = LintMatch {
+            severity: Severity::Note,
+            message: "multi\nline".to_string(),
+            range: Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            },
+            ..m
+        };
+        let expected = "test.bpf.c: note: multi line [probe-read]\n";
+        assert_eq!(report(&m, false), expected);
+        assert_eq!(report(&m, true), expected);
+    }
+}
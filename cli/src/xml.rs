@@ -0,0 +1,214 @@
+//! XML based report formats, as consumed by CI systems.
+
+use std::borrow::Cow;
+use std::io;
+use std::path::PathBuf;
+
+use bpflint::LintMatch;
+use bpflint::Severity;
+
+
+/// The lint matches found in a single source file.
+#[derive(Debug)]
+pub struct FileMatches {
+    /// The path to the file.
+    pub path: PathBuf,
+    /// The matches, each along with its terminal style report.
+    pub matches: Vec<(LintMatch, String)>,
+}
+
+
+/// Escape `s` for usage in XML attribute values and text.
+///
+/// Characters that are not allowed in XML 1.0 documents at all are
+/// replaced with U+FFFD.
+fn escape(s: &str) -> Cow<'_, str> {
+    let needs_escape = |c: char| {
+        matches!(c, '&' | '<' | '>' | '"' | '\'') || (c < ' ' && !matches!(c, '\t' | '\n' | '\r'))
+    };
+
+    if !s.contains(needs_escape) {
+        return Cow::Borrowed(s)
+    }
+
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c if needs_escape(c) => escaped.push(char::REPLACEMENT_CHARACTER),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+
+/// Write a checkstyle XML report of `files` to `writer`.
+///
+/// Line and column numbers are one-based.
+pub fn write_checkstyle(files: &[FileMatches], writer: &mut dyn io::Write) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<checkstyle version="4.3">"#)?;
+    for file in files {
+        let path = file.path.to_string_lossy();
+        writeln!(writer, r#"  <file name="{}">"#, escape(&path))?;
+        for (m, _report) in &file.matches {
+            // Checkstyle knows `error`, `warning`, and `info`.
+            let severity = match m.severity {
+                Severity::Note => "info",
+                severity => severity.name(),
+            };
+            writeln!(
+                writer,
+                r#"    <error line="{}" column="{}" severity="{severity}" message="{}" source="bpflint.{}"/>"#,
+                m.range.start_point.row + 1,
+                m.range.start_point.col + 1,
+                escape(&m.message),
+                escape(&m.lint_name),
+            )?;
+        }
+        writeln!(writer, "  </file>")?;
+    }
+    writeln!(writer, "</checkstyle>")?;
+    Ok(())
+}
+
+
+/// Write a `JUnit` XML report of `files` to `writer`.
+///
+/// Each file is represented by a test case, with each lint match
+/// constituting a failure.
+pub fn write_junit(files: &[FileMatches], writer: &mut dyn io::Write) -> io::Result<()> {
+    let tests = files.len();
+    let failures = files.iter().filter(|file| !file.matches.is_empty()).count();
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuites name="bpflint" tests="{tests}" failures="{failures}">"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <testsuite name="bpflint" tests="{tests}" failures="{failures}">"#
+    )?;
+    for file in files {
+        let path = file.path.to_string_lossy();
+        let name = escape(&path);
+        if file.matches.is_empty() {
+            writeln!(
+                writer,
+                r#"    <testcase name="{name}" classname="bpflint"/>"#
+            )?;
+            continue
+        }
+
+        writeln!(
+            writer,
+            r#"    <testcase name="{name}" classname="bpflint">"#
+        )?;
+        for (m, report) in &file.matches {
+            writeln!(
+                writer,
+                r#"      <failure message="[{}] {}" type="{}">{}</failure>"#,
+                escape(&m.lint_name),
+                escape(&m.message),
+                m.severity,
+                escape(report),
+            )?;
+        }
+        writeln!(writer, "    </testcase>")?;
+    }
+    writeln!(writer, "  </testsuite>")?;
+    writeln!(writer, "</testsuites>")?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bpflint::Point;
+    use bpflint::Range;
+
+
+    fn files() -> Vec<FileMatches> {
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is <deprecated>".to_string(),
+            range: Range {
+                bytes: 17..31,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 1, col: 18 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+
+        vec![
+            FileMatches {
+                path: PathBuf::from("a&b.bpf.c"),
+                matches: vec![(m, "report & more\n".to_string())],
+            },
+            FileMatches {
+                path: PathBuf::from("clean.bpf.c"),
+                matches: Vec::new(),
+            },
+        ]
+    }
+
+
+    /// Check that we escape special characters as expected.
+    #[test]
+    fn xml_escaping() {
+        assert!(matches!(escape("foo bar"), Cow::Borrowed("foo bar")));
+        assert_eq!(
+            escape(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;"
+        );
+        assert_eq!(escape("a\tb\nc\0d"), "a\tb\nc\u{fffd}d");
+    }
+
+    /// Check that we create the expected checkstyle report.
+    #[test]
+    fn checkstyle_reporting() {
+        let mut output = Vec::new();
+        let () = write_checkstyle(&files(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="a&amp;b.bpf.c">
+    <error line="2" column="5" severity="warning" message="bpf_probe_read() is &lt;deprecated&gt;" source="bpflint.probe-read"/>
+  </file>
+  <file name="clean.bpf.c">
+  </file>
+</checkstyle>
+"#;
+        assert_eq!(output, expected);
+    }
+
+    /// Check that we create the expected `JUnit` report.
+    #[test]
+    fn junit_reporting() {
+        let mut output = Vec::new();
+        let () = write_junit(&files(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="bpflint" tests="2" failures="1">
+  <testsuite name="bpflint" tests="2" failures="1">
+    <testcase name="a&amp;b.bpf.c" classname="bpflint">
+      <failure message="[probe-read] bpf_probe_read() is &lt;deprecated&gt;" type="warning">report &amp; more
+</failure>
+    </testcase>
+    <testcase name="clean.bpf.c" classname="bpflint"/>
+  </testsuite>
+</testsuites>
+"#;
+        assert_eq!(output, expected);
+    }
+}
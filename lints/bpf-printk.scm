@@ -0,0 +1,5 @@
+; category: performance
+; default: off
+(call_expression
+    function: (identifier) @function (#eq? @function "bpf_printk")
+)
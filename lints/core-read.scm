@@ -1,3 +1,4 @@
+; category: modernization
 (call_expression
     function: (identifier) @function (#any-of? @function "bpf_core_read" "BPF_CORE_READ")
 )
@@ -0,0 +1,3 @@
+; category: correctness
+([(primitive_type) (number_literal)] @float
+    (#match? @float "^(float|double|(([0-9]+\\.[0-9]*|\\.[0-9]+)([eE][+-]?[0-9]+)?|[0-9]+[eE][+-]?[0-9]+|0[xX]([0-9a-fA-F]+\\.?[0-9a-fA-F]*|\\.[0-9a-fA-F]+)[pP][+-]?[0-9]+)[fFlL]?)$"))
@@ -0,0 +1,2 @@
+; category: portability
+(gnu_asm_expression) @asm
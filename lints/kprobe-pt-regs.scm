@@ -0,0 +1,19 @@
+; category: modernization
+(function_definition
+    (sec_specifier
+        value: (string_literal) @__sec
+        (#match? @__sec "^\"k(ret)?probe/[^\"\\n]+\"$")
+    )
+    declarator: (function_declarator
+        parameters: (parameter_list
+            .
+            (parameter_declaration
+                type: (struct_specifier
+                    name: (type_identifier) @__type (#eq? @__type "pt_regs")
+                )
+                declarator: (pointer_declarator)
+            ) @regs
+            .
+        )
+    )
+)
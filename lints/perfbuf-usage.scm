@@ -1,3 +1,4 @@
+; category: performance
 (preproc_call_expression
     macro_name: (identifier) @__name (#eq? @__name "__uint")
     arg1: (identifier) @__arg1 (#eq? @__arg1 "type")
@@ -1,3 +1,4 @@
+; category: modernization
 (
   (preproc_call
     directive: (preproc_directive) @__directive
@@ -0,0 +1,4 @@
+; category: deprecated
+(call_expression
+    function: (identifier) @function (#eq? @function "bpf_probe_read_str")
+)
@@ -1,3 +1,4 @@
+; category: deprecated
 (call_expression
     function: (identifier) @function (#eq? @function "bpf_probe_read")
     arguments: (argument_list
@@ -0,0 +1,8 @@
+; category: modernization
+(attribute_specifier
+    (argument_list
+        (call_expression
+            function: (identifier) @__name (#any-of? @__name "section" "__section__")
+        )
+    )
+) @attribute
@@ -0,0 +1,6 @@
+; category: correctness
+(call_expression
+    function: (identifier) @function (#any-of? @function
+        "setjmp" "_setjmp" "sigsetjmp" "__builtin_setjmp"
+        "longjmp" "_longjmp" "siglongjmp" "__builtin_longjmp")
+)
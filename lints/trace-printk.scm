@@ -0,0 +1,5 @@
+; category: modernization
+(call_expression
+    function: (identifier) @function (#eq? @function "bpf_trace_printk")
+    arguments: (argument_list . (identifier))
+)
@@ -1,3 +1,5 @@
+; category: portability
+; default: off
 (function_definition
     (sec_specifier
         value: (string_literal) @probe
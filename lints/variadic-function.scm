@@ -0,0 +1,10 @@
+; category: correctness
+(function_definition
+    declarator: [
+        (function_declarator
+            parameters: (parameter_list (variadic_parameter) @variadic))
+        (pointer_declarator
+            declarator: (function_declarator
+                parameters: (parameter_list (variadic_parameter) @variadic)))
+    ]
+)
@@ -0,0 +1,357 @@
+//! Call graph construction for the functions of a translation unit.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+
+use serde_json::Value;
+use serde_json::json;
+
+use tree_sitter::Node;
+use tree_sitter::Tree;
+
+use crate::checks::call_name;
+use crate::checks::descendants;
+use crate::checks::functions;
+use crate::checks::program_name;
+use crate::checks::section;
+use crate::checks::text;
+use crate::lint::parse;
+
+
+/// Keywords, macros, and attributes requesting a function to be
+/// inlined.
+static INLINE_MARKERS: [&str; 5] = [
+    "__always_inline",
+    "__inline",
+    "__inline__",
+    "always_inline",
+    "inline",
+];
+
+
+/// The index of a function in a [`CallGraph`].
+pub(crate) type FunctionId = usize;
+
+
+/// A function defined in the translation unit.
+#[derive(Debug)]
+pub(crate) struct Function<'t> {
+    /// The function's name.
+    pub name: &'t str,
+    /// The function's definition.
+    pub definition: Node<'t>,
+    /// The node naming the function in its definition.
+    pub ident: Node<'t>,
+    /// The section of the function, if it is a BPF program.
+    pub section: Option<String>,
+    /// Whether the function is `static`.
+    pub is_static: bool,
+    /// Whether the function is (requested to be) inlined.
+    pub is_inline: bool,
+    /// Calls to other functions defined in the translation unit, in
+    /// source order.
+    pub calls: Vec<(Node<'t>, FunctionId)>,
+    /// Functions referenced other than by calling them, e.g., when
+    /// used as callbacks.
+    pub refs: Vec<FunctionId>,
+}
+
+
+/// The call graph of the functions defined in a translation unit.
+#[derive(Debug)]
+pub(crate) struct CallGraph<'t> {
+    /// The functions defined, in source order.
+    pub functions: Vec<Function<'t>>,
+    /// Functions referenced from outside of any function, e.g., in
+    /// `struct_ops` map definitions.
+    pub global_refs: Vec<FunctionId>,
+}
+
+impl<'t> CallGraph<'t> {
+    /// Construct the call graph for the code in `tree`.
+    pub fn new(tree: &'t Tree, code: &'t [u8]) -> Self {
+        let mut ids = HashMap::new();
+        let mut defs = Vec::new();
+        for function in functions(tree) {
+            let Some(ident) = program_name(function, code) else {
+                continue
+            };
+            let name = text(ident, code);
+            if ids.contains_key(name) {
+                continue
+            }
+            let _id = ids.insert(name, defs.len());
+            let () = defs.push((function, ident, name));
+        }
+
+        // Collect references to known functions inside `node`,
+        // distinguishing between calls and other references.
+        let refs = |node: Node<'t>| {
+            let mut calls = Vec::new();
+            let mut refs = Vec::new();
+            for child in descendants(node) {
+                if let Some(id) = call_name(child, code).and_then(|name| ids.get(name)) {
+                    let () = calls.push((child, *id));
+                } else if child.kind() == "identifier" {
+                    let is_callee = child.parent().is_some_and(|parent| {
+                        parent.kind() == "call_expression"
+                            && parent.child_by_field_name("function") == Some(child)
+                    });
+                    if let Some(id) = ids.get(text(child, code)).filter(|_| !is_callee) {
+                        let () = refs.push(*id);
+                    }
+                }
+            }
+            (calls, refs)
+        };
+
+        let functions = defs
+            .iter()
+            .map(|(function, ident, name)| {
+                let (calls, refs) = function
+                    .child_by_field_name("body")
+                    .map(refs)
+                    .unwrap_or_default();
+                let mut cursor = function.walk();
+                let is_static = function.children(&mut cursor).any(|child| {
+                    child.kind() == "storage_class_specifier" && text(child, code) == "static"
+                });
+                // `inline` is a storage class specifier, but macros such
+                // as `__always_inline` are not necessarily understood by
+                // the parser. So look for any marker token preceding the
+                // declarator, including in attributes.
+                let mut cursor = function.walk();
+                let is_inline = function
+                    .children(&mut cursor)
+                    .take_while(|child| child.end_byte() <= ident.start_byte())
+                    .flat_map(descendants)
+                    .filter(|node| node.child_count() == 0)
+                    .any(|node| INLINE_MARKERS.contains(&text(node, code)));
+
+                Function {
+                    name,
+                    definition: *function,
+                    ident: *ident,
+                    section: section(*function, code),
+                    is_static,
+                    is_inline,
+                    calls,
+                    refs,
+                }
+            })
+            .collect();
+
+        let mut global_refs = Vec::new();
+        let mut cursor = tree.root_node().walk();
+        for child in tree.root_node().named_children(&mut cursor) {
+            if child.kind() != "function_definition" {
+                let (calls, refs) = refs(child);
+                let () = global_refs.extend(calls.into_iter().map(|(_call, id)| id));
+                let () = global_refs.extend(refs);
+            }
+        }
+
+        Self {
+            functions,
+            global_refs,
+        }
+    }
+
+    /// Determine which functions are reachable from BPF programs or
+    /// global references, through calls and other references.
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.functions.len()];
+        let mut work = self
+            .functions
+            .iter()
+            .enumerate()
+            .filter(|(_id, function)| function.section.is_some())
+            .map(|(id, _function)| id)
+            .chain(self.global_refs.iter().copied())
+            .collect::<Vec<_>>();
+
+        while let Some(id) = work.pop() {
+            if reachable[id] {
+                continue
+            }
+            reachable[id] = true;
+
+            let function = &self.functions[id];
+            let () = work.extend(function.calls.iter().map(|(_call, callee)| *callee));
+            let () = work.extend(function.refs.iter().copied());
+        }
+        reachable
+    }
+
+    /// Find a path of calls from function `from` to function `to`, if
+    /// any.
+    pub fn path(&self, from: FunctionId, to: FunctionId) -> Option<Vec<FunctionId>> {
+        let mut preds = vec![None; self.functions.len()];
+        let mut work = vec![from];
+        while let Some(id) = work.pop() {
+            for (_call, callee) in &self.functions[id].calls {
+                if preds[*callee].is_some() {
+                    continue
+                }
+                preds[*callee] = Some(id);
+                if *callee == to {
+                    let mut path = vec![to];
+                    let mut cur = to;
+                    loop {
+                        // SANITY: Every function discovered has a
+                        //         predecessor recorded.
+                        cur = preds[cur].unwrap();
+                        let () = path.push(cur);
+                        if cur == from {
+                            break
+                        }
+                    }
+                    let () = path.reverse();
+                    return Some(path)
+                }
+                let () = work.push(*callee);
+            }
+        }
+        None
+    }
+
+    /// Convert the call graph into a JSON representation.
+    pub fn to_json(&self) -> Value {
+        let functions = self
+            .functions
+            .iter()
+            .map(|function| {
+                let calls = function
+                    .calls
+                    .iter()
+                    .map(|(call, callee)| {
+                        json!({
+                            "callee": self.functions[*callee].name,
+                            "row": call.start_position().row,
+                            "col": call.start_position().column,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let refs = function
+                    .refs
+                    .iter()
+                    .map(|id| self.functions[*id].name)
+                    .collect::<Vec<_>>();
+
+                json!({
+                    "name": function.name,
+                    "row": function.ident.start_position().row,
+                    "col": function.ident.start_position().column,
+                    "section": function.section,
+                    "static": function.is_static,
+                    "inline": function.is_inline,
+                    "calls": calls,
+                    "references": refs,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({ "functions": functions })
+    }
+}
+
+
+/// Construct the call graph of the functions defined in `code` and
+/// render it as JSON.
+///
+/// The result is a single line JSON object with a `file` member
+/// containing `path` and a `functions` member listing every function
+/// defined, in source order. Each function is described by its `name`,
+/// `row`, `col`, `section` (or `null` if it is not a BPF program),
+/// `static`, and `inline` attributes, along with the `calls` it makes
+/// to other functions of the file (each with `callee`, `row`, and
+/// `col`) and other `references` to them, e.g., as callbacks. Rows and
+/// columns are zero-based.
+///
+/// - `code` is the source code in question, for example as read from a
+///   file
+/// - `path` should be the path to the file to which `code` corresponds
+pub fn call_graph_json(code: &[u8], path: &Path) -> Result<String> {
+    let tree = parse(code)?;
+    let graph = CallGraph::new(&tree, code);
+    let mut json = graph.to_json();
+    json["file"] = Value::from(path.to_string_lossy());
+    Ok(json.to_string())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+
+    use tree_sitter::Parser;
+    use tree_sitter_bpf_c::LANGUAGE;
+
+
+    fn parse(code: &str) -> Tree {
+        let mut parser = Parser::new();
+        let () = parser.set_language(&LANGUAGE.into()).unwrap();
+        parser.parse(code, None).unwrap()
+    }
+
+
+    /// Check that we construct the expected call graph.
+    #[test]
+    fn basic() {
+        let code = indoc! { r#"
+            static int leaf(int x) { return x; }
+            static int callback(u32 idx, void *ctx) { return leaf(idx); }
+            static int unused(void) { return leaf(0); }
+            int middle(void) { return leaf(1) + leaf(2); }
+            int entry(void *ctx) {
+                bpf_loop(4, callback, NULL, 0);
+                return middle();
+            }
+        "# };
+        let tree = parse(code);
+        let graph = CallGraph::new(&tree, code.as_bytes());
+
+        let names = graph
+            .functions
+            .iter()
+            .map(|function| function.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["leaf", "callback", "unused", "middle", "entry"]);
+        assert_eq!(graph.functions[3].calls.len(), 2);
+        assert_eq!(graph.functions[4].refs, [1]);
+        assert_eq!(graph.path(4, 0), Some(vec![4, 3, 0]));
+        assert_eq!(graph.path(0, 4), None);
+
+        let json = graph.to_json();
+        assert_eq!(json["functions"][4]["calls"][0]["callee"], "middle");
+        assert_eq!(json["functions"][4]["references"][0], "callback");
+        assert_eq!(json["functions"][0]["static"], true);
+    }
+
+
+    /// Check that we detect inline functions based on the tokens of
+    /// their definition.
+    #[test]
+    fn inline() {
+        let code = indoc! { r#"
+            static __always_inline int a(void) { return 0; }
+            static inline int b(void) { return 0; }
+            static __attribute__((always_inline)) int c(void) { return 0; }
+            static __noinline int d(void) { return 0; }
+            struct inline_data *e(void) { return 0; }
+        "# };
+        let tree = parse(code);
+        let graph = CallGraph::new(&tree, code.as_bytes());
+
+        let inline = graph
+            .functions
+            .iter()
+            .map(|function| function.is_inline)
+            .collect::<Vec<_>>();
+        assert_eq!(inline, [true, true, true, false, false]);
+    }
+}
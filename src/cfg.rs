@@ -0,0 +1,763 @@
+//! Control flow graph construction for function bodies.
+//!
+//! A [`Cfg`] partitions the body of a `function_definition` into basic
+//! blocks, i.e., straight sequences of statements and conditions that
+//! are always executed together, connected by edges describing the
+//! possible flow of control between them.
+
+use std::collections::HashMap;
+
+use tree_sitter::Node;
+
+use crate::checks::call_name;
+use crate::checks::text;
+
+
+/// Macros provided by libbpf that expand to (open coded iterator
+/// based) loops, followed by the loop body.
+static LOOP_MACROS: [&str; 3] = ["bpf_for", "bpf_for_each", "bpf_repeat"];
+
+
+/// The index of a [`Block`] inside of a [`Cfg`].
+pub(crate) type BlockId = usize;
+
+
+/// The condition under which control flows along an [`Edge`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Cond<'t> {
+    /// Control always (or at least possibly) flows along the edge.
+    Always,
+    /// Control flows along the edge if the expression evaluates to
+    /// true.
+    True(Node<'t>),
+    /// Control flows along the edge if the expression evaluates to
+    /// false.
+    False(Node<'t>),
+}
+
+
+/// A directed edge between two blocks.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Edge<'t> {
+    /// The block control flows to.
+    pub to: BlockId,
+    /// The condition under which it does so.
+    pub cond: Cond<'t>,
+}
+
+
+/// A basic block.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Block<'t> {
+    /// The nodes executed as part of the block, in order.
+    ///
+    /// Nodes are either simple statements (e.g., an
+    /// `expression_statement`, a `declaration`, or a
+    /// `return_statement`) or expressions of conditions, in which case
+    /// they are the last node in the block and the block's outgoing
+    /// edges are conditional on them.
+    pub nodes: Vec<Node<'t>>,
+    /// Edges to successor blocks.
+    pub succs: Vec<Edge<'t>>,
+    /// Predecessor blocks.
+    pub preds: Vec<BlockId>,
+    /// The jump statement (`return`, `break`, `continue`, or `goto`)
+    /// that syntactically precedes the block, if the block was started
+    /// because of it.
+    pub follows: Option<Node<'t>>,
+}
+
+
+/// The control flow graph of a function.
+#[derive(Clone, Debug)]
+pub(crate) struct Cfg<'t> {
+    /// All blocks of the graph.
+    pub blocks: Vec<Block<'t>>,
+    /// The block at which execution of the function starts.
+    pub entry: BlockId,
+    /// The (always empty) block representing the function's exit.
+    pub exit: BlockId,
+}
+
+impl<'t> Cfg<'t> {
+    /// Build the control flow graph of a `function_definition`.
+    pub fn new(function: Node<'t>, code: &'t [u8]) -> Self {
+        let entry = 0;
+        let exit = 1;
+        let mut builder = Builder {
+            code,
+            blocks: vec![Block::default(), Block::default()],
+            exit,
+            labels: HashMap::new(),
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        };
+
+        if let Some(body) = function.child_by_field_name("body") {
+            let end = builder.stmt(body, entry);
+            let () = builder.edge(end, exit, Cond::Always);
+        } else {
+            let () = builder.edge(entry, exit, Cond::Always);
+        }
+
+        Self {
+            blocks: builder.blocks,
+            entry,
+            exit,
+        }
+    }
+
+    /// Determine which blocks are reachable from the entry block.
+    pub fn reachable(&self) -> Vec<bool> {
+        self.reachable_from(self.entry)
+    }
+
+    /// Determine which blocks are reachable from the provided block.
+    pub fn reachable_from(&self, block: BlockId) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = vec![block];
+        while let Some(block) = stack.pop() {
+            if reachable[block] {
+                continue
+            }
+            reachable[block] = true;
+            let () = stack.extend(self.blocks[block].succs.iter().map(|edge| edge.to));
+        }
+        reachable
+    }
+
+    /// Solve a forward data flow problem, starting with state `init`
+    /// at the entry block.
+    ///
+    /// `transfer` computes the state at the end of a block from the
+    /// one at its start, `refine` adjusts it for a specific outgoing
+    /// edge (e.g., to take into account a condition), and `join`
+    /// merges the states flowing into a block along different edges.
+    ///
+    /// Returns the state at the start of each block, or `None` for
+    /// blocks that are unreachable. `join` has to be monotone for the
+    /// computation to terminate.
+    pub fn forward<S, T, R, J>(
+        &self,
+        init: S,
+        mut transfer: T,
+        mut refine: R,
+        mut join: J,
+    ) -> Vec<Option<S>>
+    where
+        S: Clone + PartialEq,
+        T: FnMut(&Block<'t>, &S) -> S,
+        R: FnMut(&Edge<'t>, &S) -> S,
+        J: FnMut(&S, &S) -> S,
+    {
+        let mut states = vec![None; self.blocks.len()];
+        states[self.entry] = Some(init);
+        let mut work = vec![self.entry];
+
+        while let Some(id) = work.pop() {
+            // SANITY: Only blocks with a state are ever added to the
+            //         work list.
+            let state = transfer(&self.blocks[id], states[id].as_ref().unwrap());
+            for edge in &self.blocks[id].succs {
+                let state = refine(edge, &state);
+                let new = match &states[edge.to] {
+                    Some(old) => join(old, &state),
+                    None => state,
+                };
+                if states[edge.to].as_ref() != Some(&new) {
+                    states[edge.to] = Some(new);
+                    let () = work.push(edge.to);
+                }
+            }
+        }
+        states
+    }
+}
+
+
+/// A helper for building a [`Cfg`].
+struct Builder<'t> {
+    code: &'t [u8],
+    blocks: Vec<Block<'t>>,
+    /// The block representing the function's exit, which `return`
+    /// transfers control to.
+    exit: BlockId,
+    /// Blocks starting at a label, by label name.
+    labels: HashMap<&'t str, BlockId>,
+    /// The stack of blocks to continue at on `break`.
+    breaks: Vec<BlockId>,
+    /// The stack of blocks to continue at on `continue`.
+    continues: Vec<BlockId>,
+}
+
+impl<'t> Builder<'t> {
+    fn block(&mut self) -> BlockId {
+        let () = self.blocks.push(Block::default());
+        self.blocks.len() - 1
+    }
+
+    fn edge(&mut self, from: BlockId, to: BlockId, cond: Cond<'t>) {
+        let () = self.blocks[from].succs.push(Edge { to, cond });
+        let () = self.blocks[to].preds.push(from);
+    }
+
+    /// Transfer control from `from` to `to` unconditionally and start a
+    /// new (unreachable, unless jumped to) block after `jump`.
+    fn jump(&mut self, jump: Node<'t>, from: BlockId, to: BlockId) -> BlockId {
+        let () = self.edge(from, to, Cond::Always);
+        let next = self.block();
+        self.blocks[next].follows = Some(jump);
+        next
+    }
+
+    fn label(&mut self, name: &'t str) -> BlockId {
+        if let Some(block) = self.labels.get(name) {
+            *block
+        } else {
+            let block = self.block();
+            let _prev = self.labels.insert(name, block);
+            block
+        }
+    }
+
+    /// Add the condition `expr` to `cur`, transferring control to `t`
+    /// if it is true and to `f` otherwise.
+    ///
+    /// Short circuiting logical operators are decomposed into separate
+    /// blocks.
+    fn cond(&mut self, expr: Node<'t>, cur: BlockId, t: BlockId, f: BlockId) {
+        let op = expr
+            .child_by_field_name("operator")
+            .map(|op| op.kind())
+            .unwrap_or_default();
+        match (expr.kind(), op) {
+            ("parenthesized_expression", _) if expr.named_child_count() == 1 => {
+                // SANITY: We just checked that there is a child.
+                self.cond(expr.named_child(0).unwrap(), cur, t, f)
+            },
+            ("unary_expression", "!") => match expr.child_by_field_name("argument") {
+                Some(arg) => self.cond(arg, cur, f, t),
+                None => self.cond_leaf(expr, cur, t, f),
+            },
+            ("binary_expression", "&&" | "||") => {
+                match (
+                    expr.child_by_field_name("left"),
+                    expr.child_by_field_name("right"),
+                ) {
+                    (Some(left), Some(right)) => {
+                        let mid = self.block();
+                        if op == "&&" {
+                            let () = self.cond(left, cur, mid, f);
+                        } else {
+                            let () = self.cond(left, cur, t, mid);
+                        }
+                        self.cond(right, mid, t, f)
+                    },
+                    _ => self.cond_leaf(expr, cur, t, f),
+                }
+            },
+            _ => self.cond_leaf(expr, cur, t, f),
+        }
+    }
+
+    fn cond_leaf(&mut self, expr: Node<'t>, cur: BlockId, t: BlockId, f: BlockId) {
+        let () = self.blocks[cur].nodes.push(expr);
+        let () = self.edge(cur, t, Cond::True(expr));
+        let () = self.edge(cur, f, Cond::False(expr));
+    }
+
+    /// Process a sequence of statements, starting in block `cur` and
+    /// returning the block in which control continues.
+    fn stmts<I>(&mut self, stmts: I, mut cur: BlockId) -> BlockId
+    where
+        I: IntoIterator<Item = Node<'t>>,
+    {
+        let mut stmts = stmts.into_iter().peekable();
+        while let Some(stmt) = stmts.next() {
+            let is_loop_macro = stmt.kind() == "expression_statement"
+                && stmt
+                    .named_child(0)
+                    .and_then(|call| call_name(call, self.code))
+                    .is_some_and(|name| LOOP_MACROS.contains(&name));
+
+            if is_loop_macro {
+                if let Some(body) = stmts.next_if(|next| next.kind() == "compound_statement") {
+                    cur = self.loop_macro(stmt, body, cur);
+                    continue
+                }
+            }
+            cur = self.stmt(stmt, cur);
+        }
+        cur
+    }
+
+    /// Process a loop created by one of the [`LOOP_MACROS`].
+    fn loop_macro(&mut self, header: Node<'t>, body: Node<'t>, cur: BlockId) -> BlockId {
+        let head = self.block();
+        let after = self.block();
+        let () = self.edge(cur, head, Cond::Always);
+        let () = self.blocks[head].nodes.push(header);
+        let () = self.edge(head, after, Cond::Always);
+
+        let start = self.block();
+        let () = self.edge(head, start, Cond::Always);
+        let end = self.loop_body(body, start, after, head);
+        let () = self.edge(end, head, Cond::Always);
+        after
+    }
+
+    fn loop_body(&mut self, body: Node<'t>, cur: BlockId, brk: BlockId, cont: BlockId) -> BlockId {
+        let () = self.breaks.push(brk);
+        let () = self.continues.push(cont);
+        let end = self.stmt(body, cur);
+        let _brk = self.breaks.pop();
+        let _cont = self.continues.pop();
+        end
+    }
+
+    /// Process a single statement, starting in block `cur` and
+    /// returning the block in which control continues.
+    fn stmt(&mut self, node: Node<'t>, cur: BlockId) -> BlockId {
+        match node.kind() {
+            "compound_statement" => self.stmts(children(node), cur),
+            "if_statement" => {
+                let after = self.block();
+                let then = self.block();
+                let els = if node.child_by_field_name("alternative").is_some() {
+                    self.block()
+                } else {
+                    after
+                };
+
+                match node.child_by_field_name("condition") {
+                    Some(cond) => self.cond(cond, cur, then, els),
+                    None => {
+                        let () = self.edge(cur, then, Cond::Always);
+                        let () = self.edge(cur, els, Cond::Always);
+                    },
+                }
+
+                if let Some(consequence) = node.child_by_field_name("consequence") {
+                    let end = self.stmt(consequence, then);
+                    let () = self.edge(end, after, Cond::Always);
+                } else {
+                    let () = self.edge(then, after, Cond::Always);
+                }
+
+                if let Some(alternative) = node.child_by_field_name("alternative") {
+                    // Newer grammars wrap the statement in an
+                    // `else_clause`.
+                    let end = if alternative.kind() == "else_clause" {
+                        self.stmts(children(alternative), els)
+                    } else {
+                        self.stmt(alternative, els)
+                    };
+                    let () = self.edge(end, after, Cond::Always);
+                }
+                after
+            },
+            "while_statement" => {
+                let head = self.block();
+                let start = self.block();
+                let after = self.block();
+                let () = self.edge(cur, head, Cond::Always);
+                match node.child_by_field_name("condition") {
+                    Some(cond) => self.cond(cond, head, start, after),
+                    None => self.edge(head, start, Cond::Always),
+                }
+                if let Some(body) = node.child_by_field_name("body") {
+                    let end = self.loop_body(body, start, after, head);
+                    let () = self.edge(end, head, Cond::Always);
+                } else {
+                    let () = self.edge(start, head, Cond::Always);
+                }
+                after
+            },
+            "do_statement" => {
+                let start = self.block();
+                let head = self.block();
+                let after = self.block();
+                let () = self.edge(cur, start, Cond::Always);
+                let end = match node.child_by_field_name("body") {
+                    Some(body) => self.loop_body(body, start, after, head),
+                    None => start,
+                };
+                let () = self.edge(end, head, Cond::Always);
+                match node.child_by_field_name("condition") {
+                    Some(cond) => self.cond(cond, head, start, after),
+                    None => self.edge(head, start, Cond::Always),
+                }
+                after
+            },
+            "for_statement" => {
+                let mut cur = cur;
+                if let Some(init) = node.child_by_field_name("initializer") {
+                    let () = self.blocks[cur].nodes.push(init);
+                } else if let Some(init) = node.child_by_field_name("declaration") {
+                    let () = self.blocks[cur].nodes.push(init);
+                }
+
+                let head = self.block();
+                let start = self.block();
+                let update = self.block();
+                let after = self.block();
+                let () = self.edge(cur, head, Cond::Always);
+                match node.child_by_field_name("condition") {
+                    Some(cond) => self.cond(cond, head, start, after),
+                    // A missing condition means the loop is "infinite".
+                    None => self.edge(head, start, Cond::Always),
+                }
+
+                cur = match node.child_by_field_name("body") {
+                    Some(body) => self.loop_body(body, start, after, update),
+                    None => start,
+                };
+                let () = self.edge(cur, update, Cond::Always);
+                if let Some(expr) = node.child_by_field_name("update") {
+                    let () = self.blocks[update].nodes.push(expr);
+                }
+                let () = self.edge(update, head, Cond::Always);
+                after
+            },
+            "switch_statement" => {
+                let after = self.block();
+                if let Some(cond) = node.child_by_field_name("condition") {
+                    let () = self.blocks[cur].nodes.push(cond);
+                }
+
+                let () = self.breaks.push(after);
+                // Statements before the first case label are never
+                // executed.
+                let mut fallthrough = self.block();
+                let mut has_default = false;
+                let cases = node
+                    .child_by_field_name("body")
+                    .map(children)
+                    .unwrap_or_default();
+                for case in cases {
+                    if case.kind() == "case_statement" {
+                        let start = self.block();
+                        let () = self.edge(cur, start, Cond::Always);
+                        let () = self.edge(fallthrough, start, Cond::Always);
+                        has_default |= case.child_by_field_name("value").is_none();
+                        fallthrough = self.stmts(children(case), start);
+                    } else {
+                        fallthrough = self.stmt(case, fallthrough);
+                    }
+                }
+                let _brk = self.breaks.pop();
+
+                let () = self.edge(fallthrough, after, Cond::Always);
+                if !has_default {
+                    let () = self.edge(cur, after, Cond::Always);
+                }
+                after
+            },
+            "case_statement" => {
+                // A case label outside of the immediate body of a
+                // `switch`; that's legal C, but we can't model it
+                // properly. Just treat its statements as sequential.
+                self.stmts(children(node), cur)
+            },
+            "labeled_statement" => {
+                let label = node
+                    .child_by_field_name("label")
+                    .map(|label| text(label, self.code))
+                    .unwrap_or_default();
+                let start = self.label(label);
+                let () = self.edge(cur, start, Cond::Always);
+                self.stmts(children(node), start)
+            },
+            "return_statement" => {
+                let () = self.blocks[cur].nodes.push(node);
+                self.jump(node, cur, self.exit)
+            },
+            "break_statement" => match self.breaks.last() {
+                Some(target) => self.jump(node, cur, *target),
+                None => cur,
+            },
+            "continue_statement" => match self.continues.last() {
+                Some(target) => self.jump(node, cur, *target),
+                None => cur,
+            },
+            "goto_statement" => {
+                let label = node
+                    .child_by_field_name("label")
+                    .map(|label| text(label, self.code))
+                    .unwrap_or_default();
+                let target = self.label(label);
+                self.jump(node, cur, target)
+            },
+            "preproc_if" | "preproc_ifdef" | "preproc_elif" | "preproc_elifdef" => {
+                // We don't know which branch is active, so model all of
+                // them as possible.
+                let after = self.block();
+                let start = self.block();
+                let () = self.edge(cur, start, Cond::Always);
+                let end = self.stmts(children(node), start);
+                let () = self.edge(end, after, Cond::Always);
+
+                let alt = self.block();
+                let () = self.edge(cur, alt, Cond::Always);
+                let end = match node.child_by_field_name("alternative") {
+                    Some(alternative) => self.stmt(alternative, alt),
+                    None => alt,
+                };
+                let () = self.edge(end, after, Cond::Always);
+                after
+            },
+            "preproc_else" => self.stmts(children(node), cur),
+            "comment" => cur,
+            _ => {
+                let () = self.blocks[cur].nodes.push(node);
+                cur
+            },
+        }
+    }
+}
+
+
+/// Retrieve the named children of `node` not associated with a field,
+/// e.g., the statements of a compound statement or of a `case`.
+fn children(node: Node<'_>) -> Vec<Node<'_>> {
+    let mut cursor = node.walk();
+    let mut children = Vec::new();
+    if cursor.goto_first_child() {
+        loop {
+            if cursor.node().is_named() && cursor.field_name().is_none() {
+                let () = children.push(cursor.node());
+            }
+            if !cursor.goto_next_sibling() {
+                break
+            }
+        }
+    }
+    children
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+
+    use tree_sitter::Parser;
+    use tree_sitter::Tree;
+    use tree_sitter_bpf_c::LANGUAGE;
+
+    use crate::checks::descendants;
+
+
+    fn parse(code: &str) -> Tree {
+        let mut parser = Parser::new();
+        let () = parser.set_language(&LANGUAGE.into()).unwrap();
+        parser.parse(code, None).unwrap()
+    }
+
+    fn function(tree: &Tree) -> Node<'_> {
+        descendants(tree.root_node())
+            .find(|node| node.kind() == "function_definition")
+            .unwrap()
+    }
+
+    /// Find the block containing the call to function `name`.
+    fn block_of_call(cfg: &Cfg<'_>, code: &str, name: &str) -> BlockId {
+        cfg.blocks
+            .iter()
+            .position(|block| {
+                block.nodes.iter().any(|node| {
+                    descendants(*node).any(|node| call_name(node, code.as_bytes()) == Some(name))
+                })
+            })
+            .unwrap()
+    }
+
+
+    /// Check that conditions are split into separate blocks and that
+    /// both branches join afterwards.
+    #[test]
+    fn if_else() {
+        let code = indoc! { r#"
+            int test_fn(int a) {
+                if (a && b())
+                    c();
+                else
+                    d();
+                e();
+                return 0;
+            }
+        "# };
+        let tree = parse(code);
+        let cfg = Cfg::new(function(&tree), code.as_bytes());
+
+        let b = block_of_call(&cfg, code, "b");
+        let c = block_of_call(&cfg, code, "c");
+        let d = block_of_call(&cfg, code, "d");
+        let e = block_of_call(&cfg, code, "e");
+        assert_eq!(cfg.blocks[b].nodes.len(), 1);
+        assert!(cfg.blocks[cfg.entry].succs.iter().any(|edge| edge.to == b));
+        assert!(cfg.blocks[cfg.entry].succs.iter().any(|edge| edge.to == d));
+        assert!(cfg.blocks[b].succs.iter().any(|edge| edge.to == c));
+        assert!(cfg.blocks[b].succs.iter().any(|edge| edge.to == d));
+
+        let reachable = cfg.reachable_from(c);
+        assert!(reachable[e]);
+        assert!(!reachable[d]);
+        assert!(cfg.reachable_from(d)[e]);
+        assert!(cfg.reachable()[cfg.exit]);
+    }
+
+    /// Check that returns end control flow.
+    #[test]
+    fn early_return() {
+        let code = indoc! { r#"
+            int test_fn(int *a) {
+                if (!a)
+                    return 0;
+                b();
+                return 1;
+                c();
+            }
+        "# };
+        let tree = parse(code);
+        let cfg = Cfg::new(function(&tree), code.as_bytes());
+
+        let b = block_of_call(&cfg, code, "b");
+        let c = block_of_call(&cfg, code, "c");
+        let reachable = cfg.reachable();
+        assert!(reachable[b]);
+        assert!(!reachable[c]);
+        assert_eq!(
+            cfg.blocks[c].follows.map(|node| node.kind()),
+            Some("return_statement")
+        );
+
+        // The `false` edge of `!a` is the `true` edge of `a`.
+        let entry = &cfg.blocks[cfg.entry];
+        let edge = entry.succs.iter().find(|edge| edge.to == b).unwrap();
+        assert!(matches!(edge.cond, Cond::True(node) if node.kind() == "identifier"));
+        let edge = entry.succs.iter().find(|edge| edge.to != b).unwrap();
+        assert!(matches!(edge.cond, Cond::False(node) if node.kind() == "identifier"));
+    }
+
+    /// Check that loops, `break`, and `continue` are handled.
+    #[test]
+    fn loops() {
+        let code = indoc! { r#"
+            int test_fn(int a) {
+                while (a) {
+                    if (b())
+                        break;
+                    if (c())
+                        continue;
+                    d();
+                }
+                bpf_for(i, 0, 10) {
+                    e();
+                }
+                f();
+            }
+        "# };
+        let tree = parse(code);
+        let cfg = Cfg::new(function(&tree), code.as_bytes());
+
+        let b = block_of_call(&cfg, code, "b");
+        let d = block_of_call(&cfg, code, "d");
+        let e = block_of_call(&cfg, code, "e");
+        let f = block_of_call(&cfg, code, "f");
+        assert!(cfg.reachable_from(d)[b]);
+        assert!(cfg.reachable_from(d)[e]);
+        assert!(cfg.reachable_from(e)[e]);
+        assert!(cfg.reachable_from(b)[f]);
+        assert!(cfg.reachable()[e]);
+    }
+
+    /// Check that `switch` statements and `goto` are handled.
+    #[test]
+    fn switch_goto() {
+        let code = indoc! { r#"
+            int test_fn(int a) {
+                switch (a) {
+                case 1:
+                    b();
+                case 2:
+                    c();
+                    break;
+                default:
+                    goto out;
+                }
+                d();
+            out:
+                e();
+            }
+        "# };
+        let tree = parse(code);
+        let cfg = Cfg::new(function(&tree), code.as_bytes());
+
+        let b = block_of_call(&cfg, code, "b");
+        let c = block_of_call(&cfg, code, "c");
+        let d = block_of_call(&cfg, code, "d");
+        let e = block_of_call(&cfg, code, "e");
+        assert!(cfg.reachable_from(b)[c]);
+        assert!(!cfg.reachable_from(c)[b]);
+        assert!(cfg.reachable_from(c)[d]);
+        assert!(cfg.reachable_from(d)[e]);
+        assert!(cfg.reachable()[d]);
+
+        let entry = &cfg.blocks[cfg.entry];
+        assert_eq!(entry.succs.len(), 3);
+        assert!(entry.succs.iter().all(|edge| edge.to != d));
+    }
+
+    /// Check that forward data flow analysis propagates state along
+    /// all paths.
+    #[test]
+    fn forward() {
+        let code = indoc! { r#"
+            int test_fn(int x) {
+                if (x)
+                    a();
+                else if (x > 1)
+                    a();
+                b();
+                if (x)
+                    a();
+                else
+                    return 0;
+                c();
+                while (x--)
+                    d();
+                e();
+                return 0;
+                f();
+            }
+        "# };
+        let tree = parse(code);
+        let cfg = Cfg::new(function(&tree), code.as_bytes());
+
+        // Determine whether `a()` has been called on all paths.
+        let called = |block: &Block<'_>| {
+            block.nodes.iter().any(|node| {
+                descendants(*node).any(|node| call_name(node, code.as_bytes()) == Some("a"))
+            })
+        };
+        let states = cfg.forward(
+            false,
+            |block, state| *state || called(block),
+            |_edge, state| *state,
+            |a, b| *a && *b,
+        );
+
+        let b = block_of_call(&cfg, code, "b");
+        let c = block_of_call(&cfg, code, "c");
+        let d = block_of_call(&cfg, code, "d");
+        let e = block_of_call(&cfg, code, "e");
+        let f = block_of_call(&cfg, code, "f");
+        assert_eq!(states[b], Some(false));
+        assert_eq!(states[c], Some(true));
+        assert_eq!(states[d], Some(true));
+        assert_eq!(states[e], Some(true));
+        assert_eq!(states[f], None);
+    }
+}
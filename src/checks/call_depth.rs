@@ -0,0 +1,117 @@
+//! The `call-depth` lint.
+//!
+//! The kernel limits the depth of BPF-to-BPF calls: a program and the
+//! functions it calls may occupy at most eight stack frames at any
+//! time. Functions that get inlined don't count towards this limit.
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::callgraph::CallGraph;
+use crate::callgraph::FunctionId;
+
+use super::Context;
+use super::label;
+
+
+pub(crate) const NAME: &str = "call-depth";
+pub(crate) const MESSAGE: &str = "call chain exceeds the kernel limit of 8 stack frames";
+
+/// The maximum number of stack frames (`MAX_CALL_FRAMES` in the
+/// kernel).
+const MAX_FRAMES: usize = 8;
+
+
+/// The deepest call chain starting at a function.
+#[derive(Clone, Copy, Debug)]
+struct Chain<'t> {
+    /// The number of frames in the chain, including the function's
+    /// own.
+    frames: usize,
+    /// The first call of the chain, if any.
+    next: Option<(Node<'t>, FunctionId)>,
+}
+
+
+/// Determine the deepest call chain starting at function `id`.
+///
+/// Recursive calls are ignored; they are reported separately.
+fn deepest<'t>(
+    graph: &CallGraph<'t>,
+    id: FunctionId,
+    chains: &mut Vec<Option<Chain<'t>>>,
+    active: &mut Vec<bool>,
+) -> Chain<'t> {
+    if let Some(chain) = chains[id] {
+        return chain
+    }
+
+    active[id] = true;
+    let function = &graph.functions[id];
+    let mut chain = Chain {
+        frames: 0,
+        next: None,
+    };
+    for (call, callee) in &function.calls {
+        if active[*callee] {
+            continue
+        }
+        let callee_chain = deepest(graph, *callee, chains, active);
+        if callee_chain.frames > chain.frames {
+            chain = Chain {
+                frames: callee_chain.frames,
+                next: Some((*call, *callee)),
+            };
+        }
+    }
+    active[id] = false;
+
+    if !(function.is_static && function.is_inline) {
+        chain.frames += 1;
+    }
+    chains[id] = Some(chain);
+    chain
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    let graph = CallGraph::new(cx.tree, cx.code);
+    let mut chains = vec![None; graph.functions.len()];
+    let mut active = vec![false; graph.functions.len()];
+
+    for (id, function) in graph.functions.iter().enumerate() {
+        if function.section.is_none() {
+            continue
+        }
+
+        let chain = deepest(&graph, id, &mut chains, &mut active);
+        if chain.frames <= MAX_FRAMES {
+            continue
+        }
+
+        let message = format!(
+            "call chain of program `{}` is {} stack frames deep, exceeding the kernel limit of {MAX_FRAMES}",
+            function.name, chain.frames
+        );
+        let mut m = cx.match_msg(function.ident.range(), message);
+
+        let mut frame = 1;
+        let mut next = chain.next;
+        while let Some((call, id)) = next {
+            let callee = &graph.functions[id];
+            let message = if callee.is_static && callee.is_inline {
+                format!("`{}` is called (and inlined) here", callee.name)
+            } else {
+                frame += 1;
+                format!("`{}` is called here (frame {frame})", callee.name)
+            };
+            let () = m.labels.push(label(call, message));
+            next = chains[id].and_then(|chain| chain.next);
+        }
+        let () = matches.push(m);
+    }
+    Ok(matches)
+}
@@ -0,0 +1,134 @@
+//! The `gpl-only-helper` lint.
+//!
+//! BPF programs that are not licensed under a GPL-compatible license
+//! are rejected by the kernel if they call GPL-only helpers.
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::Point;
+use crate::Range;
+
+use super::Context;
+use super::Variables;
+use super::descendants;
+use super::label;
+use super::programs;
+use super::string_value;
+
+
+pub(crate) const NAME: &str = "gpl-only-helper";
+pub(crate) const MESSAGE: &str = "GPL-only helpers can only be used by programs with a GPL-compatible license; loading the program will fail";
+const MISSING_MESSAGE: &str = r#"no license defined; programs without a GPL-compatible license in a SEC("license") section can't use GPL-only helpers"#;
+
+
+/// License strings the kernel considers GPL-compatible.
+// See `license_is_gpl_compatible` in include/linux/license.h.
+static GPL_COMPATIBLE: [&str; 6] = [
+    "GPL",
+    "GPL v2",
+    "GPL and additional rights",
+    "Dual BSD/GPL",
+    "Dual MIT/GPL",
+    "Dual MPL/GPL",
+];
+
+/// Helpers that are only available to GPL-compatible programs (or
+/// macros expanding to them).
+static GPL_ONLY_HELPERS: [&str; 34] = [
+    "BPF_CORE_READ",
+    "bpf_core_read",
+    "bpf_core_read_str",
+    "bpf_get_branch_snapshot",
+    "bpf_get_current_task",
+    "bpf_get_current_task_btf",
+    "bpf_get_func_arg",
+    "bpf_get_func_arg_cnt",
+    "bpf_get_func_ip",
+    "bpf_get_func_ret",
+    "bpf_get_stack",
+    "bpf_get_stackid",
+    "bpf_get_task_stack",
+    "bpf_override_return",
+    "bpf_perf_event_output",
+    "bpf_perf_event_read",
+    "bpf_perf_event_read_value",
+    "bpf_printk",
+    "bpf_probe_read",
+    "bpf_probe_read_kernel",
+    "bpf_probe_read_kernel_str",
+    "bpf_probe_read_str",
+    "bpf_probe_read_user",
+    "bpf_probe_read_user_str",
+    "bpf_probe_write_user",
+    "bpf_read_branch_records",
+    "bpf_seq_printf",
+    "bpf_seq_printf_btf",
+    "bpf_seq_write",
+    "bpf_snprintf",
+    "bpf_task_pt_regs",
+    "bpf_timer_init",
+    "bpf_trace_printk",
+    "bpf_trace_vprintk",
+];
+
+
+/// Find the license definition, i.e., the variable placed in the
+/// `license` section, returning the node of its value (or of the
+/// variable itself, if it has no string value) alongside the license
+/// string.
+fn find_license<'t>(variables: &'t Variables, code: &[u8]) -> Option<(Node<'t>, String)> {
+    let var = variables
+        .iter()
+        .find(|var| var.section.is_some_and(|(section, _)| section == "license"))?;
+    let license = var
+        .value
+        .and_then(|value| Some((value, string_value(value, code)?)));
+    Some(license.unwrap_or((var.decl, String::new())))
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let root = cx.tree.root_node();
+    let mut matches = Vec::new();
+
+    let variables = Variables::new(cx.tree, cx.code)?;
+    let Some((license_node, license)) = find_license(&variables, cx.code) else {
+        // Files not containing any programs may very well be linked
+        // with others providing the license.
+        if programs(cx.tree).next().is_some() {
+            let range = Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            };
+            let () = matches.push(cx.match_msg(range, MISSING_MESSAGE.to_string()));
+        }
+        return Ok(matches)
+    };
+
+    if GPL_COMPATIBLE.contains(&license.as_str()) {
+        return Ok(matches)
+    }
+
+    for node in descendants(root) {
+        if node.kind() != "call_expression" {
+            continue
+        }
+
+        let Some(function) = node.child_by_field_name("function") else {
+            continue
+        };
+        if function.kind() == "identifier" && GPL_ONLY_HELPERS.contains(&cx.text(function)) {
+            let mut m = cx.r#match(function.range());
+            let () = m.labels.push(label(
+                license_node,
+                format!("license `{license}` is not GPL-compatible"),
+            ));
+            let () = matches.push(m);
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,59 @@
+//! The `indirect-call` lint.
+//!
+//! BPF has no indirect call instruction: the target of every call has
+//! to be known when the program is loaded, so that the verifier can
+//! follow it. Calls through function pointers, e.g., stored in local
+//! variables or struct members, can't be compiled to BPF.
+
+use anyhow::Result;
+
+use crate::LintMatch;
+
+use super::Context;
+use super::descendants;
+use super::functions;
+use super::locals;
+use super::strip;
+
+
+pub(crate) const NAME: &str = "indirect-call";
+pub(crate) const MESSAGE: &str = "calls through function pointers are not supported by BPF, as call targets have to be known at load time";
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    for function in functions(cx.tree) {
+        let Some(body) = function.child_by_field_name("body") else {
+            continue
+        };
+        let locals = locals(function, cx.code);
+
+        for call in descendants(body).filter(|node| node.kind() == "call_expression") {
+            let Some(callee) = call.child_by_field_name("function") else {
+                continue
+            };
+            let target = strip(callee);
+            let is_indirect = match target.kind() {
+                // Functions and (legacy) helper definitions are global;
+                // locals are necessarily function pointers.
+                "identifier" => locals.contains(cx.text(target)),
+                "field_expression"
+                | "pointer_expression"
+                | "subscript_expression"
+                | "call_expression"
+                | "conditional_expression" => true,
+                _ => false,
+            };
+            if !is_indirect {
+                continue
+            }
+
+            let message = format!(
+                "call through function pointer `{}`; BPF has no indirect calls and the verifier requires every call target to be known at load time; call the function directly, dispatch with a `switch`, or use `bpf_tail_call()` with a program array map",
+                cx.text(callee)
+            );
+            let () = matches.push(cx.match_msg(callee.range(), message));
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,368 @@
+//! The `invalid-return-value` lint.
+//!
+//! The meaning of a program's return value depends on its type, which
+//! in turn is determined by its `SEC()` annotation. Returning a value
+//! meant for a different program type (e.g., `XDP_PASS` from a TC
+//! program) or one outside of the accepted range compiles just fine,
+//! but is either rejected by the verifier or silently wrong.
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+
+use super::Context;
+use super::descendants;
+use super::programs;
+use super::section;
+use super::strip;
+use super::text;
+
+
+pub(crate) const NAME: &str = "invalid-return-value";
+pub(crate) const MESSAGE: &str = "return value is not valid for the program's type";
+
+
+/// Well-known return value constants.
+static CONSTANTS: [&str; 21] = [
+    "SK_DROP",
+    "SK_PASS",
+    "TCX_DROP",
+    "TCX_NEXT",
+    "TCX_PASS",
+    "TCX_REDIRECT",
+    "TC_ACT_OK",
+    "TC_ACT_PIPE",
+    "TC_ACT_QUEUED",
+    "TC_ACT_RECLASSIFY",
+    "TC_ACT_REDIRECT",
+    "TC_ACT_REPEAT",
+    "TC_ACT_SHOT",
+    "TC_ACT_STOLEN",
+    "TC_ACT_TRAP",
+    "TC_ACT_UNSPEC",
+    "XDP_ABORTED",
+    "XDP_DROP",
+    "XDP_PASS",
+    "XDP_REDIRECT",
+    "XDP_TX",
+];
+
+
+/// Error numbers, as used (negated) in return values. They are all
+/// positive.
+// See include/uapi/asm-generic/errno-base.h, errno.h, and
+// include/linux/errno.h.
+static ERRNOS: [&str; 93] = [
+    "E2BIG",
+    "EACCES",
+    "EADDRINUSE",
+    "EADDRNOTAVAIL",
+    "EAFNOSUPPORT",
+    "EAGAIN",
+    "EALREADY",
+    "EBADF",
+    "EBADMSG",
+    "EBUSY",
+    "ECANCELED",
+    "ECHILD",
+    "ECONNABORTED",
+    "ECONNREFUSED",
+    "ECONNRESET",
+    "EDEADLK",
+    "EDESTADDRREQ",
+    "EDOM",
+    "EDQUOT",
+    "EEXIST",
+    "EFAULT",
+    "EFBIG",
+    "EHOSTDOWN",
+    "EHOSTUNREACH",
+    "EHWPOISON",
+    "EIDRM",
+    "EILSEQ",
+    "EINPROGRESS",
+    "EINTR",
+    "EINVAL",
+    "EIO",
+    "EISCONN",
+    "EISDIR",
+    "EKEYEXPIRED",
+    "EKEYREJECTED",
+    "EKEYREVOKED",
+    "ELOOP",
+    "EMFILE",
+    "EMLINK",
+    "EMSGSIZE",
+    "ENAMETOOLONG",
+    "ENETDOWN",
+    "ENETRESET",
+    "ENETUNREACH",
+    "ENFILE",
+    "ENOBUFS",
+    "ENODATA",
+    "ENODEV",
+    "ENOENT",
+    "ENOEXEC",
+    "ENOKEY",
+    "ENOLCK",
+    "ENOLINK",
+    "ENOMEM",
+    "ENOMSG",
+    "ENOPROTOOPT",
+    "ENOSPC",
+    "ENOSR",
+    "ENOSTR",
+    "ENOSYS",
+    "ENOTBLK",
+    "ENOTCONN",
+    "ENOTDIR",
+    "ENOTEMPTY",
+    "ENOTRECOVERABLE",
+    "ENOTSOCK",
+    "ENOTSUPP",
+    "ENOTTY",
+    "ENXIO",
+    "EOPNOTSUPP",
+    "EOVERFLOW",
+    "EOWNERDEAD",
+    "EPERM",
+    "EPFNOSUPPORT",
+    "EPIPE",
+    "EPROTO",
+    "EPROTONOSUPPORT",
+    "EPROTOTYPE",
+    "ERANGE",
+    "ERFKILL",
+    "EROFS",
+    "ESHUTDOWN",
+    "ESOCKTNOSUPPORT",
+    "ESPIPE",
+    "ESRCH",
+    "ESTALE",
+    "ETIME",
+    "ETIMEDOUT",
+    "ETOOMANYREFS",
+    "ETXTBSY",
+    "EUSERS",
+    "EWOULDBLOCK",
+    "EXDEV",
+];
+
+
+/// The return value constraints of a program type.
+#[derive(Debug)]
+struct ProgType {
+    /// A human readable name of the program type.
+    name: &'static str,
+    /// Prefixes of the constants meant for this program type.
+    families: &'static [&'static str],
+    /// The range of valid numeric return values.
+    range: (i64, i64),
+    /// A description of the expected return values.
+    expected: &'static str,
+}
+
+static XDP: ProgType = ProgType {
+    name: "XDP",
+    families: &["XDP_"],
+    range: (0, 4),
+    expected: "one of `XDP_ABORTED`, `XDP_DROP`, `XDP_PASS`, `XDP_TX`, or `XDP_REDIRECT`",
+};
+static TC: ProgType = ProgType {
+    name: "TC",
+    families: &["TC_ACT_"],
+    range: (-1, 8),
+    expected: "a `TC_ACT_*` value, such as `TC_ACT_OK` or `TC_ACT_SHOT`",
+};
+static TCX: ProgType = ProgType {
+    name: "tcx",
+    families: &["TCX_", "TC_ACT_"],
+    range: (-1, 8),
+    expected: "a `TCX_*` value, such as `TCX_NEXT`, `TCX_PASS`, or `TCX_DROP`",
+};
+static CGROUP_SKB_INGRESS: ProgType = ProgType {
+    name: "cgroup_skb ingress",
+    families: &[],
+    range: (0, 1),
+    expected: "`0` (drop) or `1` (allow)",
+};
+static CGROUP_SKB_EGRESS: ProgType = ProgType {
+    name: "cgroup_skb egress",
+    families: &[],
+    range: (0, 3),
+    expected: "`0` (drop) or `1` (allow), optionally with bit 1 set for congestion notification",
+};
+static CGROUP: ProgType = ProgType {
+    name: "cgroup",
+    families: &[],
+    range: (0, 1),
+    expected: "`0` (reject) or `1` (allow)",
+};
+static LSM_CGROUP: ProgType = ProgType {
+    name: "cgroup LSM",
+    families: &[],
+    range: (0, 1),
+    expected: "`0` (deny) or `1` (allow)",
+};
+static LSM: ProgType = ProgType {
+    name: "LSM",
+    families: &[],
+    range: (i64::MIN, 0),
+    expected: "`0` or a negative error code",
+};
+static SOCKET_FILTER: ProgType = ProgType {
+    name: "socket filter",
+    families: &[],
+    range: (0, i64::MAX),
+    expected: "the non-negative number of bytes to keep, with `0` dropping the packet",
+};
+static SK_SKB: ProgType = ProgType {
+    name: "sk_skb",
+    families: &["SK_"],
+    range: (0, 1),
+    expected: "`SK_PASS` or `SK_DROP`",
+};
+static STRUCT_OPS_INIT: ProgType = ProgType {
+    name: "struct_ops initialization",
+    families: &[],
+    range: (i64::MIN, 0),
+    expected: "`0` or a negative error code",
+};
+
+
+/// Determine the program type for a section name.
+fn prog_type(section: &str) -> Option<&'static ProgType> {
+    let (kind, rest) = section.split_once('/').unwrap_or((section, ""));
+    let ty = match kind {
+        "xdp" | "xdp.frags" => &XDP,
+        "tc" | "classifier" => &TC,
+        "tcx" => &TCX,
+        "cgroup_skb" if rest == "egress" => &CGROUP_SKB_EGRESS,
+        "cgroup_skb" => &CGROUP_SKB_INGRESS,
+        "cgroup" => &CGROUP,
+        "lsm_cgroup" => &LSM_CGROUP,
+        "lsm" | "lsm.s" => &LSM,
+        "socket" => &SOCKET_FILTER,
+        "sk_skb" => &SK_SKB,
+        // struct_ops callbacks have differing return types, but
+        // initialization callbacks (e.g., `init` or `init_task` for
+        // `sched_ext_ops`) report errors as negative error codes.
+        "struct_ops" | "struct_ops.s"
+            if ["init", "init_task", "prep_move"]
+                .iter()
+                .any(|suffix| rest.ends_with(suffix)) =>
+        {
+            &STRUCT_OPS_INIT
+        },
+        _ => return None,
+    };
+    Some(ty)
+}
+
+
+/// The statically known value of a returned expression.
+enum Value<'c> {
+    /// A well-known constant.
+    Constant(&'c str),
+    /// A number.
+    Number(i64),
+}
+
+/// Evaluate an expression, if it is a constant.
+fn eval<'c>(node: Node<'_>, code: &'c [u8]) -> Option<Value<'c>> {
+    let node = strip(node);
+    match node.kind() {
+        "number_literal" => {
+            let text = text(node, code).trim_end_matches(['u', 'U', 'l', 'L']);
+            let value =
+                if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                    i64::from_str_radix(hex, 16).ok()?
+                } else {
+                    text.parse().ok()?
+                };
+            Some(Value::Number(value))
+        },
+        "identifier" => {
+            let name = text(node, code);
+            if CONSTANTS.contains(&name) {
+                Some(Value::Constant(name))
+            } else if ERRNOS.contains(&name) {
+                // The exact value doesn't matter, they are all
+                // positive.
+                Some(Value::Number(1))
+            } else {
+                None
+            }
+        },
+        "unary_expression" => {
+            let op = node.child_by_field_name("operator")?;
+            if op.kind() != "-" {
+                return None
+            }
+            match eval(node.child_by_field_name("argument")?, code)? {
+                Value::Number(value) => Some(Value::Number(-value)),
+                Value::Constant(_name) => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+
+/// Collect the expressions that may end up being returned by `expr`,
+/// looking through conditional expressions.
+fn returned<'t>(expr: Node<'t>, exprs: &mut Vec<Node<'t>>) {
+    let expr = strip(expr);
+    if expr.kind() == "conditional_expression" {
+        if let Some(consequence) = expr.child_by_field_name("consequence") {
+            let () = returned(consequence, exprs);
+        }
+        if let Some(alternative) = expr.child_by_field_name("alternative") {
+            let () = returned(alternative, exprs);
+        }
+    } else {
+        let () = exprs.push(expr);
+    }
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+
+    for program in programs(cx.tree) {
+        let section = section(program, cx.code).unwrap_or_default();
+        let Some(ty) = prog_type(&section) else {
+            continue
+        };
+
+        let mut exprs = Vec::new();
+        for ret in descendants(program).filter(|node| node.kind() == "return_statement") {
+            if let Some(expr) = ret.named_child(0) {
+                let () = returned(expr, &mut exprs);
+            }
+        }
+
+        for expr in exprs {
+            let valid = match eval(expr, cx.code) {
+                Some(Value::Constant(name)) => {
+                    ty.families.iter().any(|family| name.starts_with(family))
+                },
+                Some(Value::Number(value)) => (ty.range.0..=ty.range.1).contains(&value),
+                None => continue,
+            };
+
+            if !valid {
+                let message = format!(
+                    "`{}` is not a valid return value for {} programs; expected {}",
+                    cx.text(expr),
+                    ty.name,
+                    ty.expected
+                );
+                let () = matches.push(cx.match_msg(expr.range(), message));
+            }
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,123 @@
+//! The `legacy-map-definition` lint.
+//!
+//! Maps used to be defined as variables of type `struct bpf_map_def`
+//! placed in the `maps` section. libbpf 1.0 dropped support for these
+//! in favor of BTF-defined maps in the `.maps` section, which carry
+//! key and value type information.
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::Range;
+
+use super::Context;
+use super::Variables;
+use super::text;
+
+
+pub(crate) const NAME: &str = "legacy-map-definition";
+pub(crate) const MESSAGE: &str = "legacy map definitions in SEC(\"maps\") are no longer supported by libbpf 1.0; use a BTF-defined map in SEC(\".maps\") instead";
+
+
+/// The members of `struct bpf_map_def`, in declaration order.
+static FIELDS: [&str; 5] = ["type", "key_size", "value_size", "max_entries", "map_flags"];
+
+
+/// Determine the type whose size is taken by `value`, if it is a
+/// `sizeof` expression.
+fn sizeof_type<'c>(value: Node<'_>, code: &'c [u8]) -> Option<&'c str> {
+    if value.kind() != "sizeof_expression" {
+        return None
+    }
+    if let Some(ty) = value.child_by_field_name("type") {
+        return Some(text(ty, code))
+    }
+    // `sizeof(u32)` is indistinguishable from taking the size of a
+    // parenthesized expression.
+    let value = value.child_by_field_name("value")?;
+    (value.kind() == "parenthesized_expression")
+        .then(|| value.named_child(0))
+        .flatten()
+        .map(|inner| text(inner, code))
+}
+
+/// Convert the member `field` of a `struct bpf_map_def` initializer to
+/// its BTF-defined map counterpart.
+fn member(field: &str, value: Node<'_>, code: &[u8]) -> String {
+    let ty = match field {
+        "key_size" => sizeof_type(value, code).map(|ty| ("key", ty)),
+        "value_size" => sizeof_type(value, code).map(|ty| ("value", ty)),
+        _ => None,
+    };
+    match ty {
+        Some((name, ty)) => format!("__type({name}, {ty});"),
+        None => format!("__uint({field}, {});", text(value, code)),
+    }
+}
+
+/// Create the BTF-defined map equivalent to the legacy map definition
+/// `name` with initializer `init`.
+fn replacement(name: &str, init: Node<'_>, code: &[u8]) -> Option<String> {
+    if init.kind() != "initializer_list" {
+        return None
+    }
+
+    let mut members = Vec::new();
+    let mut cursor = init.walk();
+    for (idx, child) in init.named_children(&mut cursor).enumerate() {
+        let (field, value) = if child.kind() == "initializer_pair" {
+            let designator = child.child_by_field_name("designator")?;
+            let field = designator.named_child(0)?;
+            (text(field, code), child.child_by_field_name("value")?)
+        } else {
+            (*FIELDS.get(idx)?, child)
+        };
+        let () = members.push(member(field, value, code));
+    }
+    Some(format!(
+        "struct {{ {} }} {name} SEC(\".maps\");",
+        members.join(" ")
+    ))
+}
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    let variables = Variables::new(cx.tree, cx.code)?;
+    for var in variables.iter() {
+        let ty = var
+            .decl
+            .child_by_field_name("type")
+            .filter(|ty| ty.kind() == "struct_specifier")
+            .filter(|ty| {
+                ty.child_by_field_name("name")
+                    .is_some_and(|name| cx.text(name) == "bpf_map_def")
+            });
+        let sec = var
+            .section
+            .filter(|(section, _)| *section == "maps")
+            .map(|(_, range)| range.clone());
+        let Some(range) = ty.map(|ty| Range::from(ty.range())).or(sec.clone()) else {
+            continue
+        };
+
+        let name = cx.text(var.name);
+        let what = match (ty, sec) {
+            (Some(_), Some(_)) => "using `struct bpf_map_def` in SEC(\"maps\")",
+            (Some(_), None) => "using `struct bpf_map_def`",
+            (None, _) => "in SEC(\"maps\")",
+        };
+        let fix = var.value.and_then(|init| replacement(name, init, cx.code));
+        let message = match fix {
+            Some(fix) => format!(
+                "legacy map definition `{name}` {what} is no longer supported by libbpf 1.0; replace it with a BTF-defined map: `{fix}`"
+            ),
+            None => format!(
+                "legacy map definition `{name}` {what} is no longer supported by libbpf 1.0; replace it with a BTF-defined map in SEC(\".maps\"), using `__uint()` and `__type()` to describe it"
+            ),
+        };
+        let () = matches.push(cx.match_msg(range, message));
+    }
+    Ok(matches)
+}
@@ -0,0 +1,60 @@
+//! The `lsm-deny` lint.
+//!
+//! LSM programs returning anything other than zero deny the operation
+//! they are attached to, system wide. That makes them a powerful
+//! enforcement mechanism, but also a means of disrupting the system,
+//! and every place they may do so warrants a security review.
+//! Programs attached to cgroups (`lsm_cgroup`) use inverted semantics:
+//! one allows the operation and zero denies it.
+
+use anyhow::Result;
+
+use crate::LintMatch;
+
+use super::Context;
+use super::descendants;
+use super::program_name;
+use super::programs;
+use super::section;
+use super::strip;
+
+
+pub(crate) const NAME: &str = "lsm-deny";
+pub(crate) const MESSAGE: &str = "LSM program may deny the operation it is attached to";
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+
+    for program in programs(cx.tree) {
+        let Some(section) = section(program, cx.code) else {
+            continue
+        };
+        let (kind, _hook) = section.split_once('/').unwrap_or((&section, ""));
+        let allow = match kind {
+            "lsm" | "lsm.s" => "0",
+            "lsm_cgroup" => "1",
+            _ => continue,
+        };
+        let Some(name) = program_name(program, cx.code) else {
+            continue
+        };
+
+        for ret in descendants(program).filter(|node| node.kind() == "return_statement") {
+            let Some(value) = ret.named_child(0) else {
+                continue
+            };
+            if cx.text(strip(value)) == allow {
+                continue
+            }
+
+            let message = format!(
+                "LSM program `{}` attached to `{section}` may deny the operation by returning `{}`",
+                cx.text(name),
+                cx.text(value)
+            );
+            let () = matches.push(cx.match_msg(value.range(), message));
+        }
+    }
+    Ok(matches)
+}
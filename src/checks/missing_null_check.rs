@@ -0,0 +1,178 @@
+//! The `missing-null-check` lint.
+//!
+//! Helpers such as `bpf_map_lookup_elem` return a pointer that may be
+//! NULL. The verifier rejects programs dereferencing such a pointer
+//! without checking it first (`invalid mem access
+//! 'map_value_or_null'`). We follow pointers returned by these helpers
+//! through assignments and report dereferences reachable without a
+//! preceding NULL check.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::cfg::Cfg;
+
+use super::Context;
+use super::PointerFlow;
+use super::call_name;
+use super::edge_null_check;
+use super::functions;
+use super::label;
+use super::strip;
+use super::text;
+use super::track_pointers;
+
+
+pub(crate) const NAME: &str = "missing-null-check";
+pub(crate) const MESSAGE: &str = "pointer that may be NULL is dereferenced without a prior NULL check; the verifier will reject the program";
+
+/// Helpers returning a pointer that may be NULL.
+static NULLABLE_HELPERS: [&str; 18] = [
+    "bpf_cgrp_storage_get",
+    "bpf_dynptr_data",
+    "bpf_get_listener_sock",
+    "bpf_inode_storage_get",
+    "bpf_map_lookup_elem",
+    "bpf_map_lookup_percpu_elem",
+    "bpf_per_cpu_ptr",
+    "bpf_ringbuf_reserve",
+    "bpf_sk_fullsock",
+    "bpf_sk_lookup_tcp",
+    "bpf_sk_lookup_udp",
+    "bpf_sk_storage_get",
+    "bpf_skc_lookup_tcp",
+    "bpf_skc_to_tcp6_sock",
+    "bpf_skc_to_tcp_sock",
+    "bpf_skc_to_udp6_sock",
+    "bpf_task_storage_get",
+    "bpf_tcp_sock",
+];
+
+
+/// Variables that may be NULL, mapped to the helper call that produced
+/// their value.
+type State<'t> = BTreeMap<&'t str, Node<'t>>;
+
+
+/// A dereference of a pointer that may be NULL.
+struct Deref<'t> {
+    /// The dereferencing expression.
+    expr: Node<'t>,
+    /// The name of the dereferenced variable.
+    var: &'t str,
+    /// The helper call that produced the pointer.
+    origin: Node<'t>,
+}
+
+
+/// Tracks the flow of possibly NULL pointers, recording dereferences of
+/// them.
+struct Flow<'a, 't> {
+    code: &'t [u8],
+    state: &'a mut State<'t>,
+    derefs: &'a mut Vec<Deref<'t>>,
+}
+
+impl<'t> PointerFlow<'t> for Flow<'_, 't> {
+    fn assign(&mut self, var: &'t str, value: Node<'t>) {
+        let value = strip(value);
+        let origin =
+            if call_name(value, self.code).is_some_and(|name| NULLABLE_HELPERS.contains(&name)) {
+                Some(value)
+            } else if value.kind() == "identifier" {
+                self.state.get(text(value, self.code)).copied()
+            } else {
+                None
+            };
+
+        if let Some(origin) = origin {
+            let _prev = self.state.insert(var, origin);
+        } else {
+            let _prev = self.state.remove(var);
+        }
+    }
+
+    fn modify(&mut self, var: &'t str) {
+        let _prev = self.state.remove(var);
+    }
+
+    fn deref(&mut self, expr: Node<'t>, var: &'t str) {
+        // Report each pointer only once; the verifier stops at the
+        // first access anyway.
+        if let Some(origin) = self.state.remove(var) {
+            let () = self.derefs.push(Deref { expr, var, origin });
+        }
+    }
+}
+
+
+/// Compute the state after executing `nodes`.
+fn transfer<'t>(
+    nodes: &[Node<'t>],
+    code: &'t [u8],
+    state: &State<'t>,
+    derefs: &mut Vec<Deref<'t>>,
+) -> State<'t> {
+    let mut state = state.clone();
+    let mut flow = Flow {
+        code,
+        state: &mut state,
+        derefs,
+    };
+    for node in nodes {
+        let () = track_pointers(*node, code, &mut flow);
+    }
+    state
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+
+    for function in functions(cx.tree) {
+        let cfg = Cfg::new(function, cx.code);
+        let states = cfg.forward(
+            State::new(),
+            |block, state| transfer(&block.nodes, cx.code, state, &mut Vec::new()),
+            |edge, state| {
+                let mut state = state.clone();
+                if let Some((var, true)) = edge_null_check(edge, cx.code) {
+                    let _prev = state.remove(var);
+                }
+                state
+            },
+            |a, b| {
+                let mut state = a.clone();
+                for (var, origin) in b {
+                    let _origin = state.entry(*var).or_insert(*origin);
+                }
+                state
+            },
+        );
+
+        let mut derefs = Vec::new();
+        for (block, state) in cfg.blocks.iter().zip(&states) {
+            if let Some(state) = state {
+                let _state = transfer(&block.nodes, cx.code, state, &mut derefs);
+            }
+        }
+
+        for Deref { expr, var, origin } in derefs {
+            let helper = call_name(origin, cx.code).unwrap_or_default();
+            let message = format!(
+                "`{var}` may be NULL, as returned by `{helper}`, and must be checked before being dereferenced"
+            );
+            let mut m = cx.match_msg(expr.range(), message);
+            let () = m.labels.push(label(
+                origin,
+                format!("`{var}` may be NULL after this call"),
+            ));
+            let () = matches.push(m);
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,723 @@
+//! Lints implemented natively, in Rust, and helpers for writing them.
+
+mod call_depth;
+mod gpl_only_helper;
+mod indirect_call;
+mod invalid_return_value;
+mod legacy_map_definition;
+mod lsm_deny;
+mod missing_null_check;
+mod network_byte_order;
+mod non_constant_builtin_size;
+mod recursion;
+mod security_sensitive_helper;
+mod stack_usage;
+mod struct_padding;
+mod unbalanced_lock;
+mod unchecked_packet_access;
+mod unpaired_ringbuf_reserve;
+mod unreachable_code;
+mod unreachable_function;
+mod variable_length_array;
+
+use std::collections::HashSet;
+use std::str;
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+use tree_sitter::Tree;
+use tree_sitter::TreeCursor;
+
+use crate::Category;
+use crate::Lint;
+use crate::LintMatch;
+use crate::Point;
+use crate::Range;
+use crate::cfg::Cond;
+use crate::cfg::Edge;
+use crate::lint::Label;
+use crate::lint::LintOpts;
+use crate::lint::parse;
+use crate::types::declarator_name;
+
+
+/// The signature of a native check.
+pub(crate) type Check = fn(&Context<'_>) -> Result<Vec<LintMatch>>;
+
+/// The list of natively implemented lints, as `(name, check, message,
+/// category)` tuples.
+pub(crate) static CHECKS: [(&str, Check, &str, Category); 19] = [
+    (
+        call_depth::NAME,
+        call_depth::check,
+        call_depth::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        gpl_only_helper::NAME,
+        gpl_only_helper::check,
+        gpl_only_helper::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        indirect_call::NAME,
+        indirect_call::check,
+        indirect_call::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        invalid_return_value::NAME,
+        invalid_return_value::check,
+        invalid_return_value::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        legacy_map_definition::NAME,
+        legacy_map_definition::check,
+        legacy_map_definition::MESSAGE,
+        Category::Deprecated,
+    ),
+    (
+        lsm_deny::NAME,
+        lsm_deny::check,
+        lsm_deny::MESSAGE,
+        Category::Security,
+    ),
+    (
+        missing_null_check::NAME,
+        missing_null_check::check,
+        missing_null_check::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        network_byte_order::NAME,
+        network_byte_order::check,
+        network_byte_order::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        non_constant_builtin_size::NAME,
+        non_constant_builtin_size::check,
+        non_constant_builtin_size::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        recursion::NAME,
+        recursion::check,
+        recursion::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        security_sensitive_helper::NAME,
+        security_sensitive_helper::check,
+        security_sensitive_helper::MESSAGE,
+        Category::Security,
+    ),
+    (
+        stack_usage::NAME,
+        stack_usage::check,
+        stack_usage::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        struct_padding::NAME,
+        struct_padding::check,
+        struct_padding::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        unbalanced_lock::NAME,
+        unbalanced_lock::check,
+        unbalanced_lock::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        unchecked_packet_access::NAME,
+        unchecked_packet_access::check,
+        unchecked_packet_access::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        unpaired_ringbuf_reserve::NAME,
+        unpaired_ringbuf_reserve::check,
+        unpaired_ringbuf_reserve::MESSAGE,
+        Category::Correctness,
+    ),
+    (
+        unreachable_code::NAME,
+        unreachable_code::check,
+        unreachable_code::MESSAGE,
+        Category::Style,
+    ),
+    (
+        unreachable_function::NAME,
+        unreachable_function::check,
+        unreachable_function::MESSAGE,
+        Category::Style,
+    ),
+    (
+        variable_length_array::NAME,
+        variable_length_array::check,
+        variable_length_array::MESSAGE,
+        Category::Correctness,
+    ),
+];
+
+
+/// The context a native check operates in.
+#[derive(Debug)]
+pub(crate) struct Context<'a> {
+    /// The syntax tree of the code being linted.
+    pub tree: &'a Tree,
+    /// The source code being linted.
+    pub code: &'a [u8],
+    /// The lint on whose behalf the check runs.
+    pub lint: &'a Lint,
+    /// The options the lint is configured with.
+    pub opts: &'a LintOpts,
+}
+
+impl<'a> Context<'a> {
+    /// Retrieve the source code covered by `node`.
+    pub fn text(&self, node: Node<'_>) -> &'a str {
+        text(node, self.code)
+    }
+
+    /// Create a [`LintMatch`] for the given range, using the lint's
+    /// default message.
+    pub fn r#match<R>(&self, range: R) -> LintMatch
+    where
+        R: Into<Range>,
+    {
+        self.match_msg(range, self.lint.message.clone())
+    }
+
+    /// Create a [`LintMatch`] for the given range, reporting `message`.
+    pub fn match_msg<R>(&self, range: R, message: String) -> LintMatch
+    where
+        R: Into<Range>,
+    {
+        LintMatch {
+            lint_name: self.lint.name.clone(),
+            severity: self.lint.severity,
+            message,
+            range: range.into(),
+            labels: Vec::new(),
+            suppression: None,
+        }
+    }
+}
+
+
+/// Create a [`Label`] for the range covered by `node`.
+pub(crate) fn label(node: Node<'_>, message: impl Into<String>) -> Label {
+    Label {
+        range: Range::from(node.range()),
+        message: message.into(),
+    }
+}
+
+/// Retrieve the source code covered by `node`.
+///
+/// Code that is not valid UTF-8 is reported as empty string, as it
+/// can't be relevant to any of our checks.
+pub(crate) fn text<'c>(node: Node<'_>, code: &'c [u8]) -> &'c str {
+    str::from_utf8(&code[node.byte_range()]).unwrap_or_default()
+}
+
+/// Retrieve the value of a string literal, without quotes.
+///
+/// Concatenated strings are joined. Escape sequences are preserved
+/// verbatim.
+pub(crate) fn string_value(node: Node<'_>, code: &[u8]) -> Option<String> {
+    match node.kind() {
+        "string_literal" => {
+            let text = text(node, code);
+            let value = text.strip_prefix('"')?.strip_suffix('"')?;
+            Some(value.to_string())
+        },
+        "concatenated_string" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .map(|child| string_value(child, code))
+                .collect()
+        },
+        _ => None,
+    }
+}
+
+/// Retrieve the name of the function called by a `call_expression`,
+/// if it is called by name.
+pub(crate) fn call_name<'c>(node: Node<'_>, code: &'c [u8]) -> Option<&'c str> {
+    if node.kind() != "call_expression" {
+        return None
+    }
+    let function = node.child_by_field_name("function")?;
+    (function.kind() == "identifier").then(|| text(function, code))
+}
+
+/// Strip parentheses and casts from an expression.
+pub(crate) fn strip(mut node: Node<'_>) -> Node<'_> {
+    loop {
+        node = match node.kind() {
+            "parenthesized_expression" if node.named_child_count() == 1 => {
+                // SANITY: We just checked that there is a child.
+                node.named_child(0).unwrap()
+            },
+            "cast_expression" => match node.child_by_field_name("value") {
+                Some(value) => value,
+                None => break node,
+            },
+            _ => break node,
+        }
+    }
+}
+
+fn is_null(node: Node<'_>, code: &[u8]) -> bool {
+    let node = strip(node);
+    node.kind() == "null" || matches!(text(node, code), "NULL" | "0")
+}
+
+
+/// Determine the variable that `cond` tests for being non-NULL, if
+/// any, and whether it is non-NULL if the condition is true (as
+/// opposed to false).
+pub(crate) fn null_check<'c>(cond: Node<'_>, code: &'c [u8]) -> Option<(&'c str, bool)> {
+    let cond = strip(cond);
+    match cond.kind() {
+        "identifier" => Some((text(cond, code), true)),
+        "assignment_expression" => {
+            let op = cond.child_by_field_name("operator")?;
+            if op.kind() != "=" {
+                return None
+            }
+            null_check(cond.child_by_field_name("left")?, code)
+        },
+        "binary_expression" => {
+            let op = cond.child_by_field_name("operator")?.kind();
+            let non_null = match op {
+                "!=" => true,
+                "==" => false,
+                _ => return None,
+            };
+            let left = cond.child_by_field_name("left")?;
+            let right = cond.child_by_field_name("right")?;
+            let (var, polarity) = if is_null(right, code) {
+                null_check(left, code)?
+            } else if is_null(left, code) {
+                null_check(right, code)?
+            } else {
+                return None
+            };
+            Some((var, polarity == non_null))
+        },
+        _ => None,
+    }
+}
+
+/// Determine the variable tested for being non-NULL by the condition
+/// of `edge`, if any, and whether it is non-NULL when control flows
+/// along the edge.
+pub(crate) fn edge_null_check<'c>(edge: &Edge<'_>, code: &'c [u8]) -> Option<(&'c str, bool)> {
+    match edge.cond {
+        Cond::Always => None,
+        Cond::True(cond) => null_check(cond, code),
+        Cond::False(cond) => null_check(cond, code).map(|(var, non_null)| (var, !non_null)),
+    }
+}
+
+/// The operations on pointers held in variables that a data flow
+/// analysis built on [`track_pointers`] reacts to.
+pub(crate) trait PointerFlow<'t> {
+    /// `var` is assigned `value`, either by a plain assignment or by
+    /// its declaration.
+    fn assign(&mut self, var: &'t str, value: Node<'t>);
+
+    /// `var` is modified in any other way, e.g., by `var += 4`.
+    fn modify(&mut self, var: &'t str);
+
+    /// The pointer held by `var` is dereferenced by `expr`.
+    fn deref(&mut self, expr: Node<'t>, var: &'t str);
+
+    /// Inspect any other expression or statement before its children,
+    /// returning whether the children should be visited.
+    fn visit(&mut self, _node: Node<'t>) -> bool {
+        true
+    }
+}
+
+/// Walk an expression or statement in evaluation order, reporting
+/// assignments to and dereferences of variables to `flow`.
+pub(crate) fn track_pointers<'t, F>(node: Node<'t>, code: &'t [u8], flow: &mut F)
+where
+    F: PointerFlow<'t>,
+{
+    match node.kind() {
+        // The operand of `sizeof` is not evaluated.
+        "sizeof_expression" => return,
+        "assignment_expression" => {
+            let left = node.child_by_field_name("left");
+            let right = node.child_by_field_name("right");
+            if let Some(right) = right {
+                let () = track_pointers(right, code, flow);
+            }
+
+            match left {
+                Some(left) if left.kind() == "identifier" => {
+                    let is_plain = node
+                        .child_by_field_name("operator")
+                        .is_some_and(|op| op.kind() == "=");
+                    match right {
+                        Some(right) if is_plain => flow.assign(text(left, code), right),
+                        _ => flow.modify(text(left, code)),
+                    }
+                },
+                Some(left) => track_pointers(left, code, flow),
+                None => (),
+            }
+            return
+        },
+        "init_declarator" => {
+            let value = node.child_by_field_name("value");
+            if let Some(value) = value {
+                let () = track_pointers(value, code, flow);
+            }
+
+            let var = node
+                .child_by_field_name("declarator")
+                .and_then(declarator_name);
+            if let (Some(var), Some(value)) = (var, value) {
+                let () = flow.assign(text(var, code), value);
+            }
+            return
+        },
+        "field_expression" | "pointer_expression" | "subscript_expression" => {
+            let is_deref = match node.kind() {
+                "field_expression" | "pointer_expression" => node
+                    .child_by_field_name("operator")
+                    .is_some_and(|op| matches!(op.kind(), "->" | "*")),
+                _ => true,
+            };
+            let argument = node.child_by_field_name("argument").map(strip);
+            if let Some(argument) = argument.filter(|arg| is_deref && arg.kind() == "identifier") {
+                let () = flow.deref(node, text(argument, code));
+            }
+        },
+        _ => {
+            if !flow.visit(node) {
+                return
+            }
+        },
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let () = track_pointers(child, code, flow);
+    }
+}
+
+/// Retrieve the string literal naming the section of a `SEC()`
+/// annotated `function_definition`, i.e., a BPF program.
+pub(crate) fn sec_value(node: Node<'_>) -> Option<Node<'_>> {
+    let mut cursor = node.walk();
+    let sec = node
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "sec_specifier")?;
+    sec.child_by_field_name("value")
+}
+
+/// Retrieve the name of the section of a `SEC()` annotated
+/// `function_definition`, without quotes.
+pub(crate) fn section(node: Node<'_>, code: &[u8]) -> Option<String> {
+    sec_value(node).and_then(|value| string_value(value, code))
+}
+
+
+/// A global variable, as found by [`Variables`].
+pub(crate) struct Variable<'t> {
+    /// The `declaration` node of the variable.
+    pub decl: Node<'t>,
+    /// The identifier naming the variable.
+    pub name: Node<'t>,
+    /// The section the variable is placed in using `SEC()`, along with
+    /// the range of the specifier.
+    pub section: Option<(&'t str, &'t Range)>,
+    /// The value the variable is initialized with, if any.
+    pub value: Option<Node<'t>>,
+}
+
+/// The global variables defined in a piece of code.
+///
+/// The grammar does not support `SEC()` specifiers on variables well:
+/// depending on their placement and whether the variable has an
+/// initializer, the definition ends up as an `ERROR` node. We hence
+/// blank out all specifiers and parse the code once more. Doing so
+/// preserves the positions of everything else, meaning that nodes of
+/// the resulting tree can be used with the original code.
+pub(crate) struct Variables {
+    /// The tree of the code without `SEC()` specifiers.
+    tree: Tree,
+    /// The sections named by the blanked specifiers, along with their
+    /// ranges.
+    sections: Vec<(String, Range)>,
+}
+
+impl Variables {
+    /// Find the global variables in `code`, parsed as `tree`.
+    pub fn new(tree: &Tree, code: &[u8]) -> Result<Self> {
+        let root = tree.root_node();
+        let mut blanked = code.to_vec();
+        let mut sections = Vec::new();
+        let mut offset = 0;
+        while let Some(idx) = code[offset..].windows(4).position(|w| w == b"SEC(") {
+            let start = offset + idx;
+            offset = start + 4;
+
+            let is_ident = |c: &u8| c.is_ascii_alphanumeric() || *c == b'_';
+            if start > 0 && is_ident(&code[start - 1]) {
+                continue
+            }
+            let is_code = root
+                .descendant_for_byte_range(start, start + 3)
+                .is_some_and(|node| {
+                    !matches!(
+                        node.kind(),
+                        "comment" | "string_literal" | "string_content" | "char_literal"
+                    )
+                });
+            if !is_code {
+                continue
+            }
+            let Some((section, end)) = sec_literal(code, offset) else {
+                continue
+            };
+
+            let () = blanked[start..end]
+                .iter_mut()
+                .filter(|c| **c != b'\n')
+                .for_each(|c| *c = b' ');
+            let range = Range {
+                bytes: start..end,
+                start_point: point(code, start),
+                end_point: point(code, end),
+            };
+            let () = sections.push((section, range));
+            offset = end;
+        }
+
+        let slf = Self {
+            tree: parse(&blanked)?,
+            sections,
+        };
+        Ok(slf)
+    }
+
+    /// Iterate over all global variables.
+    pub fn iter(&self) -> impl Iterator<Item = Variable<'_>> {
+        descendants(self.tree.root_node())
+            .filter(|node| node.kind() == "declaration" && !in_function(*node))
+            .filter_map(|decl| {
+                let declarator = decl.child_by_field_name("declarator")?;
+                let name = declarator_name(declarator)?;
+                let section = self
+                    .sections
+                    .iter()
+                    .find(|(_, range)| {
+                        decl.start_byte() <= range.bytes.start && range.bytes.end <= decl.end_byte()
+                    })
+                    .map(|(section, range)| (section.as_str(), range));
+                let value = declarator
+                    .child_by_field_name("value")
+                    .filter(|_| declarator.kind() == "init_declarator");
+                Some(Variable {
+                    decl,
+                    name,
+                    section,
+                    value,
+                })
+            })
+    }
+}
+
+/// Parse the string literal and closing parenthesis of a `SEC()`
+/// specifier starting at `offset` of `code`, returning the section
+/// name and the end offset of the specifier.
+fn sec_literal(code: &[u8], offset: usize) -> Option<(String, usize)> {
+    let skip_space = |mut idx: usize| {
+        while code.get(idx).is_some_and(u8::is_ascii_whitespace) {
+            idx += 1;
+        }
+        idx
+    };
+
+    let start = skip_space(offset);
+    if code.get(start) != Some(&b'"') {
+        return None
+    }
+    let mut idx = start + 1;
+    loop {
+        match code.get(idx)? {
+            b'"' => break,
+            b'\\' => idx += 2,
+            b'\n' => return None,
+            _ => idx += 1,
+        }
+    }
+    let section = String::from_utf8_lossy(&code[start + 1..idx]).into_owned();
+    let end = skip_space(idx + 1);
+    (code.get(end) == Some(&b')')).then_some((section, end + 1))
+}
+
+/// Calculate the position of the byte at `offset` in `code`.
+fn point(code: &[u8], offset: usize) -> Point {
+    let code = &code[..offset];
+    let line_start = code
+        .iter()
+        .rposition(|&c| c == b'\n')
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    Point {
+        row: code.iter().filter(|&&c| c == b'\n').count(),
+        col: offset - line_start,
+    }
+}
+
+/// Check whether `node` is part of a function definition.
+fn in_function(node: Node<'_>) -> bool {
+    let mut parent = node.parent();
+    while let Some(node) = parent {
+        if node.kind() == "function_definition" {
+            return true
+        }
+        parent = node.parent();
+    }
+    false
+}
+
+
+/// Retrieve the identifier naming a `function_definition`.
+pub(crate) fn function_name(node: Node<'_>) -> Option<Node<'_>> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    loop {
+        match declarator.kind() {
+            "identifier" => break Some(declarator),
+            _ => declarator = declarator.child_by_field_name("declarator")?,
+        }
+    }
+}
+
+
+/// Macros from `bpf_tracing.h` and friends that wrap a program's
+/// definition, taking its actual name as first argument.
+static WRAPPERS: [&str; 10] = [
+    "BPF_KPROBE",
+    "BPF_KPROBE_SYSCALL",
+    "BPF_KRETPROBE",
+    "BPF_KSYSCALL",
+    "BPF_PROG",
+    "BPF_PROG2",
+    "BPF_STRUCT_OPS",
+    "BPF_STRUCT_OPS_SLEEPABLE",
+    "BPF_UPROBE",
+    "BPF_URETPROBE",
+];
+
+/// Retrieve the node naming a `function_definition`, looking through
+/// wrapper macros such as `BPF_PROG()`.
+pub(crate) fn program_name<'t>(node: Node<'t>, code: &[u8]) -> Option<Node<'t>> {
+    let name = function_name(node)?;
+    if !WRAPPERS.contains(&text(name, code)) {
+        return Some(name)
+    }
+
+    // The program's name is parsed as the type of the first parameter.
+    let params = name.parent()?.child_by_field_name("parameters")?;
+    let param = params.named_child(0)?;
+    match param.child_by_field_name("declarator") {
+        None => param.child_by_field_name("type"),
+        Some(_) => Some(name),
+    }
+}
+
+
+/// An iterator over a node and all its descendants, in pre-order.
+pub(crate) struct Descendants<'t> {
+    cursor: TreeCursor<'t>,
+    done: bool,
+}
+
+impl<'t> Iterator for Descendants<'t> {
+    type Item = Node<'t>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None
+        }
+
+        let node = self.cursor.node();
+        if !self.cursor.goto_first_child() {
+            while !self.cursor.goto_next_sibling() {
+                if !self.cursor.goto_parent() {
+                    self.done = true;
+                    break
+                }
+            }
+        }
+        Some(node)
+    }
+}
+
+/// Iterate over `node` and all of its descendants, in pre-order.
+pub(crate) fn descendants(node: Node<'_>) -> Descendants<'_> {
+    Descendants {
+        cursor: node.walk(),
+        done: false,
+    }
+}
+
+/// Iterate over all `function_definition` nodes in the tree.
+pub(crate) fn functions(tree: &Tree) -> impl Iterator<Item = Node<'_>> {
+    descendants(tree.root_node()).filter(|node| node.kind() == "function_definition")
+}
+
+/// Iterate over all `function_definition` nodes in the tree that
+/// represent BPF programs, i.e., that are annotated with `SEC()`.
+pub(crate) fn programs(tree: &Tree) -> impl Iterator<Item = Node<'_>> {
+    descendants(tree.root_node())
+        .filter(|node| node.kind() == "function_definition" && sec_value(*node).is_some())
+}
+
+/// Collect the names of the parameters and local variables of
+/// `function`.
+pub(crate) fn locals<'c>(function: Node<'_>, code: &'c [u8]) -> HashSet<&'c str> {
+    descendants(function)
+        .filter(|node| matches!(node.kind(), "declaration" | "parameter_declaration"))
+        .flat_map(|decl| {
+            let mut cursor = decl.walk();
+            decl.children_by_field_name("declarator", &mut cursor)
+                .filter_map(declarator_name)
+                .map(|name| text(name, code))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Check whether expression `expr` is a compile-time constant, i.e.,
+/// neither calls functions nor refers to any of the `locals` (other
+/// than inside of `sizeof` and friends).
+///
+/// Identifiers not referring to locals are assumed to be constants,
+/// such as enumerators or macros defined in headers.
+pub(crate) fn is_constant(expr: Node<'_>, code: &[u8], locals: &HashSet<&str>) -> bool {
+    match expr.kind() {
+        "sizeof_expression" | "alignof_expression" | "offsetof_expression" => true,
+        "call_expression" | "assignment_expression" | "update_expression" => false,
+        "identifier" => !locals.contains(text(expr, code)),
+        _ => {
+            let mut cursor = expr.walk();
+            expr.named_children(&mut cursor)
+                .all(|child| is_constant(child, code, locals))
+        },
+    }
+}
@@ -0,0 +1,211 @@
+//! The `network-byte-order` lint.
+//!
+//! Multi-byte fields of network protocol headers are stored in network
+//! (big endian) byte order. Comparing them against (or assigning them)
+//! constants in host byte order without conversion silently does the
+//! wrong thing on little endian machines.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+
+use super::Context;
+use super::descendants;
+use super::functions;
+use super::strip;
+use super::text;
+
+
+pub(crate) const NAME: &str = "network-byte-order";
+pub(crate) const MESSAGE: &str = "header field in network byte order is used with a constant in host byte order; convert the constant with `bpf_htons` or `bpf_htonl`";
+
+
+/// Big endian fields of well-known headers, as `(struct, field, size)`
+/// tuples.
+static BIG_ENDIAN_FIELDS: [(&str, &str, usize); 20] = [
+    ("ethhdr", "h_proto", 2),
+    ("icmphdr", "un.echo.id", 2),
+    ("icmphdr", "un.echo.sequence", 2),
+    ("iphdr", "daddr", 4),
+    ("iphdr", "frag_off", 2),
+    ("iphdr", "id", 2),
+    ("iphdr", "saddr", 4),
+    ("iphdr", "tot_len", 2),
+    ("ipv6hdr", "payload_len", 2),
+    ("tcphdr", "ack_seq", 4),
+    ("tcphdr", "dest", 2),
+    ("tcphdr", "seq", 4),
+    ("tcphdr", "source", 2),
+    ("tcphdr", "urg_ptr", 2),
+    ("tcphdr", "window", 2),
+    ("udphdr", "dest", 2),
+    ("udphdr", "len", 2),
+    ("udphdr", "source", 2),
+    ("vlan_hdr", "h_vlan_TCI", 2),
+    ("vlan_hdr", "h_vlan_encapsulated_proto", 2),
+];
+
+
+/// Collect the variables of struct type declared in `function`, mapped
+/// to the struct's name.
+fn struct_vars<'c>(function: Node<'_>, code: &'c [u8]) -> HashMap<&'c str, &'c str> {
+    let mut vars = HashMap::new();
+    for decl in descendants(function)
+        .filter(|node| matches!(node.kind(), "declaration" | "parameter_declaration"))
+    {
+        let Some(ty) = decl
+            .child_by_field_name("type")
+            .filter(|ty| ty.kind() == "struct_specifier")
+            .and_then(|ty| ty.child_by_field_name("name"))
+        else {
+            continue
+        };
+        let ty = text(ty, code);
+
+        let mut cursor = decl.walk();
+        for declarator in decl.children_by_field_name("declarator", &mut cursor) {
+            let mut declarator = Some(declarator);
+            while let Some(decl) = declarator {
+                if decl.kind() == "identifier" {
+                    let _prev = vars.insert(text(decl, code), ty);
+                    break
+                }
+                declarator = decl.child_by_field_name("declarator");
+            }
+        }
+    }
+    vars
+}
+
+
+/// Check whether `node` is a constant in host byte order that is
+/// affected by conversion.
+fn is_host_constant(node: Node<'_>, code: &[u8]) -> bool {
+    let node = strip(node);
+    let text = text(node, code);
+    match node.kind() {
+        "number_literal" => {
+            let digits = text.trim_end_matches(['u', 'U', 'l', 'L']);
+            let value = if let Some(hex) = digits
+                .strip_prefix("0x")
+                .or_else(|| digits.strip_prefix("0X"))
+            {
+                u64::from_str_radix(hex, 16).ok()
+            } else {
+                digits.parse::<u64>().ok()
+            };
+            // Zero is the same in every byte order.
+            value != Some(0)
+        },
+        "identifier" => text.starts_with("ETH_P_") || text.starts_with("IPPROTO_"),
+        _ => false,
+    }
+}
+
+
+/// If `node` accesses a big endian header field, return the field's
+/// description and size.
+fn big_endian_field(
+    node: Node<'_>,
+    code: &[u8],
+    vars: &HashMap<&str, &str>,
+) -> Option<(String, usize)> {
+    let mut node = strip(node);
+    let mut path = Vec::new();
+    // Walk nested accesses such as `icmp->un.echo.id` down to the
+    // variable.
+    while node.kind() == "field_expression" {
+        let () = path.push(text(node.child_by_field_name("field")?, code));
+        node = strip(node.child_by_field_name("argument")?);
+    }
+    if node.kind() != "identifier" || path.is_empty() {
+        return None
+    }
+
+    let ty = vars.get(text(node, code))?;
+    let () = path.reverse();
+    let field = path.join(".");
+    BIG_ENDIAN_FIELDS
+        .iter()
+        .find(|(s, f, _size)| s == ty && *f == field)
+        .map(|(s, f, size)| (format!("`{f}` of `struct {s}`"), *size))
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+
+    for function in functions(cx.tree) {
+        let vars = struct_vars(function, cx.code);
+        if vars.is_empty() {
+            continue
+        }
+
+        for node in descendants(function) {
+            // Comparisons are symmetric, with the field possibly being on
+            // either side; for assignments it has to be the target.
+            let (left, right, verb, symmetric) = match node.kind() {
+                "binary_expression" => {
+                    let op = node
+                        .child_by_field_name("operator")
+                        .map(|op| op.kind())
+                        .unwrap_or_default();
+                    if !matches!(op, "==" | "!=" | "<" | "<=" | ">" | ">=") {
+                        continue
+                    }
+                    (
+                        node.child_by_field_name("left"),
+                        node.child_by_field_name("right"),
+                        "compared to",
+                        true,
+                    )
+                },
+                "assignment_expression" => {
+                    let is_plain = node
+                        .child_by_field_name("operator")
+                        .is_some_and(|op| op.kind() == "=");
+                    if !is_plain {
+                        continue
+                    }
+                    (
+                        node.child_by_field_name("left"),
+                        node.child_by_field_name("right"),
+                        "assigned",
+                        false,
+                    )
+                },
+                _ => continue,
+            };
+            let (Some(left), Some(right)) = (left, right) else {
+                continue
+            };
+
+            let finding = if let Some(field) = big_endian_field(left, cx.code, &vars) {
+                Some((field, right))
+            } else if symmetric {
+                big_endian_field(right, cx.code, &vars).map(|field| (field, left))
+            } else {
+                None
+            };
+
+            let Some(((field, size), constant)) = finding else {
+                continue
+            };
+            if !is_host_constant(constant, cx.code) {
+                continue
+            }
+
+            let constant = cx.text(constant);
+            let convert = if size == 4 { "bpf_htonl" } else { "bpf_htons" };
+            let message = format!(
+                "{field} is in network byte order but {verb} `{constant}` in host byte order; use `{convert}({constant})` instead"
+            );
+            let () = matches.push(cx.match_msg(node.range(), message));
+        }
+    }
+    Ok(matches)
+}
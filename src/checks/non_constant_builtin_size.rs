@@ -0,0 +1,68 @@
+//! The `non-constant-builtin-size` lint.
+//!
+//! There is no C library to call into from BPF programs, so clang has
+//! to expand `__builtin_memcpy()` and friends inline, into a sequence
+//! of loads and stores. That is only possible if the number of bytes
+//! is known at compile time.
+
+use anyhow::Result;
+
+use crate::LintMatch;
+
+use super::Context;
+use super::call_name;
+use super::descendants;
+use super::functions;
+use super::is_constant;
+use super::locals;
+
+
+pub(crate) const NAME: &str = "non-constant-builtin-size";
+pub(crate) const MESSAGE: &str =
+    "memory builtins require a constant size in BPF programs, as they have to be expanded inline";
+
+
+/// Builtins that have to be expanded inline. For all of them the size
+/// is the third argument.
+static BUILTINS: [&str; 4] = [
+    "__builtin_memcmp",
+    "__builtin_memcpy",
+    "__builtin_memmove",
+    "__builtin_memset",
+];
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    for function in functions(cx.tree) {
+        let Some(body) = function.child_by_field_name("body") else {
+            continue
+        };
+        let locals = locals(function, cx.code);
+
+        for call in descendants(body) {
+            let Some(name) = call_name(call, cx.code) else {
+                continue
+            };
+            if !BUILTINS.contains(&name) {
+                continue
+            }
+            let Some(size) = call
+                .child_by_field_name("arguments")
+                .and_then(|args| args.named_child(2))
+            else {
+                continue
+            };
+            if is_constant(size, cx.code, &locals) {
+                continue
+            }
+
+            let message = format!(
+                "size `{}` of `{name}()` is not a compile-time constant; BPF programs can't call into a C library, so clang has to expand the builtin inline, which requires a constant size; use a constant upper bound or `bpf_probe_read_kernel()` for copies of variable length",
+                cx.text(size)
+            );
+            let () = matches.push(cx.match_msg(size.range(), message));
+        }
+    }
+    Ok(matches)
+}
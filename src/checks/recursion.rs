@@ -0,0 +1,82 @@
+//! The `recursion` lint.
+//!
+//! BPF does not support recursion: the verifier rejects programs with
+//! loops in their call graph, whether a function calls itself directly
+//! or indirectly via other functions.
+
+use anyhow::Result;
+
+use crate::LintMatch;
+use crate::callgraph::CallGraph;
+
+use super::Context;
+use super::label;
+
+
+pub(crate) const NAME: &str = "recursion";
+pub(crate) const MESSAGE: &str =
+    "function is called recursively; the verifier rejects recursive calls";
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    let graph = CallGraph::new(cx.tree, cx.code);
+    let mut reported = vec![false; graph.functions.len()];
+
+    // Each cycle is reported once, at the first function of the
+    // strongly connected component it is part of, with all other calls
+    // within the component attached as labels.
+    for (id, function) in graph.functions.iter().enumerate() {
+        if reported[id] {
+            continue
+        }
+
+        let component = (0..graph.functions.len())
+            .filter(|other| {
+                *other == id
+                    || (graph.path(id, *other).is_some() && graph.path(*other, id).is_some())
+            })
+            .collect::<Vec<_>>();
+        let mut calls = component.iter().flat_map(|caller| {
+            graph.functions[*caller]
+                .calls
+                .iter()
+                .filter(|(_call, callee)| component.contains(callee))
+                .map(move |(call, callee)| (*caller, *call, *callee))
+        });
+        let Some((_caller, call, callee)) = calls.next() else {
+            continue
+        };
+
+        let message = if callee == id {
+            format!("`{}` calls itself recursively", function.name)
+        } else {
+            // SANITY: All functions in the component can reach each
+            //         other.
+            let path = graph.path(callee, id).unwrap();
+            let cycle = [id]
+                .iter()
+                .chain(&path)
+                .map(|id| format!("`{}`", graph.functions[*id].name))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            format!("`{}` calls itself recursively via {cycle}", function.name)
+        };
+        let mut m = cx.match_msg(call.range(), message);
+        for (caller, call, callee) in calls {
+            let () = m.labels.push(label(
+                call,
+                format!(
+                    "`{}` calls `{}` here",
+                    graph.functions[caller].name, graph.functions[callee].name
+                ),
+            ));
+        }
+        let () = matches.push(m);
+
+        for member in component {
+            reported[member] = true;
+        }
+    }
+    Ok(matches)
+}
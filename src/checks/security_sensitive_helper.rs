@@ -0,0 +1,114 @@
+//! The `security-sensitive-helper` lint.
+//!
+//! A handful of helpers let BPF programs interfere with the rest of the
+//! system well beyond observing it: they write to user space memory,
+//! alter kernel behavior, or send signals. Security reviews need an
+//! inventory of every program using them, which is what this lint
+//! provides.
+
+use anyhow::Result;
+
+use crate::LintMatch;
+use crate::callgraph::CallGraph;
+
+use super::Context;
+use super::call_name;
+use super::descendants;
+use super::label;
+
+
+pub(crate) const NAME: &str = "security-sensitive-helper";
+pub(crate) const MESSAGE: &str = "program uses a security sensitive helper";
+
+
+/// Security sensitive helpers, along with a description of what makes
+/// them so.
+static HELPERS: [(&str, &str); 6] = [
+    (
+        "bpf_d_path",
+        "resolves the full path of files being accessed",
+    ),
+    (
+        "bpf_override_return",
+        "overrides the return value of the probed kernel function",
+    ),
+    (
+        "bpf_probe_write_user",
+        "writes to the memory of the current user space process",
+    ),
+    ("bpf_send_signal", "sends a signal to the current process"),
+    (
+        "bpf_send_signal_thread",
+        "sends a signal to the current thread",
+    ),
+    (
+        "bpf_sys_bpf",
+        "executes bpf() system call commands, e.g., to load programs",
+    ),
+];
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    let graph = CallGraph::new(cx.tree, cx.code);
+    let programs = graph
+        .functions
+        .iter()
+        .enumerate()
+        .filter_map(|(id, function)| Some((id, function, function.section.as_deref()?)))
+        .collect::<Vec<_>>();
+
+    for (id, function) in graph.functions.iter().enumerate() {
+        let Some(body) = function.definition.child_by_field_name("body") else {
+            continue
+        };
+
+        for call in descendants(body) {
+            let Some(name) = call_name(call, cx.code) else {
+                continue
+            };
+            let Some((helper, what)) = HELPERS.iter().find(|(helper, _)| *helper == name) else {
+                continue
+            };
+
+            let mut reached = false;
+            for (prog_id, program, section) in &programs {
+                let path = if *prog_id == id {
+                    Vec::new()
+                } else if let Some(path) = graph.path(*prog_id, id) {
+                    path
+                } else {
+                    continue
+                };
+                reached = true;
+
+                let message = format!(
+                    "program `{}` attached to `{section}` calls `{helper}()`, which {what}",
+                    program.name
+                );
+                let mut m = cx.match_msg(call.range(), message);
+                if !path.is_empty() {
+                    let path = path
+                        .iter()
+                        .map(|id| format!("`{}`", graph.functions[*id].name))
+                        .collect::<Vec<_>>()
+                        .join(" -> ");
+                    let () = m.labels.push(label(
+                        program.ident,
+                        format!("`{}` reaches the call via {path}", program.name),
+                    ));
+                }
+                let () = matches.push(m);
+            }
+
+            if !reached {
+                let message = format!(
+                    "function `{}` calls `{helper}()`, which {what}, but is not reachable from any program",
+                    function.name
+                );
+                let () = matches.push(cx.match_msg(call.range(), message));
+            }
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,195 @@
+//! The `stack-usage` lint.
+//!
+//! BPF programs have a stack of only 512 bytes and the verifier rejects
+//! programs exceeding it. Large local variables, such as event structs
+//! assembled before being sent to user space, quickly add up. We
+//! estimate a program's stack frame from its local declarations and
+//! those of the static functions it calls, which are inlined. Static
+//! functions marked as `noinline` get a stack frame of their own and
+//! are not accounted for.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::types::Types;
+use crate::types::declarator_name;
+
+use super::Context;
+use super::call_name;
+use super::descendants;
+use super::function_name;
+use super::functions;
+use super::label;
+use super::programs;
+use super::text;
+
+
+pub(crate) const NAME: &str = "stack-usage";
+pub(crate) const MESSAGE: &str = "program's estimated stack usage exceeds the limit";
+
+
+/// Macros and attributes preventing a function from being inlined.
+static NOINLINE_MARKERS: [&str; 2] = ["__noinline", "noinline"];
+
+
+/// A local variable occupying stack space.
+struct Var<'t> {
+    /// The identifier declaring the variable.
+    name: Node<'t>,
+    /// The variable's size, in bytes.
+    size: u64,
+    /// The inlined function the variable belongs to, if any.
+    inlined: Option<&'t str>,
+}
+
+
+/// The estimated stack frame of a function.
+#[derive(Default)]
+struct Frame<'t> {
+    vars: Vec<Var<'t>>,
+    size: u64,
+}
+
+
+/// Check whether a declaration or function definition has the given
+/// storage class.
+fn has_storage_class(node: Node<'_>, code: &[u8], class: &str) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| child.kind() == "storage_class_specifier" && text(child, code) == class)
+}
+
+/// Check whether `function` is marked as not to be inlined.
+fn is_noinline(function: Node<'_>, code: &[u8]) -> bool {
+    let Some(name) = function_name(function) else {
+        return false
+    };
+    // Markers may not be understood by the parser, so look for any
+    // token preceding the declarator, including in attributes.
+    let mut cursor = function.walk();
+    function
+        .children(&mut cursor)
+        .take_while(|child| child.end_byte() <= name.start_byte())
+        .flat_map(descendants)
+        .filter(|node| node.child_count() == 0)
+        .any(|node| NOINLINE_MARKERS.contains(&text(node, code)))
+}
+
+
+/// Estimate the stack frame of `function`.
+fn estimate<'t>(
+    function: Node<'t>,
+    code: &'t [u8],
+    types: &Types<'_>,
+    statics: &HashMap<&'t str, Node<'t>>,
+    stack: &mut Vec<&'t str>,
+) -> Frame<'t> {
+    let mut frame = Frame::default();
+    let Some(body) = function.child_by_field_name("body") else {
+        return frame
+    };
+
+    for decl in descendants(body).filter(|node| node.kind() == "declaration") {
+        if has_storage_class(decl, code, "static") || has_storage_class(decl, code, "extern") {
+            continue
+        }
+        let Some(ty) = decl.child_by_field_name("type") else {
+            continue
+        };
+
+        let mut cursor = decl.walk();
+        for declarator in decl.children_by_field_name("declarator", &mut cursor) {
+            let (Some(layout), Some(name)) =
+                (types.layout(ty, declarator), declarator_name(declarator))
+            else {
+                continue
+            };
+            frame.size += layout.size;
+            let () = frame.vars.push(Var {
+                name,
+                size: layout.size,
+                inlined: None,
+            });
+        }
+    }
+
+    // Inlined functions are called one after the other and the
+    // compiler can reuse their stack slots. Hence, only the largest
+    // one counts.
+    let mut largest = Frame::default();
+    for call in descendants(body).filter(|node| node.kind() == "call_expression") {
+        let Some(name) = call_name(call, code) else {
+            continue
+        };
+        let Some(callee) = statics.get(name) else {
+            continue
+        };
+        // Recursive functions can't be inlined (nor verified).
+        if stack.contains(&name) {
+            continue
+        }
+
+        let () = stack.push(name);
+        let mut callee = estimate(*callee, code, types, statics, stack);
+        let _name = stack.pop();
+
+        if callee.size > largest.size {
+            for var in &mut callee.vars {
+                let _inlined = var.inlined.get_or_insert(name);
+            }
+            largest = callee;
+        }
+    }
+
+    frame.size += largest.size;
+    let () = frame.vars.extend(largest.vars);
+    frame
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    let types = Types::new(cx.tree, cx.code);
+    let statics = functions(cx.tree)
+        .filter(|function| has_storage_class(*function, cx.code, "static"))
+        .filter(|function| !is_noinline(*function, cx.code))
+        .filter_map(|function| Some((cx.text(function_name(function)?), function)))
+        .collect::<HashMap<_, _>>();
+
+    for program in programs(cx.tree) {
+        let Some(name) = function_name(program) else {
+            continue
+        };
+
+        let mut stack = vec![cx.text(name)];
+        let frame = estimate(program, cx.code, &types, &statics, &mut stack);
+        if frame.size <= cx.opts.stack_limit {
+            continue
+        }
+
+        let message = format!(
+            "estimated stack usage of program `{}` is {} bytes, exceeding the limit of {} bytes",
+            cx.text(name),
+            frame.size,
+            cx.opts.stack_limit
+        );
+        let mut m = cx.match_msg(name.range(), message);
+        for var in frame.vars {
+            let message = match var.inlined {
+                Some(function) => format!(
+                    "`{}` uses {} bytes (inlined from `{function}`)",
+                    cx.text(var.name),
+                    var.size
+                ),
+                None => format!("`{}` uses {} bytes", cx.text(var.name), var.size),
+            };
+            let () = m.labels.push(label(var.name, message));
+        }
+        let () = matches.push(m);
+    }
+    Ok(matches)
+}
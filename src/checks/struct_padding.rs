@@ -0,0 +1,232 @@
+//! The `struct-padding` lint.
+//!
+//! Structs sent to user space via ring or perf buffers are copied in
+//! their entirety, including any padding holes the compiler inserted
+//! between (or after) members. Unless the struct was cleared first,
+//! these holes contain whatever happened to be in memory before,
+//! leaking kernel memory to user space.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::Label;
+use crate::LintMatch;
+use crate::types::Hole;
+use crate::types::Types;
+use crate::types::declarator_name;
+
+use super::Context;
+use super::call_name;
+use super::descendants;
+use super::functions;
+use super::label;
+use super::strip;
+use super::text;
+
+
+pub(crate) const NAME: &str = "struct-padding";
+pub(crate) const MESSAGE: &str = "struct with padding holes is sent to user space without being cleared, leaking uninitialized memory";
+
+
+/// Helpers copying data to user space, along with the index of the
+/// argument referring to the data.
+static OUTPUT_HELPERS: [(&str, usize); 2] =
+    [("bpf_perf_event_output", 3), ("bpf_ringbuf_output", 1)];
+
+
+/// A local variable.
+struct Var<'t> {
+    /// The identifier declaring the variable.
+    name: Node<'t>,
+    /// The variable's type specifier.
+    ty: Node<'t>,
+    /// Whether the variable is a pointer.
+    is_pointer: bool,
+    /// Whether the variable is zero-initialized.
+    is_zeroed: bool,
+}
+
+
+/// Check whether `value` is an initializer zeroing the entire object,
+/// i.e., `{}` or `{0}`.
+fn is_zero_initializer(value: Node<'_>, code: &[u8]) -> bool {
+    value.kind() == "initializer_list"
+        && match value.named_child_count() {
+            0 => true,
+            1 => value
+                .named_child(0)
+                .is_some_and(|child| text(child, code) == "0"),
+            _ => false,
+        }
+}
+
+
+/// Collect the local variables declared in `function`.
+fn local_vars<'t>(function: Node<'t>, code: &'t [u8]) -> HashMap<&'t str, Var<'t>> {
+    let mut vars = HashMap::new();
+    for decl in descendants(function).filter(|node| node.kind() == "declaration") {
+        let Some(ty) = decl.child_by_field_name("type") else {
+            continue
+        };
+
+        let mut cursor = decl.walk();
+        for declarator in decl.children_by_field_name("declarator", &mut cursor) {
+            let Some(name) = declarator_name(declarator) else {
+                continue
+            };
+            let value = declarator
+                .child_by_field_name("value")
+                .filter(|_| declarator.kind() == "init_declarator");
+            let inner = match declarator.kind() {
+                "init_declarator" => declarator.child_by_field_name("declarator"),
+                _ => Some(declarator),
+            };
+            let var = Var {
+                name,
+                ty,
+                is_pointer: inner.is_some_and(|inner| inner.kind() == "pointer_declarator"),
+                is_zeroed: value.is_some_and(|value| is_zero_initializer(value, code)),
+            };
+            let _prev = vars.insert(text(name, code), var);
+        }
+    }
+    vars
+}
+
+
+/// Retrieve the argument with index `idx` of a call.
+fn arg(call: Node<'_>, idx: usize) -> Option<Node<'_>> {
+    call.child_by_field_name("arguments")
+        .and_then(|args| args.named_child(idx))
+}
+
+
+/// Determine the variable referred to by a pointer expression such as
+/// `&event` or `e`, along with whether its address is taken.
+fn pointee<'c>(expr: Node<'_>, code: &'c [u8]) -> Option<(&'c str, bool)> {
+    let expr = strip(expr);
+    match expr.kind() {
+        "identifier" => Some((text(expr, code), false)),
+        "pointer_expression" => {
+            let op = expr.child_by_field_name("operator")?;
+            let arg = strip(expr.child_by_field_name("argument")?);
+            (op.kind() == "&" && arg.kind() == "identifier").then(|| (text(arg, code), true))
+        },
+        _ => None,
+    }
+}
+
+
+/// Create labels describing `holes`.
+fn hole_labels<'h>(holes: &'h [Hole<'_>], code: &'h [u8]) -> impl Iterator<Item = Label> + 'h {
+    holes.iter().map(move |hole| {
+        let what = if hole.trailing {
+            "trailing padding"
+        } else {
+            "padding"
+        };
+        let message = match hole.after {
+            Some(after) => format!(
+                "{} bytes of {what} after `{}`",
+                hole.size,
+                text(after, code)
+            ),
+            None => format!("{} bytes of {what} after this anonymous member", hole.size),
+        };
+        label(hole.node, message)
+    })
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    let types = Types::new(cx.tree, cx.code);
+
+    for function in functions(cx.tree) {
+        let vars = local_vars(function, cx.code);
+        if vars.is_empty() {
+            continue
+        }
+
+        let calls = descendants(function)
+            .filter_map(|node| Some((node, call_name(node, cx.code)?)))
+            .collect::<Vec<_>>();
+        // Variables explicitly cleared.
+        let cleared = calls
+            .iter()
+            .filter(|(_call, name)| matches!(*name, "__builtin_memset" | "memset"))
+            .filter_map(|(call, _name)| pointee(arg(*call, 0)?, cx.code))
+            .map(|(var, _addr)| var)
+            .collect::<BTreeSet<_>>();
+
+        for (call, name) in &calls {
+            if let Some((_helper, idx)) = OUTPUT_HELPERS.iter().find(|(helper, _)| helper == name) {
+                let Some((var, true)) = arg(*call, *idx).and_then(|arg| pointee(arg, cx.code))
+                else {
+                    continue
+                };
+                let Some(decl) = vars.get(var) else { continue };
+                if decl.is_pointer || decl.is_zeroed || cleared.contains(var) {
+                    continue
+                }
+
+                let holes = types.holes(decl.ty);
+                if holes.is_empty() {
+                    continue
+                }
+                let size = holes.iter().map(|hole| hole.size).sum::<u64>();
+                let message = format!(
+                    "`{var}` is sent to user space with {size} bytes of uninitialized padding, leaking stack memory; initialize it with `= {{}}` or clear it with `__builtin_memset` first"
+                );
+                let mut m = cx.match_msg(call.range(), message);
+                let () = m
+                    .labels
+                    .push(label(decl.name, format!("`{var}` is declared here")));
+                let () = m.labels.extend(hole_labels(&holes, cx.code));
+                let () = matches.push(m);
+            } else if *name == "bpf_ringbuf_reserve" {
+                // Find the variable the reservation is assigned to.
+                let Some(parent) = call.parent() else {
+                    continue
+                };
+                let target = match parent.kind() {
+                    "init_declarator" => parent
+                        .child_by_field_name("declarator")
+                        .and_then(declarator_name),
+                    "assignment_expression" => parent.child_by_field_name("left"),
+                    "cast_expression" => parent
+                        .parent()
+                        .filter(|grand| grand.kind() == "init_declarator")
+                        .and_then(|grand| grand.child_by_field_name("declarator"))
+                        .and_then(declarator_name),
+                    _ => None,
+                };
+                let Some(target) = target.filter(|target| target.kind() == "identifier") else {
+                    continue
+                };
+                let var = cx.text(target);
+                let Some(decl) = vars.get(var) else { continue };
+                if !decl.is_pointer || cleared.contains(var) {
+                    continue
+                }
+
+                let holes = types.holes(decl.ty);
+                if holes.is_empty() {
+                    continue
+                }
+                let size = holes.iter().map(|hole| hole.size).sum::<u64>();
+                let message = format!(
+                    "ring buffer reservation `{var}` has {size} bytes of padding that are never initialized, leaking kernel memory; clear it with `__builtin_memset` first"
+                );
+                let mut m = cx.match_msg(call.range(), message);
+                let () = m.labels.extend(hole_labels(&holes, cx.code));
+                let () = matches.push(m);
+            }
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,357 @@
+//! The `unbalanced-lock` lint.
+//!
+//! BPF spin locks (`bpf_spin_lock`/`bpf_spin_unlock`) and RCU read
+//! sections (`bpf_rcu_read_lock`/`bpf_rcu_read_unlock`) have to be
+//! released on every path through a program. While a spin lock is held
+//! the verifier only allows a small set of calls; inside an RCU read
+//! section sleepable helpers are forbidden.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::cfg::Cfg;
+
+use super::Context;
+use super::call_name;
+use super::descendants;
+use super::functions;
+use super::label;
+use super::text;
+
+
+pub(crate) const NAME: &str = "unbalanced-lock";
+pub(crate) const MESSAGE: &str =
+    "lock is not released on all paths; the verifier will reject the program";
+
+/// Functions that may be called while holding a spin lock.
+///
+/// Besides the unlock operation itself these are the kfuncs operating
+/// on lock protected graph data structures, as well as macros and
+/// builtins that don't result in a call.
+static SPIN_LOCK_ALLOWED: [&str; 28] = [
+    "READ_ONCE",
+    "WRITE_ONCE",
+    "__builtin_memcpy",
+    "__builtin_memset",
+    "barrier",
+    "bpf_be64_to_cpu",
+    "bpf_core_enum_value",
+    "bpf_core_enum_value_exists",
+    "bpf_core_field_exists",
+    "bpf_core_field_size",
+    "bpf_core_type_exists",
+    "bpf_core_type_size",
+    "bpf_cpu_to_be64",
+    "bpf_htonl",
+    "bpf_htons",
+    "bpf_list_back",
+    "bpf_list_front",
+    "bpf_list_pop_back",
+    "bpf_list_pop_front",
+    "bpf_list_push_back",
+    "bpf_list_push_front",
+    "bpf_ntohl",
+    "bpf_ntohs",
+    "bpf_rbtree_add",
+    "bpf_rbtree_first",
+    "bpf_rbtree_remove",
+    "bpf_refcount_acquire",
+    "bpf_spin_unlock",
+];
+
+/// Sleepable helpers, which must not be called inside of an RCU read
+/// section.
+static SLEEPABLE_HELPERS: [&str; 6] = [
+    "bpf_copy_from_user",
+    "bpf_copy_from_user_str",
+    "bpf_copy_from_user_task",
+    "bpf_get_file_xattr",
+    "bpf_ima_file_hash",
+    "bpf_ima_inode_hash",
+];
+
+
+/// The kind of a lock.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Kind {
+    Spin,
+    Rcu,
+}
+
+
+/// A lock, identified by its kind and (for spin locks) the expression
+/// referring to it, with white space removed.
+type Lock = (Kind, String);
+
+
+/// The nesting depth of RCU read sections beyond which we don't
+/// distinguish depths, bounding the data flow computation for loops
+/// acquiring the lock.
+const MAX_RCU_DEPTH: usize = 8;
+
+
+/// Information about a held lock.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Held<'t> {
+    /// The (outermost) call acquiring the lock.
+    acquire: Node<'t>,
+    /// Whether the lock is held on all paths, as opposed to just some.
+    always: bool,
+    /// How often the lock is held; RCU read sections may be nested,
+    /// spin locks are only ever held once.
+    depth: usize,
+}
+
+
+/// The locks (possibly) held at a certain point.
+type State<'t> = BTreeMap<Lock, Held<'t>>;
+
+
+fn join<'t>(a: &State<'t>, b: &State<'t>) -> State<'t> {
+    let mut state = a.clone();
+    for (lock, held) in state.iter_mut() {
+        let other = b.get(lock);
+        held.always &= other.is_some_and(|held| held.always);
+        held.depth = held.depth.max(other.map(|held| held.depth).unwrap_or(0));
+    }
+    for (lock, held) in b {
+        let _held = state.entry(lock.clone()).or_insert(Held {
+            always: false,
+            ..*held
+        });
+    }
+    state
+}
+
+
+/// Describe a lock for usage in a message.
+fn describe(lock: &Lock) -> String {
+    match lock {
+        (Kind::Spin, expr) => format!("spin lock `{expr}`"),
+        (Kind::Rcu, _) => "RCU read lock".to_string(),
+    }
+}
+
+
+/// Determine the lock operated on by a call, if any, and whether it is
+/// acquired (as opposed to released).
+fn lock_op(call: Node<'_>, code: &[u8]) -> Option<(Lock, bool)> {
+    let name = call_name(call, code)?;
+    let (kind, acquire) = match name {
+        "bpf_spin_lock" => (Kind::Spin, true),
+        "bpf_spin_unlock" => (Kind::Spin, false),
+        "bpf_rcu_read_lock" => (Kind::Rcu, true),
+        "bpf_rcu_read_unlock" => (Kind::Rcu, false),
+        _ => return None,
+    };
+
+    let lock = match kind {
+        Kind::Spin => {
+            let arg = call
+                .child_by_field_name("arguments")
+                .and_then(|args| args.named_child(0))?;
+            let expr = text(arg, code).split_whitespace().collect::<String>();
+            (kind, expr)
+        },
+        Kind::Rcu => (kind, String::new()),
+    };
+    Some((lock, acquire))
+}
+
+
+/// A problem found while processing a function.
+enum Finding<'t> {
+    /// A lock is acquired while already being held.
+    DoubleAcquire {
+        call: Node<'t>,
+        lock: Lock,
+        prev: Node<'t>,
+    },
+    /// A lock is released without being held (on all paths).
+    Unheld {
+        call: Node<'t>,
+        lock: Lock,
+        acquire: Option<Node<'t>>,
+    },
+    /// A forbidden function is called while holding a lock.
+    ForbiddenCall {
+        call: Node<'t>,
+        name: &'t str,
+        lock: Lock,
+        acquire: Node<'t>,
+    },
+}
+
+
+/// Compute the state after executing `nodes`.
+fn transfer<'t>(
+    nodes: &[Node<'t>],
+    code: &'t [u8],
+    state: &State<'t>,
+    findings: &mut Vec<Finding<'t>>,
+) -> State<'t> {
+    let mut state = state.clone();
+    let calls = nodes
+        .iter()
+        .flat_map(|node| descendants(*node))
+        .filter(|node| node.kind() == "call_expression");
+
+    for call in calls {
+        if let Some((lock, acquire)) = lock_op(call, code) {
+            if acquire {
+                match state.get_mut(&lock) {
+                    Some(held) if lock.0 == Kind::Rcu => {
+                        held.depth = (held.depth + 1).min(MAX_RCU_DEPTH);
+                    },
+                    Some(held) => {
+                        let () = findings.push(Finding::DoubleAcquire {
+                            call,
+                            lock: lock.clone(),
+                            prev: held.acquire,
+                        });
+                    },
+                    None => {
+                        let held = Held {
+                            acquire: call,
+                            always: true,
+                            depth: 1,
+                        };
+                        let _prev = state.insert(lock, held);
+                    },
+                }
+            } else if let Some(held) = state.get_mut(&lock).filter(|held| held.depth > 1) {
+                held.depth -= 1;
+            } else {
+                match state.remove(&lock) {
+                    Some(held) if held.always => (),
+                    held => {
+                        let () = findings.push(Finding::Unheld {
+                            call,
+                            lock,
+                            acquire: held.map(|held| held.acquire),
+                        });
+                    },
+                }
+            }
+            continue
+        }
+
+        let Some(name) = call_name(call, code) else {
+            continue
+        };
+        for (lock, held) in &state {
+            let forbidden = match lock.0 {
+                Kind::Spin => !SPIN_LOCK_ALLOWED.contains(&name),
+                Kind::Rcu => SLEEPABLE_HELPERS.contains(&name),
+            };
+            if forbidden {
+                let () = findings.push(Finding::ForbiddenCall {
+                    call,
+                    name,
+                    lock: lock.clone(),
+                    acquire: held.acquire,
+                });
+            }
+        }
+    }
+    state
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+
+    for function in functions(cx.tree) {
+        let cfg = Cfg::new(function, cx.code);
+        let states = cfg.forward(
+            State::new(),
+            |block, state| transfer(&block.nodes, cx.code, state, &mut Vec::new()),
+            |_edge, state| state.clone(),
+            join,
+        );
+
+        let mut findings = Vec::new();
+        for (block, state) in cfg.blocks.iter().zip(&states) {
+            let Some(state) = state else { continue };
+            let state = transfer(&block.nodes, cx.code, state, &mut findings);
+
+            if !block.succs.iter().any(|edge| edge.to == cfg.exit) {
+                continue
+            }
+
+            let exit = match block.nodes.last() {
+                Some(node) if node.kind() == "return_statement" => Some(*node),
+                // We fall off the end of the function body; point at
+                // its closing brace.
+                _ => function
+                    .child_by_field_name("body")
+                    .and_then(|body| body.child(body.child_count().saturating_sub(1))),
+            };
+            let Some(exit) = exit else { continue };
+
+            for (lock, held) in state {
+                let what = describe(&lock);
+                let verb = if held.always { "is" } else { "may be" };
+                let message = format!("returning while {what} {verb} held");
+                let mut m = cx.match_msg(exit.range(), message);
+                let () = m
+                    .labels
+                    .push(label(held.acquire, format!("{what} acquired here")));
+                let () = matches.push(m);
+            }
+        }
+
+        for finding in findings {
+            let m = match finding {
+                Finding::DoubleAcquire { call, lock, prev } => {
+                    let what = describe(&lock);
+                    let message = format!("{what} is acquired while already being held");
+                    let mut m = cx.match_msg(call.range(), message);
+                    let () = m
+                        .labels
+                        .push(label(prev, format!("{what} previously acquired here")));
+                    m
+                },
+                Finding::Unheld {
+                    call,
+                    lock,
+                    acquire,
+                } => {
+                    let what = describe(&lock);
+                    if let Some(acquire) = acquire {
+                        let message = format!("{what} is released without being held on all paths");
+                        let mut m = cx.match_msg(call.range(), message);
+                        let () = m.labels.push(label(
+                            acquire,
+                            format!("{what} is only acquired on some paths here"),
+                        ));
+                        m
+                    } else {
+                        let message = format!("{what} is released without being held");
+                        cx.match_msg(call.range(), message)
+                    }
+                },
+                Finding::ForbiddenCall {
+                    call,
+                    name,
+                    lock,
+                    acquire,
+                } => {
+                    let what = describe(&lock);
+                    let message = format!("calling `{name}` is not allowed while holding {what}");
+                    let mut m = cx.match_msg(call.range(), message);
+                    let () = m
+                        .labels
+                        .push(label(acquire, format!("{what} acquired here")));
+                    m
+                },
+            };
+            let () = matches.push(m);
+        }
+    }
+    Ok(matches)
+}
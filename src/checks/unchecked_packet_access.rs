@@ -0,0 +1,501 @@
+//! The `unchecked-packet-access` lint.
+//!
+//! XDP and TC programs access packet data through pointers derived from
+//! `ctx->data`. Before dereferencing such a pointer the program has to
+//! prove to the verifier that the access is within bounds, by comparing
+//! it against `ctx->data_end`. We track packet pointers through
+//! assignments and report accesses not preceded by such a check on all
+//! paths, or by one covering fewer bytes than accessed.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::cfg::Cfg;
+use crate::cfg::Cond;
+use crate::cfg::Edge;
+use crate::types::Types;
+use crate::types::declarator_name;
+
+use super::Context;
+use super::PointerFlow;
+use super::descendants;
+use super::label;
+use super::programs;
+use super::section;
+use super::strip;
+use super::text;
+use super::track_pointers;
+
+
+pub(crate) const NAME: &str = "unchecked-packet-access";
+pub(crate) const MESSAGE: &str = "packet access is not preceded by a bounds check against `data_end`; the verifier will reject the program";
+
+
+/// Check whether a program in section `section` operates on packets
+/// accessed via `data`/`data_end`.
+fn is_packet_program(section: &str) -> bool {
+    let (kind, _rest) = section.split_once('/').unwrap_or((section, ""));
+    matches!(kind, "xdp" | "xdp.frags" | "tc" | "classifier" | "tcx")
+}
+
+
+/// Information about a pointer into a packet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Ptr<'t> {
+    /// The variable this pointer is an exact copy of, transitively;
+    /// the pointer itself if it was derived in any other way.
+    root: &'t str,
+    /// The root of the pointer this one was derived from by adding an
+    /// offset, if any, along with the offset in bytes, if known.
+    parent: Option<(&'t str, Option<u64>)>,
+    /// The number of bytes starting at `root` that have been checked
+    /// to be within the packet on all paths.
+    checked: u64,
+    /// The expression the pointer was derived from.
+    origin: Node<'t>,
+}
+
+
+/// The state tracked throughout a program.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct State<'t> {
+    /// Variables pointing into the packet.
+    ptrs: BTreeMap<&'t str, Ptr<'t>>,
+    /// Variables pointing to the end of the packet.
+    ends: BTreeSet<&'t str>,
+}
+
+impl State<'_> {
+    fn join(&self, other: &Self) -> Self {
+        let mut state = self.clone();
+        for (var, ptr) in state.ptrs.iter_mut() {
+            if let Some(other) = other.ptrs.get(var) {
+                ptr.checked = ptr.checked.min(other.checked);
+            }
+        }
+        for (var, ptr) in &other.ptrs {
+            let _ptr = state.ptrs.entry(*var).or_insert(*ptr);
+        }
+        state.ends.extend(other.ends.iter().copied());
+        state
+    }
+}
+
+
+/// An access to a packet pointer that is not bounds checked.
+struct Access<'t> {
+    expr: Node<'t>,
+    var: &'t str,
+    origin: Node<'t>,
+    /// The number of bytes accessed.
+    size: u64,
+    /// The number of bytes checked to be within the packet.
+    checked: u64,
+}
+
+
+/// Check whether `node` is a `<ctx>-><field>` expression.
+fn is_ctx_field(node: Node<'_>, code: &[u8], field: &str) -> bool {
+    let node = strip(node);
+    node.kind() == "field_expression"
+        && node
+            .child_by_field_name("operator")
+            .is_some_and(|op| op.kind() == "->")
+        && node
+            .child_by_field_name("field")
+            .is_some_and(|name| text(name, code) == field)
+}
+
+
+/// How a value relates to packet pointers.
+enum Derived<'t> {
+    /// The value is the start of the packet.
+    Start,
+    /// The value is the end of the packet.
+    End,
+    /// The value is an exact copy of the given packet pointer.
+    Copy(&'t str),
+    /// The value is a packet pointer with an offset (in bytes, if
+    /// known) applied to the packet pointer with the given root, or
+    /// the start of the packet.
+    Offset(Option<&'t str>, Option<u64>),
+}
+
+
+/// The information about a program required for interpreting pointer
+/// arithmetic.
+struct Program<'a, 't> {
+    code: &'t [u8],
+    types: &'a Types<'t>,
+    /// The size of the objects pointed to by local pointer variables,
+    /// if known.
+    pointees: HashMap<&'t str, u64>,
+}
+
+impl<'t> Program<'_, 't> {
+    /// Determine the size of the objects pointed to by the pointer
+    /// valued expression `expr`, if known.
+    fn pointee(&self, expr: Node<'t>) -> Option<u64> {
+        match expr.kind() {
+            "parenthesized_expression" => self.pointee(expr.named_child(0)?),
+            "cast_expression" => {
+                let ty = expr.child_by_field_name("type")?;
+                self.types
+                    .pointee(
+                        ty.child_by_field_name("type")?,
+                        ty.child_by_field_name("declarator")?,
+                    )
+                    .map(|layout| layout.size)
+            },
+            "identifier" => self.pointees.get(text(expr, self.code)).copied(),
+            "binary_expression" => self.pointee(expr.child_by_field_name("left")?),
+            _ => None,
+        }
+    }
+
+    /// Evaluate an integer expression, such as `sizeof(*eth)`.
+    fn eval(&self, expr: Node<'t>) -> Option<u64> {
+        if expr.kind() == "sizeof_expression" {
+            if let Some(value) = expr.child_by_field_name("value").map(strip) {
+                return match value.kind() {
+                    "pointer_expression" => self.pointee(value.child_by_field_name("argument")?),
+                    _ => None,
+                }
+            }
+        }
+        self.types.eval(expr)
+    }
+
+    fn derive(&self, value: Node<'t>, state: &State<'t>) -> Option<Derived<'t>> {
+        let value = strip(value);
+        match value.kind() {
+            "identifier" => {
+                let var = text(value, self.code);
+                if state.ptrs.contains_key(var) {
+                    Some(Derived::Copy(var))
+                } else if state.ends.contains(var) {
+                    Some(Derived::End)
+                } else {
+                    None
+                }
+            },
+            "field_expression" if is_ctx_field(value, self.code, "data") => Some(Derived::Start),
+            "field_expression" if is_ctx_field(value, self.code, "data_end") => Some(Derived::End),
+            "binary_expression" => {
+                let op = value.child_by_field_name("operator")?;
+                let left = value.child_by_field_name("left")?;
+                let right = value.child_by_field_name("right")?;
+                let offset = match op.kind() {
+                    // Pointer arithmetic is in units of the pointee.
+                    "+" => self
+                        .eval(right)
+                        .and_then(|count| count.checked_mul(self.pointee(left).unwrap_or(1))),
+                    "-" => None,
+                    _ => return None,
+                };
+
+                match self.derive(left, state)? {
+                    Derived::Start => Some(Derived::Offset(None, offset)),
+                    Derived::Copy(var) => Some(Derived::Offset(
+                        state.ptrs.get(var).map(|ptr| ptr.root),
+                        offset,
+                    )),
+                    Derived::Offset(parent, base) => Some(Derived::Offset(
+                        parent,
+                        base.zip(offset)
+                            .and_then(|(base, offset)| base.checked_add(offset)),
+                    )),
+                    Derived::End => None,
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+
+/// Tracks packet pointers, recording unchecked accesses.
+struct Flow<'a, 'p, 't> {
+    program: &'a Program<'p, 't>,
+    state: &'a mut State<'t>,
+    accesses: &'a mut Vec<Access<'t>>,
+}
+
+impl<'t> PointerFlow<'t> for Flow<'_, '_, 't> {
+    fn assign(&mut self, var: &'t str, value: Node<'t>) {
+        let derived = self.program.derive(value, self.state);
+        let copied = match derived {
+            Some(Derived::Copy(other)) => self.state.ptrs.get(other).copied(),
+            _ => None,
+        };
+        let _ptr = self.state.ptrs.remove(var);
+        let _end = self.state.ends.remove(var);
+
+        let ptr = match derived {
+            Some(Derived::Start) => Ptr {
+                root: var,
+                parent: None,
+                checked: 0,
+                origin: value,
+            },
+            Some(Derived::Offset(parent, offset)) => {
+                // Whatever was checked beyond the offset for the
+                // pointer derived from is in bounds for the new one
+                // as well, e.g., when deriving a header pointer only
+                // after a combined check of multiple headers.
+                let checked = parent
+                    .zip(offset)
+                    .and_then(|(parent, offset)| {
+                        self.state
+                            .ptrs
+                            .values()
+                            .filter(|ptr| ptr.root == parent)
+                            .map(|ptr| ptr.checked.saturating_sub(offset))
+                            .max()
+                    })
+                    .unwrap_or(0);
+                Ptr {
+                    root: var,
+                    parent: parent.map(|parent| (parent, offset)),
+                    checked,
+                    origin: value,
+                }
+            },
+            Some(Derived::Copy(_other)) => match copied {
+                Some(ptr) => Ptr {
+                    origin: value,
+                    ..ptr
+                },
+                None => return,
+            },
+            Some(Derived::End) => {
+                let _new = self.state.ends.insert(var);
+                return
+            },
+            None => return,
+        };
+        let _prev = self.state.ptrs.insert(var, ptr);
+    }
+
+    fn modify(&mut self, var: &'t str) {
+        // E.g., `ptr += off`; the pointer is no longer known to be
+        // checked.
+        if let Some(ptr) = self.state.ptrs.get_mut(var) {
+            ptr.parent = Some((ptr.root, None));
+            ptr.root = var;
+            ptr.checked = 0;
+        }
+    }
+
+    fn deref(&mut self, expr: Node<'t>, var: &'t str) {
+        let size = self.program.pointees.get(var).copied().unwrap_or(1);
+        if let Some(ptr) = self
+            .state
+            .ptrs
+            .get_mut(var)
+            .filter(|ptr| ptr.checked < size)
+        {
+            let () = self.accesses.push(Access {
+                expr,
+                var,
+                origin: ptr.origin,
+                size,
+                checked: ptr.checked,
+            });
+            // Report each pointer only once.
+            ptr.checked = u64::MAX;
+        }
+    }
+}
+
+
+/// Compute the state after executing `nodes`.
+fn transfer<'t>(
+    nodes: &[Node<'t>],
+    program: &Program<'_, 't>,
+    state: &State<'t>,
+    accesses: &mut Vec<Access<'t>>,
+) -> State<'t> {
+    let mut state = state.clone();
+    let mut flow = Flow {
+        program,
+        state: &mut state,
+        accesses,
+    };
+    for node in nodes {
+        let () = track_pointers(*node, program.code, &mut flow);
+    }
+    state
+}
+
+
+/// Refine the state for control flowing along `edge`.
+fn refine<'t>(edge: &Edge<'t>, program: &Program<'_, 't>, state: &State<'t>) -> State<'t> {
+    let (cond, taken) = match edge.cond {
+        Cond::Always => return state.clone(),
+        Cond::True(cond) => (cond, true),
+        Cond::False(cond) => (cond, false),
+    };
+
+    let cond = strip(cond);
+    let (Some(op), Some(left), Some(right)) = (
+        cond.child_by_field_name("operator"),
+        cond.child_by_field_name("left"),
+        cond.child_by_field_name("right"),
+    ) else {
+        return state.clone()
+    };
+
+    let is_end = |node| matches!(program.derive(node, state), Some(Derived::End));
+    // Figure out the packet pointer expression and whether it is in
+    // bounds if the comparison is true.
+    let (expr, in_bounds) = match op.kind() {
+        ">" | ">=" if is_end(right) => (left, false),
+        "<" | "<=" if is_end(right) => (left, true),
+        ">" | ">=" if is_end(left) => (right, true),
+        "<" | "<=" if is_end(left) => (right, false),
+        _ => return state.clone(),
+    };
+    if in_bounds != taken {
+        return state.clone()
+    }
+
+    // The bytes up to the compared pointer (e.g., `data + sizeof(*eth)`
+    // in `data + sizeof(*eth) > data_end`) are within bounds now, for
+    // the pointer it is derived from as well as for exact copies of
+    // it. Pointers that one was in turn derived from (by adding an
+    // offset) are at lower addresses and, hence, within bounds as
+    // well. Offsets we can't make sense of we give the benefit of the
+    // doubt.
+    let (mut root, checked) = match program.derive(expr, state) {
+        Some(Derived::Copy(var)) => match state.ptrs.get(var) {
+            Some(ptr) => (ptr.root, 0),
+            None => return state.clone(),
+        },
+        Some(Derived::Offset(Some(root), offset)) => (root, offset.unwrap_or(u64::MAX)),
+        _ => return state.clone(),
+    };
+
+    let mut state = state.clone();
+    let mut checked = checked;
+    let mut visited = BTreeSet::new();
+    while visited.insert(root) {
+        for ptr in state.ptrs.values_mut() {
+            if ptr.root == root {
+                ptr.checked = ptr.checked.max(checked);
+            }
+        }
+
+        let Some((parent, offset)) = state.ptrs.get(root).and_then(|ptr| ptr.parent) else {
+            break
+        };
+        checked = checked.saturating_add(offset.unwrap_or(0));
+        root = parent;
+    }
+
+    // Pointers derived from checked ones before the check benefit from
+    // it as well, as far as the check reaches beyond their offset.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        let ptrs = state.ptrs.values().copied().collect::<Vec<_>>();
+        for ptr in state.ptrs.values_mut() {
+            let Some((parent, Some(offset))) = ptr.parent else {
+                continue
+            };
+            let checked = ptrs
+                .iter()
+                .filter(|other| other.root == parent)
+                .map(|other| other.checked.saturating_sub(offset))
+                .max()
+                .unwrap_or(0);
+            if checked > ptr.checked {
+                ptr.checked = checked;
+                changed = true;
+            }
+        }
+    }
+    state
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    let types = Types::new(cx.tree, cx.code);
+
+    for program in programs(cx.tree) {
+        let section = section(program, cx.code).unwrap_or_default();
+        if !is_packet_program(&section) {
+            continue
+        }
+
+        let mut pointees = HashMap::new();
+        let decls = descendants(program)
+            .filter(|node| matches!(node.kind(), "declaration" | "parameter_declaration"));
+        for decl in decls {
+            let Some(ty) = decl.child_by_field_name("type") else {
+                continue
+            };
+            let mut cursor = decl.walk();
+            for declarator in decl.children_by_field_name("declarator", &mut cursor) {
+                if let (Some(layout), Some(name)) =
+                    (types.pointee(ty, declarator), declarator_name(declarator))
+                {
+                    let _prev = pointees.insert(cx.text(name), layout.size);
+                }
+            }
+        }
+        let program_info = Program {
+            code: cx.code,
+            types: &types,
+            pointees,
+        };
+
+        let cfg = Cfg::new(program, cx.code);
+        let states = cfg.forward(
+            State::default(),
+            |block, state| transfer(&block.nodes, &program_info, state, &mut Vec::new()),
+            |edge, state| refine(edge, &program_info, state),
+            State::join,
+        );
+
+        let mut accesses = Vec::new();
+        for (block, state) in cfg.blocks.iter().zip(&states) {
+            if let Some(state) = state {
+                let _state = transfer(&block.nodes, &program_info, state, &mut accesses);
+            }
+        }
+
+        for access in accesses {
+            let Access {
+                expr,
+                var,
+                origin,
+                size,
+                checked,
+            } = access;
+            let message = if checked == 0 {
+                format!(
+                    "packet access via `{var}` is not preceded by a bounds check against `data_end`"
+                )
+            } else {
+                format!(
+                    "packet access via `{var}` covers {size} bytes, but only {checked} are bounds checked against `data_end`"
+                )
+            };
+            let mut m = cx.match_msg(expr.range(), message);
+            let () = m.labels.push(label(
+                origin,
+                format!("`{var}` points into the packet here"),
+            ));
+            let () = matches.push(m);
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,341 @@
+//! The `unpaired-ringbuf-reserve` lint.
+//!
+//! Every successful `bpf_ringbuf_reserve` has to be followed by exactly
+//! one `bpf_ringbuf_submit` or `bpf_ringbuf_discard` on every path
+//! through the program. Otherwise the verifier rejects it with an
+//! "unreleased reference" error, which is hard to map back to the
+//! source. Accessing a record after it got released is rejected as
+//! well.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::cfg::Cfg;
+use crate::cfg::Cond;
+use crate::cfg::Edge;
+
+use super::Context;
+use super::PointerFlow;
+use super::call_name;
+use super::edge_null_check;
+use super::functions;
+use super::label;
+use super::strip;
+use super::text;
+use super::track_pointers;
+
+
+pub(crate) const NAME: &str = "unpaired-ringbuf-reserve";
+pub(crate) const MESSAGE: &str = "ring buffer reservation is not submitted or discarded on all paths; the verifier will reject the program with an unreleased reference error";
+
+const RESERVE: &str = "bpf_ringbuf_reserve";
+/// Helpers releasing a reservation.
+static RELEASE_HELPERS: [&str; 2] = ["bpf_ringbuf_discard", "bpf_ringbuf_submit"];
+
+
+/// The status of a reservation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Reservation<'t> {
+    /// The `bpf_ringbuf_reserve` call.
+    reserve: Node<'t>,
+    /// Whether the reservation may still be unreleased.
+    live: bool,
+    /// The call that may have released the reservation already, if
+    /// any.
+    released: Option<Node<'t>>,
+}
+
+
+/// The state tracked throughout a function.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct State<'t> {
+    /// Variables referring to a reservation, mapped to the reservation
+    /// (identified by the reserve call's node ID).
+    vars: BTreeMap<&'t str, usize>,
+    /// All reservations, by the reserve call's node ID.
+    reservations: BTreeMap<usize, Reservation<'t>>,
+    /// Variables whose use after release got reported already.
+    reported: BTreeSet<&'t str>,
+}
+
+impl State<'_> {
+    fn join(&self, other: &Self) -> Self {
+        let mut state = self.clone();
+        for (var, id) in &other.vars {
+            let _id = state.vars.entry(*var).or_insert(*id);
+        }
+        for (id, other) in &other.reservations {
+            let reservation = state.reservations.entry(*id).or_insert(*other);
+            reservation.live |= other.live;
+            reservation.released = reservation.released.or(other.released);
+        }
+        let () = state.reported.extend(&other.reported);
+        state
+    }
+}
+
+
+/// An invalid use of a reservation.
+enum Misuse<'t> {
+    /// A reservation is released more than once.
+    DoubleRelease {
+        call: Node<'t>,
+        var: &'t str,
+        prev: Node<'t>,
+    },
+    /// A reservation is accessed after having been released.
+    UseAfterRelease {
+        expr: Node<'t>,
+        var: &'t str,
+        release: Node<'t>,
+    },
+}
+
+
+/// Describe the kind of release performed by a call.
+fn released<'c>(release: Node<'_>, code: &'c [u8]) -> &'c str {
+    match call_name(release, code) {
+        Some("bpf_ringbuf_discard") => "discarded",
+        _ => "submitted",
+    }
+}
+
+
+/// Tracks reservations, recording misuses of them.
+struct Flow<'a, 't> {
+    code: &'t [u8],
+    state: &'a mut State<'t>,
+    misuses: &'a mut Vec<Misuse<'t>>,
+}
+
+impl<'t> PointerFlow<'t> for Flow<'_, 't> {
+    fn assign(&mut self, var: &'t str, value: Node<'t>) {
+        let value = strip(value);
+        let id = if call_name(value, self.code) == Some(RESERVE) {
+            let reservation = Reservation {
+                reserve: value,
+                live: true,
+                released: None,
+            };
+            let _prev = self.state.reservations.insert(value.id(), reservation);
+            Some(value.id())
+        } else if value.kind() == "identifier" {
+            self.state.vars.get(text(value, self.code)).copied()
+        } else {
+            None
+        };
+
+        let _reported = self.state.reported.remove(var);
+        if let Some(id) = id {
+            let _prev = self.state.vars.insert(var, id);
+        } else {
+            let _prev = self.state.vars.remove(var);
+        }
+    }
+
+    fn modify(&mut self, var: &'t str) {
+        let _prev = self.state.vars.remove(var);
+    }
+
+    fn deref(&mut self, expr: Node<'t>, var: &'t str) {
+        let release = self
+            .state
+            .vars
+            .get(var)
+            .and_then(|id| self.state.reservations.get(id))
+            .and_then(|reservation| reservation.released);
+        // Report each variable only once, but keep tracking it, so that
+        // a subsequent release is still checked.
+        if let Some(release) = release.filter(|_| self.state.reported.insert(var)) {
+            let () = self
+                .misuses
+                .push(Misuse::UseAfterRelease { expr, var, release });
+        }
+    }
+
+    fn visit(&mut self, node: Node<'t>) -> bool {
+        if !call_name(node, self.code).is_some_and(|name| RELEASE_HELPERS.contains(&name)) {
+            return true
+        }
+
+        let arg = node
+            .child_by_field_name("arguments")
+            .and_then(|args| args.named_child(0))
+            .map(strip)
+            .filter(|arg| arg.kind() == "identifier");
+        let Some(arg) = arg else { return true };
+
+        let var = text(arg, self.code);
+        if let Some(reservation) = self
+            .state
+            .vars
+            .get(var)
+            .and_then(|id| self.state.reservations.get_mut(id))
+        {
+            if let Some(prev) = reservation.released {
+                let () = self.misuses.push(Misuse::DoubleRelease {
+                    call: node,
+                    var,
+                    prev,
+                });
+            }
+            reservation.live = false;
+            reservation.released = Some(node);
+        }
+        // The pointer itself is just passed along, not dereferenced.
+        false
+    }
+}
+
+
+/// Compute the state after executing `nodes`.
+fn transfer<'t>(
+    nodes: &[Node<'t>],
+    code: &'t [u8],
+    state: &State<'t>,
+    misuses: &mut Vec<Misuse<'t>>,
+) -> State<'t> {
+    let mut state = state.clone();
+    let mut flow = Flow {
+        code,
+        state: &mut state,
+        misuses,
+    };
+    for node in nodes {
+        let () = track_pointers(*node, code, &mut flow);
+    }
+    state
+}
+
+
+/// Refine the state for control flowing along `edge`.
+fn refine<'t>(edge: &Edge<'t>, code: &'t [u8], state: &State<'t>) -> State<'t> {
+    let mut state = state.clone();
+    // If the reservation failed there is nothing to release.
+    if let Some((var, false)) = edge_null_check(edge, code) {
+        if let Some(reservation) = state
+            .vars
+            .get(var)
+            .and_then(|id| state.reservations.get_mut(id))
+        {
+            reservation.live = false;
+        }
+    }
+    state
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+
+    for function in functions(cx.tree) {
+        let cfg = Cfg::new(function, cx.code);
+        let states = cfg.forward(
+            State::default(),
+            |block, state| transfer(&block.nodes, cx.code, state, &mut Vec::new()),
+            |edge, state| refine(edge, cx.code, state),
+            State::join,
+        );
+
+        let mut misuses = Vec::new();
+        for (block, state) in cfg.blocks.iter().zip(&states) {
+            if let Some(state) = state {
+                let _state = transfer(&block.nodes, cx.code, state, &mut misuses);
+            }
+        }
+
+        // Find the ways in which the function can be left with a
+        // reservation still being live. Starting at the exit, we walk
+        // backwards through empty blocks until we find the statement
+        // or condition that leaks the reservation.
+        let mut leaks = BTreeMap::<usize, (Node<'_>, Vec<(Node<'_>, &str)>)>::new();
+        let mut work = vec![cfg.exit];
+        let mut visited = BTreeSet::new();
+        while let Some(id) = work.pop() {
+            if !visited.insert(id) {
+                continue
+            }
+
+            let preds = cfg.blocks[id].preds.iter().collect::<BTreeSet<_>>();
+            for pred in preds {
+                let block = &cfg.blocks[*pred];
+                let Some(state) = &states[*pred] else {
+                    continue
+                };
+                let state = transfer(&block.nodes, cx.code, state, &mut Vec::new());
+
+                for edge in block.succs.iter().filter(|edge| edge.to == id) {
+                    let state = refine(edge, cx.code, &state);
+                    if !state.reservations.values().any(|res| res.live) {
+                        continue
+                    }
+
+                    let Some(node) = block.nodes.last() else {
+                        let () = work.push(*pred);
+                        continue
+                    };
+                    let reason = match edge.cond {
+                        _ if node.kind() == "return_statement" => {
+                            "the reservation is leaked if the function returns here"
+                        },
+                        Cond::True(..) => "the reservation is leaked if this condition is true",
+                        Cond::False(..) => "the reservation is leaked if this condition is false",
+                        Cond::Always => "the reservation is leaked if the function ends after this",
+                    };
+
+                    for (res_id, reservation) in &state.reservations {
+                        if reservation.live {
+                            let (_reserve, exits) = leaks
+                                .entry(*res_id)
+                                .or_insert_with(|| (reservation.reserve, Vec::new()));
+                            let () = exits.push((*node, reason));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (reserve, mut exits) in leaks.into_values() {
+            let () = exits.sort_by_key(|(node, _reason)| node.start_byte());
+            let () = exits.dedup_by_key(|(node, _reason)| node.id());
+
+            let mut m = cx.r#match(reserve.range());
+            for (exit, reason) in exits {
+                let () = m.labels.push(label(exit, reason));
+            }
+            let () = matches.push(m);
+        }
+
+        for misuse in misuses {
+            let m = match misuse {
+                Misuse::DoubleRelease { call, var, prev } => {
+                    let message =
+                        format!("ring buffer reservation `{var}` is released more than once");
+                    let mut m = cx.match_msg(call.range(), message);
+                    let () = m.labels.push(label(
+                        prev,
+                        format!("`{var}` was already {} here", released(prev, cx.code)),
+                    ));
+                    m
+                },
+                Misuse::UseAfterRelease { expr, var, release } => {
+                    let what = released(release, cx.code);
+                    let message =
+                        format!("ring buffer reservation `{var}` is used after being {what}");
+                    let mut m = cx.match_msg(expr.range(), message);
+                    let () = m
+                        .labels
+                        .push(label(release, format!("`{var}` was {what} here")));
+                    m
+                },
+            };
+            let () = matches.push(m);
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,87 @@
+//! The `unreachable-code` lint.
+//!
+//! Code following an unconditional `return` can never execute and
+//! usually hints at a logic error, such as a left over debugging
+//! statement.
+
+use anyhow::Result;
+
+use tree_sitter::Node;
+
+use crate::LintMatch;
+use crate::cfg::Cfg;
+
+use super::Context;
+use super::functions;
+use super::label;
+
+
+pub(crate) const NAME: &str = "unreachable-code";
+pub(crate) const MESSAGE: &str = "unreachable code after an unconditional return statement";
+
+
+/// Find the statement that `node` (which may be the condition of a
+/// loop, for example) belongs to.
+fn statement(mut node: Node<'_>) -> Node<'_> {
+    while let Some(parent) = node.parent() {
+        if matches!(
+            parent.kind(),
+            "compound_statement" | "case_statement" | "labeled_statement" | "function_definition"
+        ) {
+            break
+        }
+        node = parent;
+    }
+    node
+}
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+
+    // Dead code is just as much a hint at a logic error in a helper
+    // function as it is in a program, so we check all functions.
+    for function in functions(cx.tree) {
+        let cfg = Cfg::new(function, cx.code);
+        let reachable = cfg.reachable();
+        let mut covered = vec![false; cfg.blocks.len()];
+
+        for (id, block) in cfg.blocks.iter().enumerate() {
+            let Some(ret) = block
+                .follows
+                .filter(|node| node.kind() == "return_statement")
+            else {
+                continue
+            };
+
+            // Search for the first non-empty block that is dead because
+            // of the `return`; everything after it is unreachable as
+            // well, but we only report once.
+            let mut stack = vec![id];
+            while let Some(id) = stack.pop() {
+                if reachable[id] || covered[id] {
+                    continue
+                }
+                covered[id] = true;
+
+                let block = &cfg.blocks[id];
+                if let Some(node) = block.nodes.first() {
+                    let mut m = cx.r#match(statement(*node).range());
+                    let () = m
+                        .labels
+                        .push(label(ret, "any code following this return is unreachable"));
+                    let () = matches.push(m);
+
+                    let () = cfg
+                        .reachable_from(id)
+                        .into_iter()
+                        .zip(covered.iter_mut())
+                        .for_each(|(reachable, covered)| *covered |= reachable);
+                } else {
+                    let () = stack.extend(block.succs.iter().map(|edge| edge.to));
+                }
+            }
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,44 @@
+//! The `unreachable-function` lint.
+//!
+//! Static functions that are neither called nor otherwise referenced
+//! (e.g., as callbacks) by any BPF program are dead code. Files not
+//! containing any programs, such as shared headers, are not checked,
+//! as their functions are meant to be used elsewhere.
+
+use anyhow::Result;
+
+use crate::LintMatch;
+use crate::callgraph::CallGraph;
+
+use super::Context;
+
+
+pub(crate) const NAME: &str = "unreachable-function";
+pub(crate) const MESSAGE: &str = "static function is not reachable from any BPF program";
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let graph = CallGraph::new(cx.tree, cx.code);
+    if !graph
+        .functions
+        .iter()
+        .any(|function| function.section.is_some())
+    {
+        return Ok(Vec::new())
+    }
+
+    let matches = graph
+        .functions
+        .iter()
+        .zip(graph.reachable())
+        .filter(|(function, reachable)| function.is_static && !reachable)
+        .map(|(function, _reachable)| {
+            let message = format!(
+                "static function `{}` is not reachable from any BPF program",
+                function.name
+            );
+            cx.match_msg(function.ident.range(), message)
+        })
+        .collect();
+    Ok(matches)
+}
@@ -0,0 +1,52 @@
+//! The `variable-length-array` lint.
+//!
+//! The verifier checks a program's stack accesses against a frame of
+//! fixed size, which the compiler has to know up front. Arrays whose
+//! size is only known at run time can't be placed on such a stack and
+//! are rejected by the BPF backend.
+
+use anyhow::Result;
+
+use crate::LintMatch;
+use crate::types::declarator_name;
+
+use super::Context;
+use super::descendants;
+use super::functions;
+use super::is_constant;
+use super::locals;
+
+
+pub(crate) const NAME: &str = "variable-length-array";
+pub(crate) const MESSAGE: &str = "variable-length arrays are not supported by BPF, as the stack frame size has to be known at compile time";
+
+
+pub(crate) fn check(cx: &Context<'_>) -> Result<Vec<LintMatch>> {
+    let mut matches = Vec::new();
+    for function in functions(cx.tree) {
+        let Some(body) = function.child_by_field_name("body") else {
+            continue
+        };
+        let locals = locals(function, cx.code);
+
+        for array in descendants(body).filter(|node| node.kind() == "array_declarator") {
+            let Some(size) = array.child_by_field_name("size") else {
+                continue
+            };
+            if is_constant(size, cx.code, &locals) {
+                continue
+            }
+
+            let Some(name) = declarator_name(array) else {
+                continue
+            };
+            let message = format!(
+                "array `{}` has non-constant size `{}`; BPF programs have a fixed-size stack frame that the verifier checks at load time, so variable-length arrays are not supported; use a fixed-size array sized for the maximum length instead",
+                cx.text(name),
+                cx.text(size)
+            );
+            let () = matches.push(cx.match_msg(array.range(), message));
+        }
+    }
+    Ok(matches)
+}
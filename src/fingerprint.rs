@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::LintMatch;
+use crate::lines::Lines;
+
+
+/// Hash `data` using the 64 bit FNV-1a hash function.
+///
+/// Contrary to the hashers provided by the standard library, the
+/// result is guaranteed to be stable across releases, which is a
+/// requirement for fingerprints.
+fn fnv1a(data: &[&[u8]]) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    data.iter().fold(OFFSET, |hash, data| {
+        // Terminate each chunk, so that different splits of the same
+        // data hash differently.
+        data.iter().chain([&0]).fold(hash, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+        })
+    })
+}
+
+
+/// A generator of fingerprints for lint matches, as used by code
+/// scanning and code quality services for tracking findings over time.
+///
+/// A fingerprint is based on the file, the lint, its message, and the
+/// (trimmed) line a match starts on, but not on its location. It hence
+/// remains stable when unrelated code changes. Identical matches within
+/// a file are told apart by their order of occurrence, which is why
+/// matches should be fingerprinted in the order they are reported.
+#[derive(Debug, Default)]
+pub struct Fingerprints {
+    /// The number of matches seen so far, per hash.
+    occurrences: HashMap<u64, usize>,
+}
+
+impl Fingerprints {
+    /// Calculate the fingerprint of a lint match.
+    ///
+    /// - `match` is the match to fingerprint
+    /// - `code` is the source code in question, as passed to
+    ///   [`lint`][crate::lint()]
+    /// - `path` should be the path to the file to which `code`
+    ///   corresponds
+    pub fn fingerprint(&mut self, r#match: &LintMatch, code: &[u8], path: &Path) -> String {
+        // SANITY: `Lines` will always report at least a single line.
+        let line = Lines::new(code, r#match.range.bytes.start).next().unwrap();
+        let path = path.to_string_lossy();
+        let hash = fnv1a(&[
+            path.as_bytes(),
+            r#match.lint_name.as_bytes(),
+            r#match.message.as_bytes(),
+            line.trim_ascii(),
+        ]);
+        let occurrence = self.occurrences.entry(hash).or_default();
+        let fingerprint = format!("{hash:016x}:{occurrence}");
+        *occurrence += 1;
+        fingerprint
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ops;
+
+    use indoc::indoc;
+
+    use crate::Point;
+    use crate::Range;
+    use crate::Severity;
+
+
+    fn match_foo(bytes: ops::Range<usize>, row: usize, col: usize) -> LintMatch {
+        LintMatch {
+            lint_name: "foo".to_string(),
+            severity: Severity::Warning,
+            message: "foo() is called".to_string(),
+            range: Range {
+                start_point: Point { row, col },
+                end_point: Point {
+                    row,
+                    col: col + bytes.len(),
+                },
+                bytes,
+            },
+            labels: Vec::new(),
+            suppression: None,
+        }
+    }
+
+
+    /// Make sure that fingerprints do not change when a match moves,
+    /// but differ for identical matches.
+    #[test]
+    fn fingerprint_stability() {
+        let code1 = indoc! { r#"
+            int main() {
+                foo();
+            }
+        "# };
+        let code2 = indoc! { r#"
+            int main() {
+                int x = 1;
+
+                foo();
+                foo();
+            }
+        "# };
+
+        let path = Path::new("test.bpf.c");
+        let fingerprint1 =
+            Fingerprints::default().fingerprint(&match_foo(17..22, 1, 4), code1.as_bytes(), path);
+
+        let mut fingerprints = Fingerprints::default();
+        let fingerprint2 =
+            fingerprints.fingerprint(&match_foo(33..38, 3, 4), code2.as_bytes(), path);
+        let fingerprint3 =
+            fingerprints.fingerprint(&match_foo(44..49, 4, 4), code2.as_bytes(), path);
+        assert_eq!(fingerprint1, fingerprint2);
+        assert_ne!(fingerprint2, fingerprint3);
+
+        let fingerprint4 = Fingerprints::default().fingerprint(
+            &match_foo(17..22, 1, 4),
+            code1.as_bytes(),
+            Path::new("other.bpf.c"),
+        );
+        assert_ne!(fingerprint1, fingerprint4);
+    }
+}
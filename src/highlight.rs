@@ -0,0 +1,319 @@
+//! Syntax highlighting of source code snippets.
+
+use std::ops;
+
+use anyhow::Context as _;
+use anyhow::Result;
+
+use tree_sitter::Query;
+use tree_sitter::QueryCursor;
+use tree_sitter::StreamingIterator as _;
+use tree_sitter::Tree;
+use tree_sitter_bpf_c::LANGUAGE;
+
+use crate::lint::parse;
+
+
+/// The default query used for syntax highlighting.
+///
+/// It captures keywords (`@keyword`), string and character literals
+/// (`@string`), comments (`@comment`), `SEC()` specifiers
+/// (`@attribute`), and calls to BPF helpers (`@function.helper`).
+pub const HIGHLIGHT_QUERY: &str = include_str!("highlights.scm");
+
+
+/// A color, out of the basic palette supported by virtually all
+/// terminals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    /// Red.
+    Red,
+    /// Green.
+    Green,
+    /// Yellow.
+    Yellow,
+    /// Blue.
+    Blue,
+    /// Magenta.
+    Magenta,
+    /// Cyan.
+    Cyan,
+    /// Gray.
+    Gray,
+}
+
+impl Color {
+    /// Retrieve the SGR parameter selecting this color as foreground
+    /// color.
+    fn ansi(&self) -> u8 {
+        match self {
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::Gray => 90,
+        }
+    }
+
+    /// Retrieve the CSS representation of this color.
+    fn css(&self) -> &'static str {
+        match self {
+            Self::Red => "#c91b00",
+            Self::Green => "#00a600",
+            Self::Yellow => "#b58900",
+            Self::Blue => "#2a6fdb",
+            Self::Magenta => "#b200b2",
+            Self::Cyan => "#00a6b2",
+            Self::Gray => "#808080",
+        }
+    }
+}
+
+
+/// The style of a piece of text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    /// The foreground color to use, if any.
+    pub color: Option<Color>,
+    /// Whether to use a bold font.
+    pub bold: bool,
+    /// Whether to use an italic font.
+    pub italic: bool,
+}
+
+impl Style {
+    /// Create the ANSI escape sequence selecting this style.
+    pub(crate) fn ansi(&self) -> String {
+        let params = [
+            self.bold.then_some(1),
+            self.italic.then_some(3),
+            self.color.as_ref().map(Color::ansi),
+        ];
+        let params = params
+            .into_iter()
+            .flatten()
+            .map(|param| param.to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+        format!("\x1b[{params}m")
+    }
+
+    /// Create the CSS declarations representing this style.
+    pub(crate) fn css(&self) -> String {
+        let mut css = Vec::new();
+        if let Some(color) = &self.color {
+            let () = css.push(format!("color: {}", color.css()));
+        }
+        if self.bold {
+            let () = css.push("font-weight: bold".to_string());
+        }
+        if self.italic {
+            let () = css.push("font-style: italic".to_string());
+        }
+        css.join("; ")
+    }
+}
+
+
+/// A theme for syntax highlighting, mapping the names of highlight
+/// query captures to styles.
+///
+/// Capture names are dot separated, with each component making the
+/// name more specific. When looking up the style of a capture without
+/// an exact match, the last component is removed until a match is
+/// found. E.g., `@function.helper` falls back to the style of
+/// `@function`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Styles by capture name, without leading `@`.
+    pub styles: Vec<(String, Style)>,
+}
+
+impl Theme {
+    /// Retrieve the style to use for text captured as `capture`.
+    pub fn style(&self, capture: &str) -> Option<&Style> {
+        let mut capture = capture;
+        loop {
+            if let Some((_, style)) = self.styles.iter().find(|(name, _)| name == capture) {
+                break Some(style)
+            }
+            let (prefix, _) = capture.rsplit_once('.')?;
+            capture = prefix;
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let style = |color| Style {
+            color: Some(color),
+            ..Default::default()
+        };
+
+        Self {
+            styles: vec![
+                ("attribute".to_string(), style(Color::Yellow)),
+                (
+                    "comment".to_string(),
+                    Style {
+                        italic: true,
+                        ..style(Color::Gray)
+                    },
+                ),
+                ("function".to_string(), style(Color::Cyan)),
+                ("keyword".to_string(), style(Color::Magenta)),
+                ("string".to_string(), style(Color::Green)),
+            ],
+        }
+    }
+}
+
+
+/// A syntax highlighter for a piece of source code.
+#[derive(Debug)]
+pub(crate) struct Highlighter<'src> {
+    /// The source code in question.
+    code: &'src [u8],
+    /// The syntax tree of `code`.
+    tree: Tree,
+    /// The compiled highlight query.
+    query: Query,
+    /// The style of each of the query's captures, if any.
+    styles: Vec<Option<Style>>,
+}
+
+impl<'src> Highlighter<'src> {
+    /// Create a highlighter for `code`, using the highlight query
+    /// `query` and `theme`.
+    pub fn new(code: &'src [u8], query: &str, theme: &Theme) -> Result<Self> {
+        let tree = parse(code)?;
+        let query = Query::new(&LANGUAGE.into(), query)
+            .with_context(|| "failed to compile highlight query")?;
+        let styles = query
+            .capture_names()
+            .iter()
+            .map(|name| theme.style(name).copied())
+            .collect();
+
+        Ok(Self {
+            code,
+            tree,
+            query,
+            styles,
+        })
+    }
+
+    /// Highlight the code in `range`, returning the styled ranges in
+    /// it, in order.
+    pub fn highlight(&self, range: ops::Range<usize>) -> Vec<(ops::Range<usize>, Style)> {
+        let mut captures = Vec::new();
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(range.clone());
+        let mut matches = cursor.matches(&self.query, self.tree.root_node(), self.code);
+        while let Some(m) = matches.next() {
+            for capture in m.captures {
+                if let Some(style) = self.styles[capture.index as usize] {
+                    let () = captures.push((m.pattern_index, capture.node.byte_range(), style));
+                }
+            }
+        }
+        // Earlier patterns take precedence over later ones.
+        let () = captures.sort_by_key(|(pattern_idx, _, _)| *pattern_idx);
+
+        let mut styles = vec![None; range.len()];
+        for (_, bytes, style) in captures {
+            let start = bytes.start.clamp(range.start, range.end) - range.start;
+            let end = bytes.end.clamp(range.start, range.end) - range.start;
+            for slot in &mut styles[start..end] {
+                if slot.is_none() {
+                    *slot = Some(style);
+                }
+            }
+        }
+
+        let mut highlights = Vec::<(ops::Range<usize>, Style)>::new();
+        for (idx, style) in styles.into_iter().enumerate() {
+            let Some(style) = style else { continue };
+            let idx = range.start + idx;
+            match highlights.last_mut() {
+                Some((bytes, last)) if bytes.end == idx && *last == style => bytes.end += 1,
+                _ => highlights.push((idx..idx + 1, style)),
+            }
+        }
+        highlights
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+
+
+    /// Check that we fall back to less specific capture names when
+    /// looking up styles.
+    #[test]
+    fn theme_lookup() {
+        let theme = Theme::default();
+        assert_eq!(theme.style("function.helper"), theme.style("function"));
+        assert!(theme.style("function").is_some());
+        assert_eq!(theme.style("variable"), None);
+        assert_eq!(theme.style("variable.parameter"), None);
+    }
+
+    /// Check that styles are mapped to ANSI escape sequences and CSS as
+    /// expected.
+    #[test]
+    fn style_conversion() {
+        let style = Style {
+            color: Some(Color::Gray),
+            bold: true,
+            italic: true,
+        };
+        assert_eq!(style.ansi(), "\x1b[1;3;90m");
+        assert_eq!(
+            style.css(),
+            "color: #808080; font-weight: bold; font-style: italic"
+        );
+        assert_eq!(Style::default().css(), "");
+    }
+
+    /// Check that we highlight source code as expected.
+    #[test]
+    fn highlighting() {
+        let code = indoc! { r#"
+            /* read it */
+            int x = bpf_probe_read(a, 1, "foo");
+            return x;
+        "# };
+        let theme = Theme::default();
+        let highlighter = Highlighter::new(code.as_bytes(), HIGHLIGHT_QUERY, &theme).unwrap();
+        let highlights = highlighter.highlight(0..code.len());
+        let highlights = highlights
+            .into_iter()
+            .map(|(bytes, style)| (&code[bytes], style))
+            .collect::<Vec<_>>();
+
+        let style = |name| *theme.style(name).unwrap();
+        assert_eq!(
+            highlights,
+            vec![
+                ("/* read it */", style("comment")),
+                ("bpf_probe_read", style("function.helper")),
+                ("\"foo\"", style("string")),
+                ("return", style("keyword")),
+            ]
+        );
+
+        // Highlights are confined to the requested range.
+        let highlights = highlighter.highlight(3..24);
+        assert_eq!(
+            highlights,
+            vec![(3..13, style("comment")), (22..24, style("function"))]
+        );
+    }
+}
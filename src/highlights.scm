@@ -0,0 +1,50 @@
+; The default query used for syntax highlighting source code snippets
+; in reports. Where captures overlap, earlier patterns take precedence.
+
+(comment) @comment
+
+(sec_specifier) @attribute
+
+(call_expression
+    function: (identifier) @function.helper
+    (#match? @function.helper "^bpf_")
+)
+
+[
+    (string_literal)
+    (char_literal)
+    (system_lib_string)
+] @string
+
+[
+    "break"
+    "case"
+    "const"
+    "continue"
+    "default"
+    "do"
+    "else"
+    "enum"
+    "extern"
+    "for"
+    "goto"
+    "if"
+    "inline"
+    "return"
+    "sizeof"
+    "static"
+    "struct"
+    "switch"
+    "typedef"
+    "union"
+    "volatile"
+    "while"
+    "#define"
+    "#elif"
+    "#else"
+    "#endif"
+    "#if"
+    "#ifdef"
+    "#ifndef"
+    "#include"
+] @keyword
@@ -21,16 +21,36 @@
 //! In the above examples, none of the instances of `bpf_probe_read`
 //! will be flagged.
 //!
+//! Instead of an individual lint, a lint category can be provided, for
+//! example `bpflint: disable=style`, to disable all lints of this
+//! category.
+//!
 //! The directive `bpflint: disable=all` acts as a catch-all, disabling
 //! reporting of all lints.
+//!
+//! Any text following the lint name is treated as a justification for
+//! disabling it, e.g., `bpflint: disable=probe-read only used on old
+//! kernels`.
+//!
+//! Conversely, lints (or categories) that are not enabled by default
+//! can be enabled for a statement or block with a comment such as
+//! `bpflint: enable=bpf-printk`. The innermost directive applying to a
+//! piece of code takes precedence.
 
 #[cfg(target_arch = "wasm32")]
 #[macro_use]
 mod redefine;
 
+mod callgraph;
+mod cfg;
+mod checks;
+mod fingerprint;
+mod highlight;
 mod lines;
 mod lint;
 mod report;
+mod sarif;
+mod types;
 
 use std::ops;
 
@@ -56,14 +76,34 @@ pub struct Range {
     pub end_point: Point,
 }
 
+pub use crate::callgraph::call_graph_json;
+pub use crate::fingerprint::Fingerprints;
+pub use crate::highlight::Color;
+pub use crate::highlight::HIGHLIGHT_QUERY;
+pub use crate::highlight::Style;
+pub use crate::highlight::Theme;
+pub use crate::lint::Category;
+pub use crate::lint::Label;
 pub use crate::lint::Lint;
+pub use crate::lint::LintCode;
 pub use crate::lint::LintMatch;
+pub use crate::lint::LintOpts;
+pub use crate::lint::NativeCheck;
+pub use crate::lint::Scope;
+pub use crate::lint::Severity;
+pub use crate::lint::Suppression;
 pub use crate::lint::builtin_lints;
 pub use crate::lint::lint;
 pub use crate::lint::lint_custom;
+pub use crate::lint::lint_custom_opts;
+pub use crate::lint::lint_opts;
 pub use crate::report::Opts;
+pub use crate::report::report_html;
+pub use crate::report::report_json;
 pub use crate::report::report_terminal;
+pub use crate::report::report_terminal_all;
 pub use crate::report::report_terminal_opts;
+pub use crate::sarif::SarifLog;
 
 
 #[cfg(target_arch = "wasm32")]
@@ -79,8 +119,9 @@ mod wasm {
     use super::*;
 
     /// Lint source code `code` representing a file at `path` and
-    /// produce a report, end-to-end. `context` describes the number of
-    /// lines of source code context to include in the report.
+    /// produce a syntax highlighted HTML report, end-to-end. `context`
+    /// describes the number of lines of source code context to include
+    /// in the report.
     ///
     /// This function exists mostly because exposing something like our
     /// `LintMatch` type across WASM's ABI is a major PITA and our
@@ -91,6 +132,8 @@ mod wasm {
         fn lint_impl(code: Vec<u8>, path: PathBuf, context: u8) -> Result<String, Error> {
             let opts = Opts {
                 extra_lines: (context, context),
+                hyperlinks: true,
+                highlight_query: Some(HIGHLIGHT_QUERY.to_string()),
                 ..Default::default()
             };
             let mut first = true;
@@ -103,7 +146,7 @@ mod wasm {
                     first = false;
                 }
 
-                let () = report_terminal_opts(&m, &code, &path, &opts, &mut report)?;
+                let () = report_html(&m, &code, &path, &opts, &mut report)?;
             }
             let report =
                 String::from_utf8(report).context("generated report contains invalid UTF-8")?;
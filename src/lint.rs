@@ -1,7 +1,12 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
 use std::str;
+use std::str::FromStr;
 
 use anyhow::Context as _;
 use anyhow::Result;
+use anyhow::anyhow;
 
 use tracing::warn;
 
@@ -15,6 +20,10 @@ use tree_sitter_bpf_c::LANGUAGE;
 
 use crate::Point;
 use crate::Range;
+use crate::checks;
+use crate::checks::Context;
+use crate::checks::descendants;
+use crate::checks::function_name;
 
 
 mod lints {
@@ -45,16 +54,197 @@ impl From<tree_sitter::Range> for Range {
 }
 
 
+/// A lint check implemented natively, in Rust.
+///
+/// Native checks are used for lints that can't be expressed as a
+/// [tree-sitter query][query] alone, for example because they need to
+/// correlate information from different parts of a file. They are only
+/// available as part of the [built-in lints][builtin_lints].
+///
+/// [query]: https://tree-sitter.github.io/tree-sitter/using-parsers/queries/
+#[derive(Clone, Copy, Debug)]
+pub struct NativeCheck(pub(crate) fn(&Context<'_>) -> Result<Vec<LintMatch>>);
+
+
+/// The scope in which an [absence lint][LintCode::Absence] requires
+/// its pattern to be present.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Scope {
+    /// The pattern has to be present somewhere in the file. Absence is
+    /// reported at the start of the file.
+    File,
+    /// The pattern has to be present in each function definition.
+    /// Absence is reported at the function's name.
+    Function,
+}
+
+
+/// The source code of a lint.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum LintCode {
+    /// A [tree-sitter query][query], with each non-internal capture
+    /// being reported as a match.
+    ///
+    /// [query]: https://tree-sitter.github.io/tree-sitter/using-parsers/queries/
+    Query(String),
+    /// A [tree-sitter query][query] that is required to match inside
+    /// each instance of `scope`, with a match being reported if it
+    /// does not.
+    ///
+    /// Query patterns containing a capture named `@trigger` act as
+    /// preconditions: if present, the remaining patterns are only
+    /// required in scopes in which a trigger matched and absence is
+    /// reported at each of the triggers.
+    ///
+    /// [query]: https://tree-sitter.github.io/tree-sitter/using-parsers/queries/
+    Absence {
+        /// The query describing the required pattern.
+        query: String,
+        /// The scope in which the pattern is required.
+        scope: Scope,
+    },
+    /// A check implemented natively.
+    Native(NativeCheck),
+}
+
+
+/// The category a lint belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Category {
+    /// Lints flagging code that is wrong or gets rejected by the
+    /// verifier.
+    Correctness,
+    /// Lints flagging usage of deprecated functionality.
+    Deprecated,
+    /// Lints suggesting more modern replacements for working code.
+    Modernization,
+    /// Lints flagging code that may break across kernel versions or
+    /// configurations.
+    Portability,
+    /// Lints flagging code with suboptimal performance.
+    Performance,
+    /// Lints auditing the use of security sensitive functionality, for
+    /// example as part of a security review.
+    ///
+    /// Lints of this category are disabled by default.
+    Security,
+    /// Lints flagging stylistic issues.
+    Style,
+}
+
+impl Category {
+    /// All lint categories.
+    const ALL: [Self; 7] = [
+        Self::Correctness,
+        Self::Deprecated,
+        Self::Modernization,
+        Self::Portability,
+        Self::Performance,
+        Self::Security,
+        Self::Style,
+    ];
+
+    /// Retrieve the category's name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Correctness => "correctness",
+            Self::Deprecated => "deprecated",
+            Self::Modernization => "modernization",
+            Self::Portability => "portability",
+            Self::Performance => "performance",
+            Self::Security => "security",
+            Self::Style => "style",
+        }
+    }
+
+    /// Check whether lints of this category are enabled by default.
+    pub fn is_default_enabled(&self) -> bool {
+        match self {
+            Self::Security => false,
+            Self::Correctness
+            | Self::Deprecated
+            | Self::Modernization
+            | Self::Portability
+            | Self::Performance
+            | Self::Style => true,
+        }
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Category {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.name() == s)
+            .ok_or_else(|| {
+                let names = Self::ALL
+                    .iter()
+                    .map(|category| format!("`{category}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                anyhow!("unknown lint category `{s}`; expected one of {names}")
+            })
+    }
+}
+
+
+/// The severity of a lint's matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Severity {
+    /// The match indicates a definite problem.
+    Error,
+    /// The match indicates a likely problem.
+    Warning,
+    /// The match is informational only.
+    Note,
+}
+
+impl Severity {
+    /// Retrieve the severity's name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Note => "note",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.name())
+    }
+}
+
+
 /// The representation of a lint.
 #[derive(Clone, Debug)]
 pub struct Lint {
     /// The lint's name.
     pub name: String,
-    /// The lints source code in the form of a [tree-sitter
-    /// query][query].
+    /// The lint's source code.
+    pub code: LintCode,
+    /// The category the lint belongs to.
+    pub category: Category,
+    /// The severity of the lint's matches.
+    pub severity: Severity,
+    /// Whether the lint is run by default.
     ///
-    /// [query]: https://tree-sitter.github.io/tree-sitter/using-parsers/queries/
-    pub code: String,
+    /// Lints that are not enabled by default can be enabled via
+    /// [`LintOpts`] or an inline `bpflint: enable=<lint>` comment.
+    pub default_enabled: bool,
     /// The message reported in a [`LintMatch`][LintMatch::message].
     pub message: String,
 }
@@ -67,13 +257,76 @@ impl AsRef<Lint> for Lint {
 }
 
 
+/// Determine the severity of a built-in lint of the given category.
+///
+/// Security audit findings are informational, everything else is
+/// reported as a warning.
+fn builtin_severity(category: Category) -> Severity {
+    match category {
+        Category::Security => Severity::Note,
+        _ => Severity::Warning,
+    }
+}
+
 /// Retrieve the list of lints shipped with the library.
 pub fn builtin_lints() -> impl ExactSizeIterator<Item = Lint> + DoubleEndedIterator + Clone {
-    lints::LINTS.iter().map(|(name, code, message)| Lint {
-        name: name.to_string(),
-        code: code.to_string(),
-        message: message.to_string(),
-    })
+    let queries = lints::LINTS
+        .iter()
+        .map(|(name, code, message, category, default_enabled)| Lint {
+            name: name.to_string(),
+            code: LintCode::Query(code.to_string()),
+            category: *category,
+            severity: builtin_severity(*category),
+            default_enabled: *default_enabled && category.is_default_enabled(),
+            message: message.to_string(),
+        });
+    let natives = checks::CHECKS
+        .iter()
+        .map(|(name, check, message, category)| Lint {
+            name: name.to_string(),
+            code: LintCode::Native(NativeCheck(*check)),
+            category: *category,
+            severity: builtin_severity(*category),
+            default_enabled: category.is_default_enabled(),
+            message: message.to_string(),
+        });
+    queries.chain(natives).collect::<Vec<_>>().into_iter()
+}
+
+/// Retrieve the URL of the documentation of the built-in lint with the
+/// given name, i.e., of its source code.
+pub(crate) fn builtin_lint_url(name: &str) -> Option<String> {
+    const BASE_URL: &str = "https://github.com/d-e-s-o/bpflint/blob/main";
+
+    if lints::LINTS.iter().any(|(lint, ..)| *lint == name) {
+        Some(format!("{BASE_URL}/lints/{name}.scm"))
+    } else if checks::CHECKS.iter().any(|(lint, ..)| *lint == name) {
+        let module = name.replace('-', "_");
+        Some(format!("{BASE_URL}/src/checks/{module}.rs"))
+    } else {
+        None
+    }
+}
+
+
+/// A secondary code location that is related to a lint match.
+#[derive(Clone, Debug)]
+pub struct Label {
+    /// The code range the label refers to.
+    pub range: Range,
+    /// A message explaining how the range relates to the match.
+    pub message: String,
+}
+
+
+/// Details about the suppression of a lint match by a `bpflint:
+/// disable` comment.
+#[derive(Clone, Debug)]
+pub struct Suppression {
+    /// The code range of the comment containing the directive.
+    pub range: Range,
+    /// The justification following the directive's target, if any.
+    pub justification: Option<String>,
 }
 
 
@@ -82,36 +335,161 @@ pub fn builtin_lints() -> impl ExactSizeIterator<Item = Lint> + DoubleEndedItera
 pub struct LintMatch {
     /// The name of the lint that matched.
     pub lint_name: String,
+    /// The severity of the match.
+    pub severity: Severity,
     /// The lint's message.
     pub message: String,
     /// The code range that triggered the lint.
     pub range: Range,
+    /// Secondary code locations related to the match, if any.
+    pub labels: Vec<Label>,
+    /// The suppression of the match, if it is disabled by a comment.
+    ///
+    /// Suppressed matches are only reported if
+    /// [`LintOpts::report_suppressed`] is set.
+    pub suppression: Option<Suppression>,
+}
+
+
+/// Configuration options for linting.
+#[derive(Clone, Debug)]
+pub struct LintOpts {
+    /// The stack size, in bytes, above which the `stack-usage` lint
+    /// reports a program.
+    pub stack_limit: u64,
+    /// Lint categories to enable in addition to those enabled by
+    /// default.
+    pub enabled_categories: Vec<Category>,
+    /// Lint categories to disable. Takes precedence over
+    /// [`enabled_categories`][LintOpts::enabled_categories].
+    pub disabled_categories: Vec<Category>,
+    /// Names of lints to enable, even if disabled by default or
+    /// belonging to a category that is not enabled. Lints belonging
+    /// to any of [`disabled_categories`][LintOpts::disabled_categories]
+    /// stay disabled.
+    pub enabled_lints: Vec<String>,
+    /// Whether to report matches suppressed by a `bpflint: disable`
+    /// comment, with [`LintMatch::suppression`] set, instead of
+    /// dropping them.
+    pub report_suppressed: bool,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Default for LintOpts {
+    fn default() -> Self {
+        Self {
+            // The stack size limit enforced by the verifier.
+            stack_limit: 512,
+            enabled_categories: Vec::new(),
+            disabled_categories: Vec::new(),
+            enabled_lints: Vec::new(),
+            report_suppressed: false,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+
+/// The verdict of inline directives on a lint match.
+enum Verdict {
+    /// The match is to be reported.
+    Report,
+    /// The match is suppressed by a `bpflint: disable` directive.
+    Suppress(Suppression),
+    /// The lint is not enabled for the match.
+    Skip,
+}
+
+impl Verdict {
+    /// Apply the verdict to `match`.
+    fn apply(self, r#match: LintMatch) -> Option<LintMatch> {
+        match self {
+            Self::Report => Some(r#match),
+            Self::Suppress(suppression) => Some(LintMatch {
+                suppression: Some(suppression),
+                ..r#match
+            }),
+            Self::Skip => None,
+        }
+    }
+}
+
+
+/// A `bpflint: enable=<target>` or `bpflint: disable=<target>`
+/// directive.
+struct Directive<'c> {
+    /// Whether the directive disables (as opposed to enables) the
+    /// target.
+    disable: bool,
+    /// The targeted lint, category, or `all`.
+    target: &'c str,
+    /// The justification following the target, if any.
+    justification: Option<&'c str>,
+}
+
+impl<'c> Directive<'c> {
+    /// Parse the directive contained in `comment`, if any.
+    fn parse(comment: &'c str) -> Option<Self> {
+        // The comment node will still contain the actual comment
+        // syntax, unfortunately.
+        let comment = comment.trim_start_matches("//");
+        let comment = comment.trim_start_matches("/*");
+        let comment = comment.trim_end_matches("*/");
+        let comment = comment.trim();
+
+        let directive = comment.strip_prefix("bpflint:")?.trim();
+        let (disable, directive) = match directive.strip_prefix("disable=") {
+            Some(directive) => (true, directive),
+            None => (false, directive.strip_prefix("enable=")?),
+        };
+        // Anything following the target is considered a justification.
+        let (target, justification) = match directive.split_once(char::is_whitespace) {
+            Some((target, rest)) => (target, Some(rest.trim())),
+            None => (directive, None),
+        };
+
+        let slf = Self {
+            disable,
+            target,
+            justification: justification.filter(|justification| !justification.is_empty()),
+        };
+        Some(slf)
+    }
+
+    /// Check whether the directive applies to `lint`.
+    fn applies_to(&self, lint: &Lint) -> bool {
+        self.target == "all" || self.target == lint.name || self.target == lint.category.name()
+    }
 }
 
 
-/// Walk the syntax tree, checking if a comment node that disable the
-/// given lint is present.
-fn is_lint_disabled(lint_name: &str, mut node: Node, code: &[u8]) -> bool {
+/// Walk the syntax tree, checking whether reporting of the given lint
+/// is enabled or disabled for `node`.
+///
+/// The closest comment node with a directive enabling or disabling the
+/// lint (or its category) decides. Absent any, the lint is reported if
+/// it is `enabled` as per the options in use.
+fn verdict(lint: &Lint, mut node: Node, code: &[u8], enabled: bool) -> Verdict {
     loop {
         // Walk all previous siblings of the current node.
         if let Some(s) = node.prev_sibling() {
             if s.kind() == "comment" {
                 let comment = &code[s.start_byte()..s.end_byte()];
                 if let Ok(comment) = str::from_utf8(comment) {
-                    // The comment node will still contain the actual
-                    // comment syntax, unfortunately.
-                    let comment = comment.trim_start_matches("//");
-                    let comment = comment.trim_start_matches("/*");
-                    let comment = comment.trim_end_matches("*/");
-                    let comment = comment.trim();
-
-                    if let Some(comment) = comment.strip_prefix("bpflint:") {
-                        let directive = comment.trim();
-                        match directive.strip_prefix("disable=") {
-                            Some("all") => break true,
-                            Some(disable) if disable == lint_name => break true,
-                            _ => (),
+                    if let Some(directive) =
+                        Directive::parse(comment).filter(|directive| directive.applies_to(lint))
+                    {
+                        if !directive.disable {
+                            break Verdict::Report
                         }
+
+                        let suppression = Suppression {
+                            range: Range::from(s.range()),
+                            justification: directive.justification.map(str::to_string),
+                        };
+                        break Verdict::Suppress(suppression)
                     }
                 } else {
                     // If it's not valid UTF-8 it can't be a comment for
@@ -128,17 +506,73 @@ fn is_lint_disabled(lint_name: &str, mut node: Node, code: &[u8]) -> bool {
         // Move one level up and repeat.
         match node.parent() {
             Some(parent) => node = parent,
-            None => break false,
+            None if enabled => break Verdict::Report,
+            None => break Verdict::Skip,
+        }
+    }
+}
+
+
+/// Find the node to use for evaluating lint disabling directives for
+/// the provided code range.
+fn disable_anchor<'t>(tree: &'t Tree, range: &Range) -> Node<'t> {
+    let mut node = tree
+        .root_node()
+        .descendant_for_byte_range(range.bytes.start, range.bytes.end)
+        .unwrap_or_else(|| tree.root_node());
+
+    // An empty range at a comment (e.g., a match anchored at the start
+    // of a file) is attributed to the first subsequent non-comment
+    // node, so that directives preceding it take effect.
+    if range.bytes.is_empty() {
+        while node.kind() == "comment" {
+            match node.next_sibling() {
+                Some(next) => node = next,
+                None => break,
+            }
         }
     }
+    node
 }
 
 
-fn lint_impl(tree: &Tree, code: &[u8], lint: &Lint) -> Result<Vec<LintMatch>> {
+fn lint_native(
+    tree: &Tree,
+    code: &[u8],
+    lint: &Lint,
+    check: &NativeCheck,
+    opts: &LintOpts,
+    enabled: bool,
+) -> Result<Vec<LintMatch>> {
+    let cx = Context {
+        tree,
+        code,
+        lint,
+        opts,
+    };
+    let matches = (check.0)(&cx)
+        .with_context(|| format!("failed to run native check for lint `{}`", lint.name))?
+        .into_iter()
+        .filter_map(|m| {
+            let anchor = disable_anchor(tree, &m.range);
+            verdict(lint, anchor, code, enabled).apply(m)
+        })
+        .collect();
+    Ok(matches)
+}
+
+
+fn lint_query(
+    tree: &Tree,
+    code: &[u8],
+    lint: &Lint,
+    lint_src: &str,
+    enabled: bool,
+) -> Result<Vec<LintMatch>> {
     let Lint {
         name: lint_name,
-        code: lint_src,
         message: lint_msg,
+        ..
     } = lint;
 
     let query =
@@ -148,10 +582,6 @@ fn lint_impl(tree: &Tree, code: &[u8], lint: &Lint) -> Result<Vec<LintMatch>> {
     let mut matches = query_cursor.matches(&query, tree.root_node(), code);
     while let Some(m) = matches.next() {
         for capture in m.captures {
-            if is_lint_disabled(lint_name, capture.node, code) {
-                continue;
-            }
-
             // SANITY: It would be a tree-sitter bug if the capture
             //         index does not map to a valid capture name.
             let capture_name = query.capture_names()[capture.index as usize];
@@ -163,10 +593,15 @@ fn lint_impl(tree: &Tree, code: &[u8], lint: &Lint) -> Result<Vec<LintMatch>> {
 
             let r#match = LintMatch {
                 lint_name: lint_name.to_string(),
+                severity: lint.severity,
                 message: lint_msg.to_string(),
                 range: Range::from(capture.node.range()),
+                labels: Vec::new(),
+                suppression: None,
             };
-            let () = results.push(r#match);
+            if let Some(r#match) = verdict(lint, capture.node, code, enabled).apply(r#match) {
+                let () = results.push(r#match);
+            }
         }
     }
 
@@ -177,6 +612,123 @@ fn lint_impl(tree: &Tree, code: &[u8], lint: &Lint) -> Result<Vec<LintMatch>> {
 }
 
 
+fn lint_absence(
+    tree: &Tree,
+    code: &[u8],
+    lint: &Lint,
+    lint_src: &str,
+    scope: Scope,
+    enabled: bool,
+) -> Result<Vec<LintMatch>> {
+    let query =
+        Query::new(&LANGUAGE.into(), lint_src).with_context(|| "failed to compile lint query")?;
+    let trigger = query.capture_index_for_name("trigger");
+    let has_trigger = trigger.is_some();
+
+    let root = tree.root_node();
+    let scopes = match scope {
+        Scope::File => vec![root],
+        Scope::Function => descendants(root)
+            .filter(|node| node.kind() == "function_definition")
+            .collect(),
+    };
+
+    let mut results = Vec::new();
+    for node in scopes {
+        let mut query_cursor = QueryCursor::new();
+        let mut triggers = Vec::new();
+        let mut present = false;
+        let mut matches = query_cursor.matches(&query, node, code);
+        while let Some(m) = matches.next() {
+            match m.captures.iter().find(|c| Some(c.index) == trigger) {
+                Some(capture) => triggers.push(capture.node),
+                None => present = true,
+            }
+        }
+
+        if present || (has_trigger && triggers.is_empty()) {
+            continue
+        }
+
+        let anchors = if has_trigger {
+            triggers
+                .into_iter()
+                .map(|trigger| (trigger, Range::from(trigger.range())))
+                .collect()
+        } else {
+            match scope {
+                Scope::File => {
+                    let range = Range {
+                        bytes: 0..0,
+                        start_point: Point::default(),
+                        end_point: Point::default(),
+                    };
+                    vec![(disable_anchor(tree, &range), range)]
+                },
+                Scope::Function => {
+                    let name = function_name(node).unwrap_or(node);
+                    vec![(name, Range::from(name.range()))]
+                },
+            }
+        };
+
+        for (anchor, range) in anchors {
+            let r#match = LintMatch {
+                lint_name: lint.name.clone(),
+                severity: lint.severity,
+                message: lint.message.clone(),
+                range,
+                labels: Vec::new(),
+                suppression: None,
+            };
+            if let Some(r#match) = verdict(lint, anchor, code, enabled).apply(r#match) {
+                let () = results.push(r#match);
+            }
+        }
+    }
+    Ok(results)
+}
+
+
+/// Parse BPF C code.
+pub(crate) fn parse(code: &[u8]) -> Result<Tree> {
+    let mut parser = Parser::new();
+    let () = parser
+        .set_language(&LANGUAGE.into())
+        .context("failed to load BPF C language parser")?;
+    let tree = parser
+        .parse(code, None)
+        .context("failed to provided source code")?;
+    Ok(tree)
+}
+
+
+/// Check whether `lint` is enabled as per `opts`.
+fn is_enabled(lint: &Lint, opts: &LintOpts) -> bool {
+    if opts.enabled_lints.contains(&lint.name) {
+        return true
+    }
+    lint.default_enabled || opts.enabled_categories.contains(&lint.category)
+}
+
+
+fn lint_impl(
+    tree: &Tree,
+    code: &[u8],
+    lint: &Lint,
+    opts: &LintOpts,
+    enabled: bool,
+) -> Result<Vec<LintMatch>> {
+    match &lint.code {
+        LintCode::Query(query) => lint_query(tree, code, lint, query, enabled),
+        LintCode::Absence { query, scope } => {
+            lint_absence(tree, code, lint, query, *scope, enabled)
+        },
+        LintCode::Native(check) => lint_native(tree, code, lint, check, opts, enabled),
+    }
+}
+
+
 /// Lint code using the provided set of lints.
 ///
 /// Matches are reported in source code order.
@@ -189,14 +741,20 @@ fn lint_impl(tree: &Tree, code: &[u8], lint: &Lint) -> Result<Vec<LintMatch>> {
 /// ```rust
 /// # use bpflint::builtin_lints;
 /// # use bpflint::lint_custom;
+/// # use bpflint::Category;
 /// # use bpflint::Lint;
+/// # use bpflint::LintCode;
+/// # use bpflint::Severity;
 /// let bpf_printk = Lint {
 ///     name: "bpf_printk-usage".to_string(),
-///     code: r#"
+///     code: LintCode::Query(r#"
 ///         (call_expression
 ///             function: (identifier) @function (#eq? @function "bpf_printk")
 ///         )
-///       "#.to_string(),
+///       "#.to_string()),
+///     category: Category::Style,
+///     severity: Severity::Warning,
+///     default_enabled: true,
 ///     message: "use bpf_printk only for debugging!".to_string(),
 /// };
 ///
@@ -206,6 +764,8 @@ fn lint_impl(tree: &Tree, code: &[u8], lint: &Lint) -> Result<Vec<LintMatch>> {
 ///         bpf_printk("context %p\n", ctx);
 ///         return 0;
 ///     }
+///
+///     char LICENSE[] SEC("license") = "GPL";
 /// "#;
 ///
 /// // We want to include the built-in lints as well, not just our
@@ -218,19 +778,58 @@ where
     I: IntoIterator<Item = L>,
     L: AsRef<Lint> + 'l,
 {
-    let mut parser = Parser::new();
-    let () = parser
-        .set_language(&LANGUAGE.into())
-        .context("failed to load BPF C language parser")?;
-    let tree = parser
-        .parse(code, None)
-        .context("failed to provided source code")?;
+    lint_custom_opts(code, lints, &LintOpts::default())
+}
+
+/// Lint code using the provided set of lints, configured as per
+/// `opts`.
+///
+/// Lints that are not [enabled by default][Lint::default_enabled] are
+/// only run if enabled via [`LintOpts::enabled_lints`],
+/// [`LintOpts::enabled_categories`], or an inline `bpflint:
+/// enable=<lint>` comment. Lints belonging to any of
+/// [`LintOpts::disabled_categories`] are not run.
+///
+/// Matches are reported in source code order.
+///
+/// - `code` is the source code in question, for example as read from a
+///   file
+/// - `lints` the lints to use for linting the provided source code
+/// - `opts` specifies the options to configure lints with
+pub fn lint_custom_opts<'l, I, L>(code: &[u8], lints: I, opts: &LintOpts) -> Result<Vec<LintMatch>>
+where
+    I: IntoIterator<Item = L>,
+    L: AsRef<Lint> + 'l,
+{
+    let tree = parse(code)?;
+    let enables = descendants(tree.root_node())
+        .filter(|node| node.kind() == "comment")
+        .filter_map(|node| str::from_utf8(&code[node.byte_range()]).ok())
+        .filter_map(Directive::parse)
+        .filter(|directive| !directive.disable)
+        .collect::<Vec<_>>();
     let mut results = Vec::new();
     for lint in lints {
-        let matches = lint_impl(&tree, code, lint.as_ref())?;
+        let lint = lint.as_ref();
+        if opts.disabled_categories.contains(&lint.category) {
+            continue
+        }
+
+        let enabled = is_enabled(lint, opts);
+        // A lint that is not enabled may still be turned on by an
+        // inline directive. Only bother running it if there is one.
+        if !enabled && !enables.iter().any(|directive| directive.applies_to(lint)) {
+            continue
+        }
+
+        let matches = lint_impl(&tree, code, lint, opts, enabled)?;
         let () = results.extend(matches);
     }
 
+    if !opts.report_suppressed {
+        let () = results.retain(|m| m.suppression.is_none());
+    }
+
     // Sort results to ensure more consistent reporting with ascending
     // lines.
     let () = results.sort_by(|match1, match2| {
@@ -256,6 +855,18 @@ pub fn lint(code: &[u8]) -> Result<Vec<LintMatch>> {
     lint_custom(code, builtin_lints())
 }
 
+/// Lint code using the default ([built-in][builtin_lints]) set of
+/// lints, configured as per `opts`.
+///
+/// Matches are reported in source code order.
+///
+/// - `code` is the source code in question, for example as read from a
+///   file
+/// - `opts` specifies the options to configure lints with
+pub fn lint_opts(code: &[u8], opts: &LintOpts) -> Result<Vec<LintMatch>> {
+    lint_custom_opts(code, builtin_lints(), opts)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -269,12 +880,17 @@ mod tests {
     fn lint_foo() -> Lint {
         Lint {
             name: "foo".to_string(),
-            code: indoc! { r#"
-                (call_expression
-                    function: (identifier) @function (#eq? @function "foo")
-                )
-            "# }
-            .to_string(),
+            code: LintCode::Query(
+                indoc! { r#"
+                    (call_expression
+                        function: (identifier) @function (#eq? @function "foo")
+                    )
+                "# }
+                .to_string(),
+            ),
+            category: Category::Correctness,
+            severity: Severity::Warning,
+            default_enabled: true,
             message: "foo".to_string(),
         }
     }
@@ -289,12 +905,17 @@ mod tests {
         "# };
         let lint = Lint {
             name: "bar".to_string(),
-            code: indoc! { r#"
-                (call_expression
-                    function: (identifier) @__function (#eq? @__function "bar")
-                )
-            "# }
-            .to_string(),
+            code: LintCode::Query(
+                indoc! { r#"
+                    (call_expression
+                        function: (identifier) @__function (#eq? @__function "bar")
+                    )
+                "# }
+                .to_string(),
+            ),
+            category: Category::Correctness,
+            severity: Severity::Warning,
+            default_enabled: true,
             message: "a message".to_string(),
         };
         let matches = lint_custom(code.as_bytes(), [lint]).unwrap();
@@ -310,13 +931,19 @@ mod tests {
                 name,
                 code,
                 message,
+                ..
             } = lint;
-            let query = Query::new(&LANGUAGE.into(), &code).unwrap();
-            assert_eq!(
-                query.pattern_count(),
-                1,
-                "lint `{name}` has too many pattern matches: only a single one is supported currently"
-            );
+            match code {
+                LintCode::Query(code) => {
+                    let query = Query::new(&LANGUAGE.into(), &code).unwrap();
+                    assert_eq!(
+                        query.pattern_count(),
+                        1,
+                        "lint `{name}` has too many pattern matches: only a single one is supported currently"
+                    );
+                },
+                LintCode::Absence { .. } | LintCode::Native(..) => (),
+            }
 
             let last = message.chars().last().unwrap();
             assert!(
@@ -339,6 +966,8 @@ mod tests {
                 bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
                 return 0;
             }
+
+            char LICENSE[] SEC("license") = "GPL";
         "# };
 
         let matches = lint(code.as_bytes()).unwrap();
@@ -346,10 +975,14 @@ mod tests {
 
         let LintMatch {
             lint_name,
+            severity,
             message,
             range,
+            labels,
+            suppression,
         } = &matches[0];
         assert_eq!(lint_name, "probe-read");
+        assert_eq!(*severity, Severity::Warning);
         assert!(
             message.starts_with("bpf_probe_read() is deprecated"),
             "{message}"
@@ -357,6 +990,8 @@ mod tests {
         assert_eq!(&code[range.bytes.clone()], "bpf_probe_read");
         assert_eq!(range.start_point, Point { row: 6, col: 4 });
         assert_eq!(range.end_point, Point { row: 6, col: 18 });
+        assert!(labels.is_empty(), "{labels:?}");
+        assert!(suppression.is_none(), "{suppression:?}");
     }
 
     /// Check that reported matches are sorted by line number.
@@ -368,12 +1003,17 @@ mod tests {
         "# };
         let lint = Lint {
             name: "bar".to_string(),
-            code: indoc! { r#"
-                (call_expression
-                    function: (identifier) @function (#eq? @function "bar")
-                )
-            "# }
-            .to_string(),
+            code: LintCode::Query(
+                indoc! { r#"
+                    (call_expression
+                        function: (identifier) @function (#eq? @function "bar")
+                    )
+                "# }
+                .to_string(),
+            ),
+            category: Category::Correctness,
+            severity: Severity::Warning,
+            default_enabled: true,
             message: "bar".to_string(),
         };
         let matches = lint_custom(code.as_bytes(), [lint_foo(), lint]).unwrap();
@@ -421,6 +1061,281 @@ mod tests {
         assert_eq!(matches.len(), 0, "{matches:?}");
     }
 
+    /// Check that we can disable lints by category.
+    #[test]
+    fn lint_category_disabling() {
+        let code = indoc! { r#"
+            /* bpflint: disable=correctness */
+            foo();
+            // bpflint: disable=style
+            foo();
+        "# };
+        let matches = lint_custom(code.as_bytes(), [lint_foo()]).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(matches[0].range.start_point, Point { row: 3, col: 0 });
+    }
+
+    /// Check that lints are enabled and disabled by category as per
+    /// the provided options.
+    #[test]
+    fn lint_category_selection() {
+        let code = indoc! { r#"
+            foo();
+        "# };
+        let lint = Lint {
+            category: Category::Security,
+            default_enabled: false,
+            ..lint_foo()
+        };
+
+        let matches = lint_custom(code.as_bytes(), [&lint]).unwrap();
+        assert_eq!(matches.len(), 0, "{matches:?}");
+
+        let mut opts = LintOpts {
+            enabled_categories: vec![Category::Security],
+            ..Default::default()
+        };
+        let matches = lint_custom_opts(code.as_bytes(), [&lint], &opts).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+
+        // Disabling takes precedence.
+        opts.disabled_categories = vec![Category::Security];
+        let matches = lint_custom_opts(code.as_bytes(), [&lint], &opts).unwrap();
+        assert_eq!(matches.len(), 0, "{matches:?}");
+
+        let opts = LintOpts {
+            disabled_categories: vec![Category::Correctness],
+            ..Default::default()
+        };
+        let matches = lint_custom_opts(code.as_bytes(), [lint_foo()], &opts).unwrap();
+        assert_eq!(matches.len(), 0, "{matches:?}");
+    }
+
+    /// Check that matches suppressed by a comment are reported, along
+    /// with the justification, if requested.
+    #[test]
+    fn suppressed_match_reporting() {
+        let code = indoc! { r#"
+            foo();
+            /* bpflint: disable=foo foo() is fine here */
+            foo();
+            // bpflint: disable=correctness
+            foo();
+        "# };
+        let matches = lint_custom(code.as_bytes(), [lint_foo()]).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert!(matches[0].suppression.is_none());
+
+        let opts = LintOpts {
+            report_suppressed: true,
+            ..Default::default()
+        };
+        let matches = lint_custom_opts(code.as_bytes(), [lint_foo()], &opts).unwrap();
+        assert_eq!(matches.len(), 3, "{matches:?}");
+        assert!(matches[0].suppression.is_none());
+
+        let suppression = matches[1].suppression.as_ref().unwrap();
+        assert_eq!(
+            suppression.justification.as_deref(),
+            Some("foo() is fine here")
+        );
+        assert_eq!(suppression.range.start_point, Point { row: 1, col: 0 });
+
+        let suppression = matches[2].suppression.as_ref().unwrap();
+        assert_eq!(suppression.justification, None);
+        assert_eq!(suppression.range.start_point, Point { row: 3, col: 0 });
+    }
+
+    /// Check that the documentation URLs of built-in lints point to
+    /// their sources.
+    #[test]
+    fn builtin_lint_urls() {
+        assert_eq!(
+            builtin_lint_url("probe-read").as_deref(),
+            Some("https://github.com/d-e-s-o/bpflint/blob/main/lints/probe-read.scm")
+        );
+        assert_eq!(
+            builtin_lint_url("stack-usage").as_deref(),
+            Some("https://github.com/d-e-s-o/bpflint/blob/main/src/checks/stack_usage.rs")
+        );
+        assert_eq!(builtin_lint_url("foobar"), None);
+    }
+
+    /// Check that lints not enabled by default can be enabled via the
+    /// provided options.
+    #[test]
+    fn lint_default_disabled() {
+        let code = indoc! { r#"
+            foo();
+        "# };
+        let lint = Lint {
+            default_enabled: false,
+            ..lint_foo()
+        };
+
+        let matches = lint_custom(code.as_bytes(), [&lint]).unwrap();
+        assert_eq!(matches.len(), 0, "{matches:?}");
+
+        let mut opts = LintOpts {
+            enabled_lints: vec!["foo".to_string()],
+            ..Default::default()
+        };
+        let matches = lint_custom_opts(code.as_bytes(), [&lint], &opts).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+
+        let matches = lint_custom_opts(code.as_bytes(), [lint_foo()], &opts).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+
+        // Disabled categories take precedence over enabled lints.
+        opts.disabled_categories = vec![Category::Correctness];
+        let matches = lint_custom_opts(code.as_bytes(), [&lint], &opts).unwrap();
+        assert_eq!(matches.len(), 0, "{matches:?}");
+    }
+
+    /// Check that we can enable lints not enabled by default via inline
+    /// comments, and that the innermost directive wins.
+    #[test]
+    fn lint_inline_enabling() {
+        let code = indoc! { r#"
+            foo();
+            // bpflint: enable=foo
+            foo();
+            /* bpflint: enable=correctness */
+            void test_fn(void) {
+                foo();
+                /* bpflint: disable=foo */
+                foo();
+            }
+        "# };
+        let lint = Lint {
+            default_enabled: false,
+            ..lint_foo()
+        };
+
+        let matches = lint_custom(code.as_bytes(), [&lint]).unwrap();
+        assert_eq!(matches.len(), 2, "{matches:?}");
+        assert_eq!(matches[0].range.start_point, Point { row: 2, col: 0 });
+        assert_eq!(matches[1].range.start_point, Point { row: 5, col: 4 });
+
+        let opts = LintOpts {
+            enabled_lints: vec!["foo".to_string()],
+            ..Default::default()
+        };
+        let matches = lint_custom_opts(code.as_bytes(), [&lint], &opts).unwrap();
+        assert_eq!(matches.len(), 3, "{matches:?}");
+    }
+
+    /// Check that absence lints with file scope work as expected.
+    #[test]
+    fn absence_file_scope() {
+        let lint = Lint {
+            name: "no-foo".to_string(),
+            code: LintCode::Absence {
+                query: indoc! { r#"
+                    (call_expression
+                        function: (identifier) @function (#eq? @function "foo")
+                    )
+                "# }
+                .to_string(),
+                scope: Scope::File,
+            },
+            category: Category::Correctness,
+            severity: Severity::Warning,
+            default_enabled: true,
+            message: "foo() is never called".to_string(),
+        };
+
+        let code = indoc! { r#"
+            void test_fn(void) {
+                foo();
+            }
+        "# };
+        let matches = lint_custom(code.as_bytes(), [&lint]).unwrap();
+        assert!(matches.is_empty(), "{matches:?}");
+
+        let code = indoc! { r#"
+            void test_fn(void) {
+                bar();
+            }
+        "# };
+        let matches = lint_custom(code.as_bytes(), [&lint]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].range.bytes, 0..0);
+
+        let code = indoc! { r#"
+            // bpflint: disable=no-foo
+            void test_fn(void) {
+                bar();
+            }
+        "# };
+        let matches = lint_custom(code.as_bytes(), [&lint]).unwrap();
+        assert!(matches.is_empty(), "{matches:?}");
+    }
+
+    /// Check that absence lints with function scope and triggers work
+    /// as expected.
+    #[test]
+    fn absence_function_scope() {
+        let code = indoc! { r#"
+            void test_fn1(void) {
+                lock();
+            }
+
+            void test_fn2(void) {
+                lock();
+                unlock();
+            }
+
+            void test_fn3(void) {
+            }
+        "# };
+
+        let lint = Lint {
+            name: "no-unlock".to_string(),
+            code: LintCode::Absence {
+                query: indoc! { r#"
+                    (call_expression
+                        function: (identifier) @function (#eq? @function "unlock")
+                    )
+                "# }
+                .to_string(),
+                scope: Scope::Function,
+            },
+            category: Category::Correctness,
+            severity: Severity::Warning,
+            default_enabled: true,
+            message: "unlock() is never called".to_string(),
+        };
+        let matches = lint_custom(code.as_bytes(), [&lint]).unwrap();
+        assert_eq!(matches.len(), 2, "{matches:?}");
+        assert_eq!(&code[matches[0].range.bytes.clone()], "test_fn1");
+        assert_eq!(&code[matches[1].range.bytes.clone()], "test_fn3");
+
+        let lint = Lint {
+            name: "unlock-missing".to_string(),
+            code: LintCode::Absence {
+                query: indoc! { r#"
+                    (call_expression
+                        function: (identifier) @trigger (#eq? @trigger "lock")
+                    )
+                    (call_expression
+                        function: (identifier) @function (#eq? @function "unlock")
+                    )
+                "# }
+                .to_string(),
+                scope: Scope::Function,
+            },
+            category: Category::Correctness,
+            severity: Severity::Warning,
+            default_enabled: true,
+            message: "lock() without unlock()".to_string(),
+        };
+        let matches = lint_custom(code.as_bytes(), [&lint]).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(&code[matches[0].range.bytes.clone()], "lock");
+        assert_eq!(matches[0].range.start_point, Point { row: 1, col: 4 });
+    }
+
     /// Check that erroneous disabling syntax is not accidentally recognized.
     #[test]
     fn lint_invalid_disabling() {
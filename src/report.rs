@@ -1,10 +1,232 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
 use std::io;
 use std::path::Path;
 
 use anyhow::Result;
 
+use serde_json::Value;
+use serde_json::json;
+
+use crate::Label;
 use crate::LintMatch;
+use crate::Point;
+use crate::Range;
+use crate::Severity;
+use crate::highlight::Color;
+use crate::highlight::Highlighter;
+use crate::highlight::Style;
+use crate::highlight::Theme;
 use crate::lines::Lines;
+use crate::lint::builtin_lint_url;
+
+
+/// The ANSI escape sequence resetting all styles.
+const RESET: &str = "\x1b[0m";
+/// The style used for emphasized text.
+const BOLD: Style = Style {
+    color: None,
+    bold: true,
+    italic: false,
+};
+/// The style used for the gutter and other decorations.
+const GUTTER: Style = Style {
+    color: Some(Color::Blue),
+    bold: true,
+    italic: false,
+};
+
+
+/// Retrieve the style used for highlighting matches of the given
+/// severity.
+fn severity_style(severity: Severity) -> Style {
+    let color = match severity {
+        Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+        Severity::Note => Color::Cyan,
+    };
+    Style {
+        color: Some(color),
+        bold: true,
+        italic: false,
+    }
+}
+
+/// Escape `s` for usage in HTML text and attribute values.
+fn escape_html(s: &str) -> Cow<'_, str> {
+    if !s.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(s)
+    }
+
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+
+/// The markup used for styling a report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Markup {
+    /// Plain text, without any styling.
+    None,
+    /// Text styled using ANSI escape sequences.
+    Ansi,
+    /// HTML.
+    Html,
+}
+
+
+/// Text that is styled according to the markup in use when displayed.
+struct Styled<T> {
+    markup: Markup,
+    style: Style,
+    /// The URL the text should link to, if any.
+    link: Option<String>,
+    text: T,
+}
+
+impl<T> Display for Styled<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            markup,
+            style,
+            link,
+            text,
+        } = self;
+
+        match markup {
+            Markup::None => text.fmt(f),
+            Markup::Ansi => {
+                let styled = *style != Style::default();
+                if styled {
+                    let () = f.write_str(&style.ansi())?;
+                }
+                if let Some(url) = link {
+                    let () = write!(f, "\x1b]8;;{url}\x1b\\")?;
+                }
+                let () = text.fmt(f)?;
+                if link.is_some() {
+                    let () = f.write_str("\x1b]8;;\x1b\\")?;
+                }
+                if styled {
+                    let () = f.write_str(RESET)?;
+                }
+                Ok(())
+            },
+            Markup::Html => {
+                let text = text.to_string();
+                let text = escape_html(&text);
+                if let Some(url) = link {
+                    let () = write!(f, r#"<a href="{}">"#, escape_html(url))?;
+                }
+                let css = style.css();
+                if css.is_empty() {
+                    let () = f.write_str(&text)?;
+                } else {
+                    let () = write!(f, r#"<span style="{css}">{text}</span>"#)?;
+                }
+                if link.is_some() {
+                    let () = f.write_str("</a>")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+
+/// A helper for styling report elements using a given markup.
+#[derive(Debug)]
+struct Painter<'src> {
+    markup: Markup,
+    /// The highlighter for source code snippets, if syntax
+    /// highlighting is enabled.
+    highlighter: Option<Highlighter<'src>>,
+}
+
+impl<'src> Painter<'src> {
+    fn new(markup: Markup, code: &'src [u8], opts: &Opts) -> Result<Self> {
+        let highlighter = match (&opts.highlight_query, markup) {
+            (Some(query), Markup::Ansi | Markup::Html) => {
+                Some(Highlighter::new(code, query, &opts.theme)?)
+            },
+            _ => None,
+        };
+
+        Ok(Self {
+            markup,
+            highlighter,
+        })
+    }
+
+    fn paint<T>(&self, style: Style, text: T) -> Styled<T> {
+        self.link(style, text, None)
+    }
+
+    fn link<T>(&self, style: Style, text: T, link: Option<String>) -> Styled<T> {
+        Styled {
+            markup: self.markup,
+            style,
+            link,
+            text,
+        }
+    }
+
+    /// Prepare unstyled text for output.
+    fn text<T>(&self, text: T) -> Styled<T> {
+        self.paint(Style::default(), text)
+    }
+
+    /// Create the gutter preceding a snippet line, optionally showing a
+    /// row number.
+    fn gutter(&self, row: Option<usize>, width: usize) -> String {
+        let gutter = match row {
+            Some(row) => format!("{row:width$} |"),
+            None => format!("{:width$} |", ""),
+        };
+        format!("{} ", self.paint(GUTTER, gutter))
+    }
+
+    /// Prepare a line of source code for output, syntax highlighting
+    /// it if enabled.
+    ///
+    /// `line` has to be a sub-slice of `code`.
+    fn source(&self, code: &[u8], line: &[u8]) -> String {
+        let Some(highlighter) = &self.highlighter else {
+            return self.text(String::from_utf8_lossy(line)).to_string()
+        };
+
+        let offset = line.as_ptr() as usize - code.as_ptr() as usize;
+        let mut source = String::new();
+        let mut idx = 0;
+        for (bytes, style) in highlighter.highlight(offset..offset + line.len()) {
+            let start = bytes.start - offset;
+            let end = bytes.end - offset;
+            let text = String::from_utf8_lossy(&line[idx..start]);
+            let () = source.push_str(&self.text(text).to_string());
+            let text = String::from_utf8_lossy(&line[start..end]);
+            let () = source.push_str(&self.paint(style, text).to_string());
+            idx = end;
+        }
+        let text = String::from_utf8_lossy(&line[idx..]);
+        let () = source.push_str(&self.text(text).to_string());
+        source
+    }
+}
 
 
 /// Configuration options for terminal reporting.
@@ -12,6 +234,18 @@ use crate::lines::Lines;
 pub struct Opts {
     /// Extra lines of context to report before and after a match.
     pub extra_lines: (u8, u8),
+    /// Whether to color the report using ANSI escape sequences.
+    pub color: bool,
+    /// Whether to link the names of built-in lints to their
+    /// documentation, using OSC 8 escape sequences as understood by
+    /// many terminals. Only takes effect if `color` is set.
+    pub hyperlinks: bool,
+    /// The tree-sitter query used for syntax highlighting source code
+    /// snippets, if any (see [`HIGHLIGHT_QUERY`][crate::HIGHLIGHT_QUERY]).
+    /// Only takes effect if `color` is set or when reporting as HTML.
+    pub highlight_query: Option<String>,
+    /// The theme used for syntax highlighting.
+    pub theme: Theme,
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
@@ -79,92 +313,627 @@ pub fn report_terminal_opts(
     opts: &Opts,
     writer: &mut dyn io::Write,
 ) -> Result<()> {
+    let markup = if opts.color {
+        Markup::Ansi
+    } else {
+        Markup::None
+    };
+    let painter = Painter::new(markup, code, opts)?;
+    report(&[r#match], code, path, opts, &painter, writer)
+}
+
+/// Report all lint matches found in a piece of code in terminal style.
+///
+/// Contrary to reporting each match individually, single-line matches
+/// of the same lint whose snippets (including context) overlap or are
+/// adjacent are reported together, with a single header and all
+/// matches underlined in a shared snippet, irrespective of matches of
+/// other lints in between. Reports are separated by empty lines.
+///
+/// - `matches` are the matches to create a report for, as reported by
+///   [`lint`][crate::lint()]
+/// - `code` is the source code in question, as passed to
+///   [`lint`][crate::lint()]
+/// - `path` should be the path to the file to which `code` corresponds
+///   and is used to enhance the generated report
+/// - `opts` specifies the reporting options
+/// - `writer` is a reference to a [`io::Write`] to which to write the
+///   report
+///
+/// # Example
+/// ```text
+/// warning: [probe-read] bpf_probe_read() is deprecated and replaced by
+///          bpf_probe_user() and bpf_probe_kernel(); refer to bpf-helpers(7)
+///   --> example.bpf.c:48:24
+///    |
+/// 48 |                         bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
+///    |                         ^^^^^^^^^^^^^^
+/// 49 |                         bpf_probe_read(event.bt, sizeof(t->bt), t->bt);
+///    |                         ^^^^^^^^^^^^^^
+///    |
+/// ```
+pub fn report_terminal_all(
+    matches: &[LintMatch],
+    code: &[u8],
+    path: &Path,
+    opts: &Opts,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    let markup = if opts.color {
+        Markup::Ansi
+    } else {
+        Markup::None
+    };
+    let painter = Painter::new(markup, code, opts)?;
+
+    // Matches of other lints may be interleaved with those of a group,
+    // so consider all groups formed so far and not just the last one.
+    let mut groups = Vec::<Vec<&LintMatch>>::new();
+    for m in matches {
+        match groups
+            .iter_mut()
+            .find(|group| is_groupable(group, m, opts.extra_lines))
+        {
+            Some(group) => group.push(m),
+            None => groups.push(vec![m]),
+        }
+    }
+
+    for (idx, group) in groups.iter().enumerate() {
+        if idx > 0 {
+            writeln!(writer)?;
+        }
+        let () = report(group, code, path, opts, &painter, writer)?;
+    }
+    Ok(())
+}
+
+/// Report a lint match as HTML.
+///
+/// The report has the same layout as that created by
+/// [`report_terminal_opts`] and is meant to be placed in a `<pre>`
+/// element. It is always styled, using inline CSS, and syntax
+/// highlighting is used if configured. If `opts.hyperlinks` is set,
+/// the names of built-in lints link to their documentation.
+///
+/// - `match` is the match to create a report for
+/// - `code` is the source code in question, as passed to
+///   [`lint`][crate::lint()]
+/// - `path` should be the path to the file to which `code` corresponds
+///   and is used to enhance the generated report
+/// - `opts` specifies the reporting options
+/// - `writer` is a reference to a [`io::Write`] to which to write the
+///   report
+pub fn report_html(
+    r#match: &LintMatch,
+    code: &[u8],
+    path: &Path,
+    opts: &Opts,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    let painter = Painter::new(Markup::Html, code, opts)?;
+    report(&[r#match], code, path, opts, &painter, writer)
+}
+
+/// An annotation of a piece of source code in a snippet.
+#[derive(Debug)]
+struct Annotation<'m> {
+    /// The annotated range.
+    range: &'m Range,
+    /// Whether the annotation marks a primary location, underlined
+    /// with `^`, as opposed to a secondary one, underlined with `-`.
+    primary: bool,
+    /// The message to attach to the annotation, if any.
+    message: Option<&'m str>,
+}
+
+impl Annotation<'_> {
+    /// Check whether the annotation is rendered as spanning multiple
+    /// lines.
+    ///
+    /// Secondary annotations are only ever underlined until the end of
+    /// their first line.
+    fn is_multi_line(&self) -> bool {
+        self.primary && self.range.start_point.row != self.range.end_point.row
+    }
+
+    /// Retrieve the (inclusive) range of rows of source code to show
+    /// for the annotation.
+    fn rows(&self, extra_lines: (u8, u8)) -> (usize, usize) {
+        let start = self.range.start_point.row;
+        if self.primary {
+            let end = self.range.end_point.row;
+            (
+                start.saturating_sub(extra_lines.0.into()),
+                end + usize::from(extra_lines.1),
+            )
+        } else {
+            (start, start)
+        }
+    }
+
+    /// Retrieve the columns to underline on `line`, which is the first
+    /// line of the annotation.
+    fn columns(&self, line: &[u8]) -> (usize, usize) {
+        let start = self.range.start_point.col;
+        let end = if self.range.end_point.row == self.range.start_point.row {
+            self.range.end_point.col
+        } else {
+            line.len()
+        };
+        (start, end.max(start))
+    }
+}
+
+
+/// A contiguous section of source code shown in a report, along with
+/// the annotations pointing into it.
+#[derive(Debug)]
+struct Chunk<'a, 'm> {
+    /// The position of the chunk's earliest annotation in the order in
+    /// which annotations were provided.
+    order: usize,
+    /// The first row shown.
+    start: usize,
+    /// The last row shown.
+    end: usize,
+    /// The annotations in the chunk.
+    annotations: Vec<&'a Annotation<'m>>,
+}
+
+
+/// Check whether `m` can be reported along with the matches in `group`,
+/// sharing a single header and snippet.
+///
+/// That is the case for single-line matches of the same lint whose
+/// snippets overlap or are adjacent.
+fn is_groupable(group: &[&LintMatch], m: &LintMatch, extra_lines: (u8, u8)) -> bool {
+    let rows = |m: &LintMatch| {
+        Annotation {
+            range: &m.range,
+            primary: true,
+            message: None,
+        }
+        .rows(extra_lines)
+    };
+    let is_single_line = |m: &LintMatch| {
+        !m.range.bytes.is_empty() && m.range.start_point.row == m.range.end_point.row
+    };
+
+    let (start, end) = rows(m);
+    group.iter().all(|other| {
+        other.lint_name == m.lint_name
+            && other.severity == m.severity
+            && other.message == m.message
+            && is_single_line(other)
+    }) && is_single_line(m)
+        && group.iter().any(|other| {
+            let (other_start, other_end) = rows(other);
+            start <= other_end + 1 && other_start <= end + 1
+        })
+}
+
+/// Report a group of matches of the same lint, styled using `painter`.
+fn report(
+    matches: &[&LintMatch],
+    code: &[u8],
+    path: &Path,
+    opts: &Opts,
+    painter: &Painter<'_>,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    // SANITY: We never create empty groups.
     let LintMatch {
         lint_name,
+        severity,
         message,
         range,
-    } = r#match;
+        ..
+    } = matches[0];
 
-    writeln!(writer, "warning: [{lint_name}] {message}")?;
-    let start_row = range.start_point.row;
-    let end_row = range.end_point.row;
-    let start_col = range.start_point.col;
-    let end_col = range.end_point.col;
-    writeln!(writer, "  --> {}:{start_row}:{start_col}", path.display())?;
-    let width = (end_row + usize::from(opts.extra_lines.1))
+    let style = severity_style(*severity);
+    let url = builtin_lint_url(lint_name).filter(|_| opts.hyperlinks);
+    writeln!(
+        writer,
+        "{}: {} {}",
+        painter.paint(style, severity),
+        painter.link(BOLD, format!("[{lint_name}]"), url),
+        painter.paint(BOLD, message)
+    )?;
+    writeln!(
+        writer,
+        "  {} {}:{}:{}",
+        painter.paint(GUTTER, "-->"),
+        painter.text(path.display()),
+        range.start_point.row,
+        range.start_point.col,
+    )?;
+
+    let primaries = matches
+        .iter()
+        // Matches not covering any code have nothing to point at.
+        .filter(|m| !m.range.bytes.is_empty())
+        .map(|m| Annotation {
+            range: &m.range,
+            primary: true,
+            message: None,
+        });
+    let secondaries = matches
+        .iter()
+        .flat_map(|m| &m.labels)
+        .map(|Label { range, message }| Annotation {
+            range,
+            primary: false,
+            message: Some(message),
+        });
+    let annotations = primaries.chain(secondaries).collect::<Vec<_>>();
+
+    // Use the largest row number, so we end up with a consistent
+    // indentation.
+    let width = matches
+        .iter()
+        .flat_map(|m| {
+            m.labels
+                .iter()
+                .map(|label| label.range.end_point.row)
+                .chain([m.range.end_point.row + usize::from(opts.extra_lines.1)])
+        })
+        .max()
+        .unwrap_or_default()
         .to_string()
         .len();
 
-    if range.bytes.is_empty() {
+    let mut sorted = annotations.iter().enumerate().collect::<Vec<_>>();
+    let () = sorted.sort_by_key(|(_, annotation)| annotation.rows(opts.extra_lines));
+    let mut chunks = Vec::<Chunk>::new();
+    for (idx, annotation) in sorted {
+        let (start, end) = annotation.rows(opts.extra_lines);
+        match chunks.last_mut() {
+            // Merge overlapping and adjacent context windows.
+            Some(chunk) if start <= chunk.end + 1 => {
+                chunk.order = chunk.order.min(idx);
+                chunk.end = chunk.end.max(end);
+                let () = chunk.annotations.push(annotation);
+            },
+            _ => chunks.push(Chunk {
+                order: idx,
+                start,
+                end,
+                annotations: vec![annotation],
+            }),
+        }
+    }
+
+    // Primary annotations come first, so the chunk containing them is
+    // reported first, with all others following in the order in which
+    // their labels were provided.
+    let () = chunks.sort_by_key(|chunk| chunk.order);
+
+    // SANITY: It would be a tree-sitter bug if any of the ranges did
+    //         not map to a valid code location.
+    let lines = Lines::new(code, 0).collect::<Vec<_>>();
+    let prefix = painter.gutter(None, width);
+    for chunk in chunks {
+        if !chunk
+            .annotations
+            .iter()
+            .any(|annotation| annotation.primary)
+        {
+            // SANITY: Chunks always contain at least one annotation.
+            let Point { row, col } = chunk.annotations[0].range.start_point;
+            writeln!(
+                writer,
+                "  {} {}:{row}:{col}",
+                painter.paint(GUTTER, ":::"),
+                painter.text(path.display())
+            )?;
+        }
+        writeln!(writer, "{prefix}")?;
+        let () = report_chunk(&chunk, &lines, code, painter, style, width, writer)?;
+        writeln!(writer, "{prefix}")?;
+    }
+    Ok(())
+}
+
+/// Report the source code lines of a chunk, along with its annotations.
+fn report_chunk(
+    chunk: &Chunk<'_, '_>,
+    lines: &[&[u8]],
+    code: &[u8],
+    painter: &Painter<'_>,
+    style: Style,
+    width: usize,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    let prefix = painter.gutter(None, width);
+    let multi_line = chunk
+        .annotations
+        .iter()
+        .find(|annotation| annotation.is_multi_line());
+    let closing = multi_line.map(|annotation| {
+        let underline = format!("|{:_<width$}^", "", width = annotation.range.end_point.col);
+        format!("{prefix} {}", painter.paint(style, underline))
+    });
+    let mut closed = false;
+
+    // Rows are mapped to lines relative to the line containing the
+    // first annotation.
+    // SANITY: Chunks always contain at least one annotation.
+    let anchor = &chunk.annotations[0].range;
+    let anchor_idx = code[..anchor.bytes.start]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count();
+
+    for row in chunk.start..=chunk.end {
+        let lprefix = painter.gutter(Some(row), width);
+        let margin = multi_line.and_then(|annotation| {
+            let start_row = annotation.range.start_point.row;
+            let end_row = annotation.range.end_point.row;
+            (start_row..=end_row)
+                .contains(&row)
+                .then_some(row == start_row)
+        });
+        // Our `Lines` logic may not report a trailing newline if it is
+        // completely empty, but `tree-sitter` may actually report it.
+        // If that's the case just ignore this empty line.
+        let Some(line) = (row + anchor_idx)
+            .checked_sub(anchor.start_point.row)
+            .and_then(|idx| lines.get(idx))
+        else {
+            continue
+        };
+        let uprefix = match margin {
+            Some(first) => {
+                let c = if first { "/" } else { "|" };
+                writeln!(
+                    writer,
+                    "{lprefix} {} {}",
+                    painter.paint(style, c),
+                    painter.source(code, line)
+                )?;
+                format!("{prefix} {} ", painter.paint(style, "|"))
+            },
+            None => {
+                writeln!(writer, "{lprefix}{}", painter.source(code, line))?;
+                prefix.clone()
+            },
+        };
+
+        let mut annotations = chunk
+            .annotations
+            .iter()
+            .filter(|annotation| !annotation.is_multi_line())
+            .filter(|annotation| annotation.range.start_point.row == row)
+            .collect::<Vec<_>>();
+        let () = annotations.sort_by_key(|annotation| annotation.columns(line));
+        let () = report_underlines(&annotations, line, painter, style, &uprefix, writer)?;
+
+        if let (Some(annotation), Some(closing)) = (multi_line, &closing) {
+            if row == annotation.range.end_point.row {
+                writeln!(writer, "{closing}")?;
+                closed = true;
+            }
+        }
+    }
+
+    if let Some(closing) = closing {
+        if !closed {
+            writeln!(writer, "{closing}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Report the underlines of the single-line `annotations` on `line`,
+/// along with their messages, in the style of rustc's emitter:
+/// ```text
+///   |     ^^^^ -- -- second
+///   |          |
+///   |          first
+/// ```
+///
+/// `annotations` are expected to be sorted by column.
+fn report_underlines(
+    annotations: &[&&Annotation<'_>],
+    line: &[u8],
+    painter: &Painter<'_>,
+    style: Style,
+    prefix: &str,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    if annotations.is_empty() {
         return Ok(())
     }
 
-    // Use the end row here, as it's the largest number, so we end up
-    // with a consistent indentation.
-    let prefix = format!("{:width$} | ", "");
-    writeln!(writer, "{prefix}")?;
-
-    // Print source code context before the actual match. Need to
-    // `collect` here, because the `Write` interface we work with forces
-    // us to emit data in sequential order, but we necessary have to go
-    // backwards from the match.
-    // SANITY: It would be a tree-sitter bug the range does not
-    //         map to a valid code location.
-    let () = Lines::new(code, range.bytes.start)
-        .rev()
-        // Skip the line of the match.
-        .skip(1)
-        .take(opts.extra_lines.0.into())
-        .collect::<Vec<&[u8]>>()
-        .into_iter()
-        .enumerate()
-        .rev()
-        .try_for_each(|(row_sub, line)| {
-            let row = start_row - row_sub - 1;
-            writeln!(writer, "{row:width$} | {}", String::from_utf8_lossy(line))
-        })?;
-
-    // SANITY: It would be a tree-sitter bug the range does not
-    //         map to a valid code location.
-    let mut lines = Lines::new(code, range.bytes.start);
-
-    if start_row == end_row {
-        let lprefix = format!("{start_row:width$} | ");
-        // SANITY: `Lines` will always report at least a single
-        //          line.
-        let line = lines.next().unwrap();
-        writeln!(writer, "{lprefix}{}", String::from_utf8_lossy(line))?;
+    let style = |annotation: &Annotation<'_>| {
+        if annotation.primary { style } else { GUTTER }
+    };
+
+    // Lay out the underlines, with primary ones taking precedence where
+    // they overlap.
+    let mut underlines = Vec::<Option<(char, Style)>>::new();
+    for annotation in annotations {
+        let (start, end) = annotation.columns(line);
+        if underlines.len() < end {
+            let () = underlines.resize(end, None);
+        }
+        let c = if annotation.primary { '^' } else { '-' };
+        for slot in &mut underlines[start..end] {
+            if !matches!(slot, Some(('^', _))) {
+                *slot = Some((c, style(annotation)));
+            }
+        }
+    }
+
+    // Render a sequence of characters, painting runs of them.
+    let render = |chars: &[Option<(char, Style)>]| {
+        let mut rendered = String::new();
+        let mut idx = 0;
+        while idx < chars.len() {
+            let len = chars[idx..]
+                .iter()
+                .take_while(|c| **c == chars[idx])
+                .count();
+            let run = chars[idx..idx + len]
+                .iter()
+                .map(|c| c.map(|(c, _)| c).unwrap_or(' '))
+                .collect::<String>();
+            match chars[idx] {
+                Some((_, style)) => rendered.push_str(&painter.paint(style, run).to_string()),
+                None => rendered.push_str(&run),
+            }
+            idx += len;
+        }
+        rendered
+    };
+
+    let mut labeled = annotations
+        .iter()
+        .filter(|annotation| annotation.message.is_some())
+        .collect::<Vec<_>>();
+
+    // The message of the last annotation can be placed right after the
+    // underlines, provided no other annotation starts after it.
+    let (start, _) = annotations[0].columns(line);
+    let mut underline = format!("{:start$}{}", "", render(&underlines[start..]));
+    if let Some(last) = labeled.last() {
+        let (last_start, _) = last.columns(line);
+        let is_last = annotations
+            .iter()
+            .all(|annotation| annotation.columns(line).0 <= last_start);
+        if is_last {
+            // SANITY: We only consider annotations with messages.
+            let message = last.message.unwrap();
+            let () = underline.push_str(&format!(" {}", painter.paint(style(last), message)));
+            let _last = labeled.pop();
+        }
+    }
+    writeln!(writer, "{prefix}{underline}")?;
+
+    // All remaining messages are placed on lines of their own,
+    // connected to their underlines.
+    let connectors = |labeled: &[&&&Annotation<'_>]| {
+        let mut connectors = Vec::new();
+        for annotation in labeled {
+            let (start, _) = annotation.columns(line);
+            if connectors.len() <= start {
+                let () = connectors.resize(start + 1, None);
+            }
+            connectors[start] = Some(('|', style(annotation)));
+        }
+        connectors
+    };
+
+    if !labeled.is_empty() {
+        writeln!(writer, "{prefix}{}", render(&connectors(&labeled)))?;
+    }
+    while let Some(annotation) = labeled.pop() {
+        let (start, _) = annotation.columns(line);
+        let mut connectors = connectors(&labeled);
+        let () = connectors.resize(start, None);
+        // SANITY: We only consider annotations with messages.
+        let message = annotation.message.unwrap();
         writeln!(
             writer,
-            "{prefix}{:indent$}{:^<width$}",
-            "",
-            "",
-            indent = start_col,
-            width = end_col.saturating_sub(start_col)
+            "{prefix}{}{}",
+            render(&connectors),
+            painter.paint(style(annotation), message)
         )?;
-    } else {
-        for (idx, row) in (start_row..=end_row).enumerate() {
-            let lprefix = format!("{row:width$} | ");
-            let c = if idx == 0 { "/" } else { "|" };
-            // Our `Lines` logic may not report a trailing newline if it
-            // is completely empty, but `tree-sitter` may actually
-            // report it. If that's the case just ignore this empty
-            // line.
-            let Some(line) = lines.next() else { break };
-            writeln!(writer, "{lprefix} {c} {}", String::from_utf8_lossy(line))?;
-        }
-        writeln!(writer, "{prefix} |{:_<width$}^", "", width = end_col)?;
     }
+    Ok(())
+}
+
+
+/// Retrieve the source code lines spanned by `range`, without the
+/// trailing newline.
+fn snippet(code: &[u8], range: &Range) -> String {
+    if range.bytes.is_empty() {
+        return String::new()
+    }
+
+    let count = range.end_point.row - range.start_point.row + 1;
+    Lines::new(code, range.bytes.start)
+        .take(count)
+        .map(String::from_utf8_lossy)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn point_json(point: &Point) -> Value {
+    json!({
+        "line": point.row,
+        "col": point.col,
+    })
+}
+
+fn range_json(range: &Range) -> Value {
+    json!({
+        "bytes": {
+            "start": range.bytes.start,
+            "end": range.bytes.end,
+        },
+        "start": point_json(&range.start_point),
+        "end": point_json(&range.end_point),
+    })
+}
+
+/// Report a lint match as a single line of JSON.
+///
+/// The report is an object terminated by a newline, meaning that
+/// reports of several matches written to the same `writer` form a [JSON
+/// Lines](https://jsonlines.org) stream. It has the following members:
+/// - `file`: the path to the file containing the match
+/// - `lint`: the name of the lint that matched
+/// - `severity`: the severity of the match, e.g., `warning`
+/// - `message`: the lint's message
+/// - `bytes`: an object with the `start` (inclusive) and `end`
+///   (exclusive) byte offsets of the match
+/// - `start` and `end`: objects with the `line` and `col` of the start
+///   and end of the match; both are zero-based, as is the case for
+///   [`Point`], and columns are counted in bytes
+/// - `snippet`: the source code lines spanned by the match, empty if
+///   the match does not cover any code
+/// - `labels`: the match's secondary labels, each with a `message` and
+///   `bytes`, `start`, and `end` members as above
+///
+/// - `match` is the match to create a report for
+/// - `code` is the source code in question, as passed to
+///   [`lint`][crate::lint()]
+/// - `path` should be the path to the file to which `code` corresponds
+/// - `writer` is a reference to a [`io::Write`] to which to write the
+///   report
+pub fn report_json(
+    r#match: &LintMatch,
+    code: &[u8],
+    path: &Path,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    let LintMatch {
+        lint_name,
+        severity,
+        message,
+        range,
+        labels,
+        suppression: _,
+    } = r#match;
 
-    let () = lines
-        .take(opts.extra_lines.1.into())
-        .enumerate()
-        .try_for_each(|(row_add, line)| {
-            let row = end_row + row_add + 1;
-            writeln!(writer, "{row:width$} | {}", String::from_utf8_lossy(line))
-        })?;
+    let mut json = range_json(range);
+    json["file"] = Value::from(path.to_string_lossy());
+    json["lint"] = Value::from(lint_name.as_str());
+    json["severity"] = Value::from(severity.name());
+    json["message"] = Value::from(message.as_str());
+    json["snippet"] = Value::from(snippet(code, range));
+    json["labels"] = labels
+        .iter()
+        .map(|Label { range, message }| {
+            let mut json = range_json(range);
+            json["message"] = Value::from(message.as_str());
+            json
+        })
+        .collect();
 
-    writeln!(writer, "{prefix}")?;
+    writeln!(writer, "{json}")?;
     Ok(())
 }
 
@@ -173,12 +942,14 @@ pub fn report_terminal_opts(
 mod tests {
     use super::*;
 
+    use std::ops;
+
     use indoc::indoc;
 
     use pretty_assertions::assert_eq;
 
-    use crate::Point;
-    use crate::Range;
+    use crate::HIGHLIGHT_QUERY;
+    use crate::Severity;
 
 
     /// Tests that a match with an empty range includes no code snippet.
@@ -190,12 +961,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "bogus-file-extension".to_string(),
+            severity: Severity::Warning,
             message: "by convention BPF C code should use the file extension '.bpf.c'".to_string(),
             range: Range {
                 bytes: 0..0,
                 start_point: Point::default(),
                 end_point: Point::default(),
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () =
@@ -224,12 +998,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
             message: "bpf_probe_read() is deprecated".to_string(),
             range: Range {
                 bytes: 68..140,
                 start_point: Point { row: 2, col: 4 },
                 end_point: Point { row: 5, col: 17 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
@@ -270,12 +1047,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
             message: "bpf_probe_read() is deprecated".to_string(),
             range: Range {
                 bytes: 103..175,
                 start_point: Point { row: 7, col: 4 },
                 end_point: Point { row: 10, col: 17 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
@@ -306,12 +1086,15 @@ mod tests {
         "# };
         let m = LintMatch {
             lint_name: "lint".to_string(),
+            severity: Severity::Warning,
             message: "message".to_string(),
             range: Range {
                 bytes: 0..21,
                 start_point: Point { row: 0, col: 0 },
                 end_point: Point { row: 1, col: 0 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
 
         let mut report = Vec::new();
@@ -348,12 +1131,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
             message: "bpf_probe_read() is deprecated".to_string(),
             range: Range {
                 bytes: 160..174,
                 start_point: Point { row: 6, col: 4 },
                 end_point: Point { row: 6, col: 18 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
@@ -382,12 +1168,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "unstable-attach-point".to_string(),
+            severity: Severity::Warning,
             message: "kprobe/kretprobe/fentry/fexit are unstable".to_string(),
             range: Range {
                 bytes: 4..17,
                 start_point: Point { row: 0, col: 4 },
                 end_point: Point { row: 0, col: 17 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
@@ -420,12 +1209,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
             message: "bpf_probe_read() is deprecated".to_string(),
             range: Range {
                 bytes: 160..174,
                 start_point: Point { row: 5, col: 4 },
                 end_point: Point { row: 5, col: 18 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
 
         let mut report_old = Vec::new();
@@ -461,12 +1253,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
             message: "bpf_probe_read() is deprecated".to_string(),
             range: Range {
                 bytes: 160..174,
                 start_point: Point { row: 5, col: 4 },
                 end_point: Point { row: 5, col: 18 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () = report_terminal_opts(
@@ -512,12 +1307,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
             message: "bpf_probe_read() is deprecated".to_string(),
             range: Range {
                 bytes: 68..140,
                 start_point: Point { row: 2, col: 4 },
                 end_point: Point { row: 5, col: 17 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () = report_terminal_opts(
@@ -561,12 +1359,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "unstable-attach-point".to_string(),
+            severity: Severity::Warning,
             message: "kprobe/kretprobe/fentry/fexit are unstable".to_string(),
             range: Range {
                 bytes: 4..17,
                 start_point: Point { row: 0, col: 4 },
                 end_point: Point { row: 0, col: 17 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () = report_terminal_opts(
@@ -595,6 +1396,443 @@ mod tests {
         assert_eq!(report, expected);
     }
 
+    /// Check that secondary labels are reported alongside the match.
+    #[test]
+    fn label_reporting() {
+        let code = indoc! { r#"
+            SEC("tp_btf/sched_switch")
+            int handle__sched_switch(u64 *ctx)
+            {
+                bpf_probe_read_kernel(event.comm, TASK_COMM_LEN, prev->comm);
+                return 0;
+            }
+
+            char LICENSE[] SEC("license") = "Proprietary";
+        "# };
+
+        let m = LintMatch {
+            lint_name: "gpl-only-helper".to_string(),
+            severity: Severity::Warning,
+            message: "GPL-only helper".to_string(),
+            range: Range {
+                bytes: 68..89,
+                start_point: Point { row: 3, col: 4 },
+                end_point: Point { row: 3, col: 25 },
+            },
+            labels: vec![Label {
+                range: Range {
+                    bytes: 179..192,
+                    start_point: Point { row: 7, col: 32 },
+                    end_point: Point { row: 7, col: 45 },
+                },
+                message: "license defined here".to_string(),
+            }],
+            suppression: None,
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+
+        let expected = indoc! { r#"
+            warning: [gpl-only-helper] GPL-only helper
+              --> <stdin>:3:4
+              | 
+            3 |     bpf_probe_read_kernel(event.comm, TASK_COMM_LEN, prev->comm);
+              |     ^^^^^^^^^^^^^^^^^^^^^
+              | 
+              ::: <stdin>:7:32
+              | 
+            7 | char LICENSE[] SEC("license") = "Proprietary";
+              |                                 ------------- license defined here
+              | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that we color reports and link lint names to their
+    /// documentation, if requested.
+    #[test]
+    fn colored_reporting() {
+        let code = indoc! { r#"
+            int main() {
+                bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
+            }
+        "# };
+
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is deprecated".to_string(),
+            range: Range {
+                bytes: 17..31,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 1, col: 18 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+        let opts = Opts {
+            color: true,
+            hyperlinks: true,
+            ..Default::default()
+        };
+        let mut report = Vec::new();
+        let () = report_terminal_opts(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &opts,
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let url = "https://github.com/d-e-s-o/bpflint/blob/main/lints/probe-read.scm";
+        let expected = [
+            format!(
+                "\x1b[1;33mwarning\x1b[0m: \x1b[1m\x1b]8;;{url}\x1b\\[probe-read]\x1b]8;;\x1b\\\x1b[0m \x1b[1mbpf_probe_read() is deprecated\x1b[0m"
+            ),
+            "  \x1b[1;34m-->\x1b[0m <stdin>:1:4".to_string(),
+            "\x1b[1;34m  |\x1b[0m ".to_string(),
+            "\x1b[1;34m1 |\x1b[0m     bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);"
+                .to_string(),
+            "\x1b[1;34m  |\x1b[0m     \x1b[1;33m^^^^^^^^^^^^^^\x1b[0m".to_string(),
+            "\x1b[1;34m  |\x1b[0m ".to_string(),
+        ];
+        assert_eq!(report.lines().collect::<Vec<_>>(), expected);
+
+        // Without coloring, no escape sequences should be emitted at
+        // all.
+        let opts = Opts {
+            hyperlinks: true,
+            ..Default::default()
+        };
+        let mut report = Vec::new();
+        let () = report_terminal_opts(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &opts,
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(!report.contains('\x1b'), "{report}");
+    }
+
+    /// Check that source code snippets are syntax highlighted, if
+    /// requested.
+    #[test]
+    fn highlighted_reporting() {
+        let code = indoc! { r#"
+            int main() {
+                return bpf_probe_read(dst, 1, "src"); // read
+            }
+        "# };
+
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is deprecated".to_string(),
+            range: Range {
+                bytes: 24..38,
+                start_point: Point { row: 1, col: 11 },
+                end_point: Point { row: 1, col: 25 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+        let opts = Opts {
+            color: true,
+            highlight_query: Some(HIGHLIGHT_QUERY.to_string()),
+            ..Default::default()
+        };
+        let mut report = Vec::new();
+        let () = report_terminal_opts(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &opts,
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let line = report.lines().nth(3).unwrap();
+        assert_eq!(
+            line,
+            "\x1b[1;34m1 |\x1b[0m     \x1b[35mreturn\x1b[0m \x1b[36mbpf_probe_read\x1b[0m(dst, 1, \x1b[32m\"src\"\x1b[0m); \x1b[3;90m// read\x1b[0m"
+        );
+
+        // Without coloring there is no highlighting.
+        let opts = Opts {
+            color: false,
+            ..opts
+        };
+        let mut report = Vec::new();
+        let () = report_terminal_opts(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &opts,
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(!report.contains('\x1b'), "{report}");
+    }
+
+    /// Check that we create the expected HTML reports.
+    #[test]
+    fn html_reporting() {
+        let code = indoc! { r#"
+            int main() {
+                if (x < 1) bpf_probe_read(dst, 1, "src");
+            }
+        "# };
+
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is <deprecated>".to_string(),
+            range: Range {
+                bytes: 28..42,
+                start_point: Point { row: 1, col: 15 },
+                end_point: Point { row: 1, col: 29 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+        let opts = Opts {
+            hyperlinks: true,
+            highlight_query: Some(HIGHLIGHT_QUERY.to_string()),
+            ..Default::default()
+        };
+        let mut report = Vec::new();
+        let () = report_html(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &opts,
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+
+        let bold = r#"<span style="font-weight: bold">"#;
+        let gutter = r#"<span style="color: #2a6fdb; font-weight: bold">"#;
+        let url = "https://github.com/d-e-s-o/bpflint/blob/main/lints/probe-read.scm";
+        let expected = [
+            format!(
+                r#"<span style="color: #b58900; font-weight: bold">warning</span>: <a href="{url}">{bold}[probe-read]</span></a> {bold}bpf_probe_read() is &lt;deprecated&gt;</span>"#
+            ),
+            format!("  {gutter}--&gt;</span> &lt;stdin&gt;:1:15"),
+            format!("{gutter}  |</span> "),
+            format!(
+                r#"{gutter}1 |</span>     <span style="color: #b200b2">if</span> (x &lt; 1) <span style="color: #00a6b2">bpf_probe_read</span>(dst, 1, <span style="color: #00a600">&quot;src&quot;</span>);"#
+            ),
+            format!(
+                r#"{gutter}  |</span>                <span style="color: #b58900; font-weight: bold">^^^^^^^^^^^^^^</span>"#
+            ),
+            format!("{gutter}  |</span> "),
+        ];
+        assert_eq!(report.lines().collect::<Vec<_>>(), expected);
+    }
+
+    /// Check that several labels on a single line are rendered as
+    /// expected.
+    #[test]
+    fn multi_label_reporting() {
+        let code = indoc! { r#"
+            int main() {
+                x = foo(a, b);
+            }
+        "# };
+
+        let label = |col: usize, message: &str| Label {
+            range: Range {
+                bytes: 13 + col..13 + col + 1,
+                start_point: Point { row: 1, col },
+                end_point: Point {
+                    row: 1,
+                    col: col + 1,
+                },
+            },
+            message: message.to_string(),
+        };
+        let m = LintMatch {
+            lint_name: "lint".to_string(),
+            severity: Severity::Warning,
+            message: "message".to_string(),
+            range: Range {
+                bytes: 21..24,
+                start_point: Point { row: 1, col: 8 },
+                end_point: Point { row: 1, col: 11 },
+            },
+            labels: vec![label(12, "first"), label(15, "second")],
+            suppression: None,
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+
+        let expected = indoc! { r#"
+            warning: [lint] message
+              --> <stdin>:1:8
+              | 
+            1 |     x = foo(a, b);
+              |         ^^^ -  - second
+              |             |
+              |             first
+              | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that matches of the same lint on adjacent lines are
+    /// reported together.
+    #[test]
+    fn grouped_reporting() {
+        let code = indoc! { r#"
+            int main() {
+                bpf_probe_read(a, 1, b);
+                bpf_probe_read(c, 1, d);
+
+                bpf_probe_read(e, 1, f);
+            }
+        "# };
+
+        let m = |bytes: ops::Range<usize>, row| LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is deprecated".to_string(),
+            range: Range {
+                bytes,
+                start_point: Point { row, col: 4 },
+                end_point: Point { row, col: 18 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+        let matches = [m(17..31, 1), m(46..60, 2), m(76..90, 4)];
+        let report = |opts: &Opts| {
+            let mut report = Vec::new();
+            let () = report_terminal_all(
+                &matches,
+                code.as_bytes(),
+                Path::new("<stdin>"),
+                opts,
+                &mut report,
+            )
+            .unwrap();
+            String::from_utf8(report).unwrap()
+        };
+
+        let expected = indoc! { r#"
+            warning: [probe-read] bpf_probe_read() is deprecated
+              --> <stdin>:1:4
+              | 
+            1 |     bpf_probe_read(a, 1, b);
+              |     ^^^^^^^^^^^^^^
+            2 |     bpf_probe_read(c, 1, d);
+              |     ^^^^^^^^^^^^^^
+              | 
+
+            warning: [probe-read] bpf_probe_read() is deprecated
+              --> <stdin>:4:4
+              | 
+            4 |     bpf_probe_read(e, 1, f);
+              |     ^^^^^^^^^^^^^^
+              | 
+        "# };
+        assert_eq!(report(&Opts::default()), expected);
+
+        // With context, the snippets of all matches overlap.
+        let opts = Opts {
+            extra_lines: (1, 1),
+            ..Default::default()
+        };
+        let expected = indoc! { r#"
+            warning: [probe-read] bpf_probe_read() is deprecated
+              --> <stdin>:1:4
+              | 
+            0 | int main() {
+            1 |     bpf_probe_read(a, 1, b);
+              |     ^^^^^^^^^^^^^^
+            2 |     bpf_probe_read(c, 1, d);
+              |     ^^^^^^^^^^^^^^
+            3 | 
+            4 |     bpf_probe_read(e, 1, f);
+              |     ^^^^^^^^^^^^^^
+            5 | }
+              | 
+        "# };
+        assert_eq!(report(&opts), expected);
+    }
+
+    /// Check that matches of the same lint are reported together even
+    /// when matches of another lint are interleaved with them.
+    #[test]
+    fn grouped_reporting_interleaved() {
+        let code = indoc! { r#"
+            int main() {
+                bpf_probe_read(a, 1, b);
+                bpf_probe_read(c, 1, d);
+            }
+        "# };
+
+        let m = |bytes: ops::Range<usize>, row| LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is deprecated".to_string(),
+            range: Range {
+                bytes,
+                start_point: Point { row, col: 4 },
+                end_point: Point { row, col: 18 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+        let other = LintMatch {
+            lint_name: "other".to_string(),
+            message: "other".to_string(),
+            range: Range {
+                bytes: 32..33,
+                start_point: Point { row: 1, col: 19 },
+                end_point: Point { row: 1, col: 20 },
+            },
+            ..m(0..0, 0)
+        };
+        let matches = [m(17..31, 1), other, m(46..60, 2)];
+        let mut report = Vec::new();
+        let () = report_terminal_all(
+            &matches,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &Opts::default(),
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+
+        let expected = indoc! { r#"
+            warning: [probe-read] bpf_probe_read() is deprecated
+              --> <stdin>:1:4
+              | 
+            1 |     bpf_probe_read(a, 1, b);
+              |     ^^^^^^^^^^^^^^
+            2 |     bpf_probe_read(c, 1, d);
+              |     ^^^^^^^^^^^^^^
+              | 
+
+            warning: [other] other
+              --> <stdin>:1:19
+              | 
+            1 |     bpf_probe_read(a, 1, b);
+              |                    ^
+              | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
     /// Test context lines when there aren't enough lines after the error.
     #[test]
     fn report_terminal_opts_insufficient_context_after() {
@@ -608,12 +1846,15 @@ mod tests {
 
         let m = LintMatch {
             lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
             message: "bpf_probe_read() is deprecated".to_string(),
             range: Range {
                 bytes: 68..82,
                 start_point: Point { row: 3, col: 4 },
                 end_point: Point { row: 3, col: 18 },
             },
+            labels: Vec::new(),
+            suppression: None,
         };
         let mut report = Vec::new();
         let () = report_terminal_opts(
@@ -641,4 +1882,101 @@ mod tests {
         "# };
         assert_eq!(report, expected);
     }
+
+    /// Check that our JSON reporting works as expected.
+    #[test]
+    fn json_reporting() {
+        let code = indoc! { r#"
+            SEC("tp_btf/sched_switch")
+            int handle__sched_switch(u64 *ctx)
+            {
+                bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
+                return 0;
+            }
+        "# };
+
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            severity: Severity::Warning,
+            message: "bpf_probe_read() is deprecated".to_string(),
+            range: Range {
+                bytes: 68..82,
+                start_point: Point { row: 3, col: 4 },
+                end_point: Point { row: 3, col: 18 },
+            },
+            labels: vec![Label {
+                range: Range {
+                    bytes: 31..51,
+                    start_point: Point { row: 1, col: 4 },
+                    end_point: Point { row: 1, col: 24 },
+                },
+                message: "in this program".to_string(),
+            }],
+            suppression: None,
+        };
+        let mut report = Vec::new();
+        let () = report_json(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert_eq!(report.lines().count(), 1);
+        assert!(report.ends_with('\n'));
+
+        let report = serde_json::from_str::<Value>(&report).unwrap();
+        let expected = json!({
+            "file": "<stdin>",
+            "lint": "probe-read",
+            "severity": "warning",
+            "message": "bpf_probe_read() is deprecated",
+            "bytes": { "start": 68, "end": 82 },
+            "start": { "line": 3, "col": 4 },
+            "end": { "line": 3, "col": 18 },
+            "snippet": "    bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);",
+            "labels": [{
+                "message": "in this program",
+                "bytes": { "start": 31, "end": 51 },
+                "start": { "line": 1, "col": 4 },
+                "end": { "line": 1, "col": 24 },
+            }],
+        });
+        assert_eq!(report, expected);
+    }
+
+    /// Check that matches spanning multiple lines or no code at all
+    /// are reported as JSON with the expected snippet.
+    #[test]
+    fn json_reporting_snippet() {
+        let code = indoc! { r#"
+            int main() {
+                foo(1,
+                    2);
+            }
+        "# };
+
+        let mut m = LintMatch {
+            lint_name: "lint".to_string(),
+            severity: Severity::Warning,
+            message: "message".to_string(),
+            range: Range {
+                bytes: 17..34,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 2, col: 10 },
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+        let mut report = Vec::new();
+        let () = report_json(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = serde_json::from_slice::<Value>(&report).unwrap();
+        assert_eq!(report["snippet"], "    foo(1,\n        2);");
+
+        m.range = Range {
+            bytes: 0..0,
+            start_point: Point::default(),
+            end_point: Point::default(),
+        };
+        let mut report = Vec::new();
+        let () = report_json(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = serde_json::from_slice::<Value>(&report).unwrap();
+        assert_eq!(report["snippet"], "");
+        assert_eq!(report["start"], json!({ "line": 0, "col": 0 }));
+    }
 }
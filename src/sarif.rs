@@ -0,0 +1,392 @@
+//! Reporting of lint matches in the SARIF 2.1.0 format.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use anyhow::Result;
+
+use serde_json::Value;
+use serde_json::json;
+
+use crate::Fingerprints;
+use crate::Label;
+use crate::Lint;
+use crate::LintMatch;
+use crate::Range;
+use crate::Severity;
+use crate::Suppression;
+use crate::lint::builtin_lint_url;
+
+
+/// The JSON schema of SARIF 2.1.0 logs.
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+/// The key under which we store our fingerprints.
+const FINGERPRINT_KEY: &str = "bpflint/v1";
+
+
+/// Calculate the one-based column, in Unicode code points, of the byte
+/// at `offset` in `code`.
+///
+/// Other report formats count columns in bytes, but the log declares
+/// its column kind as code points, which consumers rely on.
+fn column(code: &[u8], offset: usize) -> usize {
+    let start = code[..offset]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    String::from_utf8_lossy(&code[start..offset])
+        .chars()
+        .count()
+        + 1
+}
+
+/// Create a SARIF `physicalLocation` object for `range` in the file at
+/// `path`.
+///
+/// Empty ranges are reported as referring to the file as a whole.
+fn physical_location(code: &[u8], path: &Path, range: &Range) -> Value {
+    let uri = path.to_string_lossy().replace('\\', "/");
+    let mut location = json!({
+        "artifactLocation": { "uri": uri },
+    });
+
+    if !range.bytes.is_empty() {
+        location["region"] = json!({
+            "startLine": range.start_point.row + 1,
+            "startColumn": column(code, range.bytes.start),
+            "endLine": range.end_point.row + 1,
+            "endColumn": column(code, range.bytes.end),
+            "byteOffset": range.bytes.start,
+            "byteLength": range.bytes.len(),
+            "snippet": {
+                "text": String::from_utf8_lossy(&code[range.bytes.clone()]),
+            },
+        });
+    }
+    location
+}
+
+/// Create a SARIF `reportingDescriptor` object describing a rule.
+fn rule(name: &str, message: &str, severity: Severity) -> Value {
+    let mut rule = json!({
+        "id": name,
+        "shortDescription": { "text": message },
+        // Our severity names map directly to SARIF levels.
+        "defaultConfiguration": { "level": severity.name() },
+    });
+    if let Some(url) = builtin_lint_url(name) {
+        rule["helpUri"] = Value::from(url);
+    }
+    rule
+}
+
+
+/// A SARIF 2.1.0 log, collecting the lint matches of a run, e.g., for
+/// upload to a code scanning service.
+///
+/// Each lint is described by a rule and each match added is reported
+/// as a result referencing it. Contrary to other report formats,
+/// matches suppressed by a `bpflint: disable` comment are included,
+/// with a `suppressions` entry carrying the justification provided, if
+/// any (see [`LintOpts::report_suppressed`][crate::LintOpts::report_suppressed]).
+///
+/// Results carry a partial fingerprint as calculated by
+/// [`Fingerprints`].
+#[derive(Debug, Default)]
+pub struct SarifLog {
+    /// The rules describing the lints.
+    rules: Vec<Value>,
+    /// A mapping from lint name to the index of its rule.
+    rule_indices: HashMap<String, usize>,
+    /// The reported results.
+    results: Vec<Value>,
+    /// The generator of result fingerprints.
+    fingerprints: Fingerprints,
+}
+
+impl SarifLog {
+    /// Create a new log, with a rule for each of the provided lints.
+    pub fn new<'l, I, L>(lints: I) -> Self
+    where
+        I: IntoIterator<Item = L>,
+        L: AsRef<Lint> + 'l,
+    {
+        let mut slf = Self::default();
+        for lint in lints {
+            let lint = lint.as_ref();
+            let mut rule = rule(&lint.name, &lint.message, lint.severity);
+            rule["defaultConfiguration"]["enabled"] = Value::from(lint.default_enabled);
+            rule["properties"] = json!({ "category": lint.category.name() });
+            let _idx = slf.add_rule(&lint.name, rule);
+        }
+        slf
+    }
+
+    fn add_rule(&mut self, name: &str, rule: Value) -> usize {
+        let idx = self.rules.len();
+        let () = self.rules.push(rule);
+        let _prev = self.rule_indices.insert(name.to_string(), idx);
+        idx
+    }
+
+    /// Add a lint match to the log.
+    ///
+    /// If the match's lint is not described by any of the log's rules,
+    /// a rule is derived from the match.
+    ///
+    /// - `match` is the match to add
+    /// - `code` is the source code in question, as passed to
+    ///   [`lint`][crate::lint()]
+    /// - `path` should be the path to the file to which `code`
+    ///   corresponds
+    pub fn add(&mut self, r#match: &LintMatch, code: &[u8], path: &Path) {
+        let LintMatch {
+            lint_name,
+            severity,
+            message,
+            range,
+            labels,
+            suppression,
+        } = r#match;
+
+        let rule_idx = match self.rule_indices.get(lint_name) {
+            Some(idx) => *idx,
+            None => self.add_rule(lint_name, rule(lint_name, message, *severity)),
+        };
+
+        let fingerprint = self.fingerprints.fingerprint(r#match, code, path);
+        let mut result = json!({
+            "ruleId": lint_name,
+            "ruleIndex": rule_idx,
+            "level": severity.name(),
+            "message": { "text": message },
+            "locations": [{
+                "physicalLocation": physical_location(code, path, range),
+            }],
+            "partialFingerprints": { FINGERPRINT_KEY: fingerprint },
+        });
+
+        if !labels.is_empty() {
+            result["relatedLocations"] = labels
+                .iter()
+                .enumerate()
+                .map(|(id, Label { range, message })| {
+                    json!({
+                        "id": id,
+                        "physicalLocation": physical_location(code, path, range),
+                        "message": { "text": message },
+                    })
+                })
+                .collect();
+        }
+
+        if let Some(Suppression {
+            range,
+            justification,
+        }) = suppression
+        {
+            let mut suppression = json!({
+                "kind": "inSource",
+                "location": { "physicalLocation": physical_location(code, path, range) },
+            });
+            if let Some(justification) = justification {
+                suppression["justification"] = Value::from(justification.as_str());
+            }
+            result["suppressions"] = json!([suppression]);
+        }
+
+        let () = self.results.push(result);
+    }
+
+    /// Write the log, as JSON, to `writer`.
+    pub fn write(&self, writer: &mut dyn io::Write) -> Result<()> {
+        let log = json!({
+            "$schema": SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "bpflint",
+                        "informationUri": "https://github.com/d-e-s-o/bpflint",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": self.rules,
+                    },
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": self.results,
+            }],
+        });
+        writeln!(writer, "{log:#}")?;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ops;
+
+    use indoc::indoc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::Category;
+    use crate::LintCode;
+    use crate::Point;
+
+
+    fn lint_foo() -> Lint {
+        Lint {
+            name: "foo".to_string(),
+            code: LintCode::Query("(call_expression) @call".to_string()),
+            category: Category::Style,
+            severity: Severity::Warning,
+            default_enabled: true,
+            message: "foo() is called".to_string(),
+        }
+    }
+
+    fn match_foo(bytes: ops::Range<usize>, row: usize, col: usize) -> LintMatch {
+        LintMatch {
+            lint_name: "foo".to_string(),
+            severity: Severity::Warning,
+            message: "foo() is called".to_string(),
+            range: Range {
+                start_point: Point { row, col },
+                end_point: Point {
+                    row,
+                    col: col + bytes.len(),
+                },
+                bytes,
+            },
+            labels: Vec::new(),
+            suppression: None,
+        }
+    }
+
+    fn write(log: &SarifLog) -> Value {
+        let mut output = Vec::new();
+        let () = log.write(&mut output).unwrap();
+        serde_json::from_slice(&output).unwrap()
+    }
+
+
+    /// Check that we create the expected SARIF log.
+    #[test]
+    fn basic() {
+        let code = indoc! { r#"
+            int main() {
+                foo();
+                /* bpflint: disable=foo on purpose */
+                foo();
+            }
+        "# };
+
+        let mut log = SarifLog::new([lint_foo()]);
+        let () = log.add(
+            &match_foo(17..22, 1, 4),
+            code.as_bytes(),
+            Path::new("test.bpf.c"),
+        );
+
+        let mut suppressed = match_foo(70..75, 3, 4);
+        suppressed.suppression = Some(Suppression {
+            range: Range {
+                bytes: 28..65,
+                start_point: Point { row: 2, col: 4 },
+                end_point: Point { row: 2, col: 41 },
+            },
+            justification: Some("on purpose".to_string()),
+        });
+        let () = log.add(&suppressed, code.as_bytes(), Path::new("test.bpf.c"));
+
+        let log = write(&log);
+        assert_eq!(log["version"], "2.1.0");
+
+        let run = &log["runs"][0];
+        let rules = &run["tool"]["driver"]["rules"];
+        assert_eq!(
+            rules,
+            &json!([{
+                "id": "foo",
+                "shortDescription": { "text": "foo() is called" },
+                "defaultConfiguration": { "level": "warning", "enabled": true },
+                "properties": { "category": "style" },
+            }])
+        );
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "foo");
+        assert_eq!(results[0]["ruleIndex"], 0);
+        assert_eq!(results[0]["level"], "warning");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "test.bpf.c" },
+                "region": {
+                    "startLine": 2,
+                    "startColumn": 5,
+                    "endLine": 2,
+                    "endColumn": 10,
+                    "byteOffset": 17,
+                    "byteLength": 5,
+                    "snippet": { "text": "foo()" },
+                },
+            })
+        );
+        assert_eq!(results[0].get("suppressions"), None);
+
+        let suppressions = &results[1]["suppressions"];
+        assert_eq!(suppressions[0]["kind"], "inSource");
+        assert_eq!(suppressions[0]["justification"], "on purpose");
+        assert_eq!(
+            suppressions[0]["location"]["physicalLocation"]["region"]["startLine"],
+            3
+        );
+
+        // Identical matches in the same file have distinct
+        // fingerprints.
+        let fingerprint0 = &results[0]["partialFingerprints"][FINGERPRINT_KEY];
+        let fingerprint1 = &results[1]["partialFingerprints"][FINGERPRINT_KEY];
+        assert_ne!(fingerprint0, fingerprint1);
+    }
+
+    /// Check that matches of unknown lints and matches not covering any
+    /// code are handled gracefully.
+    #[test]
+    fn unknown_lint_file_match() {
+        let m = LintMatch {
+            lint_name: "bogus-file-extension".to_string(),
+            severity: Severity::Warning,
+            message: "by convention BPF C code should use the file extension '.bpf.c'".to_string(),
+            range: Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            },
+            labels: Vec::new(),
+            suppression: None,
+        };
+        let mut log = SarifLog::new([lint_foo()]);
+        let () = log.add(&m, b"int main() {}", Path::new("test.c"));
+
+        let log = write(&log);
+        let run = &log["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"][1]["id"],
+            "bogus-file-extension"
+        );
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleIndex"], 1);
+        assert_eq!(
+            result["locations"][0]["physicalLocation"],
+            json!({ "artifactLocation": { "uri": "test.c" } })
+        );
+    }
+}
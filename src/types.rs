@@ -0,0 +1,844 @@
+//! Size and alignment computation for C types.
+//!
+//! Layouts are computed according to the BPF target's rules, which
+//! match those of 64 bit System V platforms: pointers and `long` are
+//! eight bytes wide and every type is aligned to its natural
+//! alignment. Types are resolved using definitions found in the code
+//! being linted, falling back to a table of commonly used kernel and
+//! libbpf types.
+
+use std::collections::HashMap;
+
+use tree_sitter::Node;
+use tree_sitter::Tree;
+
+use crate::checks::descendants;
+use crate::checks::text;
+
+
+/// The maximum nesting of type definitions we resolve, as a safeguard
+/// against malformed (cyclic) definitions.
+const MAX_DEPTH: usize = 32;
+
+
+/// The size and alignment of a type, in bytes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Layout {
+    pub size: u64,
+    pub align: u64,
+}
+
+impl Layout {
+    const fn new(size: u64, align: u64) -> Self {
+        Self { size, align }
+    }
+}
+
+
+/// Layouts of well-known types, as `(name, size, align)` tuples.
+static BUILTIN_TYPES: [(&str, u64, u64); 77] = [
+    ("_Bool", 1, 1),
+    ("__be16", 2, 2),
+    ("__be32", 4, 4),
+    ("__be64", 8, 8),
+    ("__int128", 16, 16),
+    ("__kernel_pid_t", 4, 4),
+    ("__kernel_size_t", 8, 8),
+    ("__le16", 2, 2),
+    ("__le32", 4, 4),
+    ("__le64", 8, 8),
+    ("__s16", 2, 2),
+    ("__s32", 4, 4),
+    ("__s64", 8, 8),
+    ("__s8", 1, 1),
+    ("__sum16", 2, 2),
+    ("__u16", 2, 2),
+    ("__u32", 4, 4),
+    ("__u64", 8, 8),
+    ("__u8", 1, 1),
+    ("__wsum", 4, 4),
+    ("atomic64_t", 8, 8),
+    ("atomic_t", 4, 4),
+    ("bool", 1, 1),
+    ("char", 1, 1),
+    ("dev_t", 4, 4),
+    ("double", 8, 8),
+    ("float", 4, 4),
+    ("gid_t", 4, 4),
+    ("ino_t", 8, 8),
+    ("int", 4, 4),
+    ("int16_t", 2, 2),
+    ("int32_t", 4, 4),
+    ("int64_t", 8, 8),
+    ("int8_t", 1, 1),
+    ("intptr_t", 8, 8),
+    ("loff_t", 8, 8),
+    ("long", 8, 8),
+    ("pid_t", 4, 4),
+    ("ptrdiff_t", 8, 8),
+    ("s16", 2, 2),
+    ("s32", 4, 4),
+    ("s64", 8, 8),
+    ("s8", 1, 1),
+    ("short", 2, 2),
+    ("size_t", 8, 8),
+    ("ssize_t", 8, 8),
+    ("struct bpf_dynptr", 16, 8),
+    ("struct bpf_fib_lookup", 64, 4),
+    ("struct bpf_iter_num", 8, 8),
+    ("struct bpf_perf_event_value", 24, 8),
+    ("struct bpf_pidns_info", 8, 4),
+    ("struct bpf_sock_tuple", 36, 4),
+    ("struct bpf_spin_lock", 4, 4),
+    ("struct bpf_timer", 16, 8),
+    ("struct ethhdr", 14, 1),
+    ("struct icmphdr", 8, 4),
+    ("struct in6_addr", 16, 4),
+    ("struct in_addr", 4, 4),
+    ("struct iphdr", 20, 4),
+    ("struct ipv6hdr", 40, 4),
+    ("struct pt_regs", 168, 8),
+    ("struct sockaddr_in", 16, 4),
+    ("struct sockaddr_in6", 28, 4),
+    ("struct tcphdr", 20, 4),
+    ("struct udphdr", 8, 2),
+    ("struct vlan_hdr", 4, 2),
+    ("u16", 2, 2),
+    ("u32", 4, 4),
+    ("u64", 8, 8),
+    ("u8", 1, 1),
+    ("uid_t", 4, 4),
+    ("uint16_t", 2, 2),
+    ("uint32_t", 4, 4),
+    ("uint64_t", 8, 8),
+    ("uint8_t", 1, 1),
+    ("uintptr_t", 8, 8),
+    ("unsigned", 4, 4),
+];
+
+
+/// Round `value` up to the next multiple of `align`.
+fn align_up(value: u64, align: u64) -> u64 {
+    value.div_ceil(align.max(1)) * align.max(1)
+}
+
+
+/// Parse a C integer literal.
+fn parse_number(text: &str) -> Option<u64> {
+    let text = text.trim().trim_end_matches(['u', 'U', 'l', 'L']);
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()
+    } else if text.len() > 1 && text.starts_with('0') {
+        u64::from_str_radix(&text[1..], 8).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+
+/// Determine the number of bytes of the string denoted by a string
+/// literal, excluding the terminating NUL byte.
+fn string_len(node: Node<'_>, code: &[u8]) -> Option<usize> {
+    let mut cursor = node.walk();
+    match node.kind() {
+        "string_literal" => node
+            .named_children(&mut cursor)
+            .map(|child| match child.kind() {
+                "escape_sequence" => {
+                    let escape = text(child, code);
+                    match escape
+                        .strip_prefix("\\u")
+                        .or_else(|| escape.strip_prefix("\\U"))
+                    {
+                        Some(hex) => u32::from_str_radix(hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .map(char::len_utf8),
+                        // Any other escape sequence denotes a single
+                        // character.
+                        None => Some(1),
+                    }
+                },
+                _ => Some(child.byte_range().len()),
+            })
+            .sum(),
+        "concatenated_string" => node
+            .named_children(&mut cursor)
+            .map(|child| string_len(child, code))
+            .sum(),
+        _ => None,
+    }
+}
+
+
+/// A derivation applied to a base type by a declarator.
+enum Derivation {
+    Pointer,
+    /// An array with the given number of elements, if known.
+    Array(Option<u64>),
+}
+
+
+/// A member of a struct or union, as laid out.
+#[derive(Debug)]
+struct Member<'t> {
+    /// The declarator of the member, if it is not anonymous.
+    name: Option<Node<'t>>,
+    /// The `field_declaration` declaring the member.
+    decl: Node<'t>,
+    /// The offset of the member, in bits.
+    offset: u64,
+    /// The size of the member, in bits.
+    size: u64,
+    /// The definition of the struct or union directly embedded by the
+    /// member, if any, and whether it is a union.
+    record: Option<(Node<'t>, bool)>,
+}
+
+
+/// A padding hole in a struct.
+#[derive(Debug)]
+pub(crate) struct Hole<'t> {
+    /// The declarator of the member preceding the hole, if it is not
+    /// anonymous.
+    pub after: Option<Node<'t>>,
+    /// The node representing the member preceding the hole.
+    pub node: Node<'t>,
+    /// The size of the hole, in bytes.
+    pub size: u64,
+    /// Whether the hole is at the end of the struct.
+    pub trailing: bool,
+}
+
+
+/// Type definitions and constants found in a translation unit.
+#[derive(Debug)]
+pub(crate) struct Types<'t> {
+    code: &'t [u8],
+    /// Struct definitions, by name.
+    structs: HashMap<&'t str, Node<'t>>,
+    /// Union definitions, by name.
+    unions: HashMap<&'t str, Node<'t>>,
+    /// Type definitions, as `(type, declarator)` tuples by name.
+    typedefs: HashMap<&'t str, (Node<'t>, Node<'t>)>,
+    /// Object-like macros, mapped to their (textual) value.
+    defines: HashMap<&'t str, &'t str>,
+    /// Enumeration constants, mapped to their value.
+    enumerators: HashMap<&'t str, u64>,
+}
+
+impl<'t> Types<'t> {
+    /// Collect the type definitions and constants in `tree`.
+    pub fn new(tree: &'t Tree, code: &'t [u8]) -> Self {
+        let mut types = Self {
+            code,
+            structs: HashMap::new(),
+            unions: HashMap::new(),
+            typedefs: HashMap::new(),
+            defines: HashMap::new(),
+            enumerators: HashMap::new(),
+        };
+
+        let mut enums = Vec::new();
+        for node in descendants(tree.root_node()) {
+            match node.kind() {
+                "struct_specifier" | "union_specifier" => {
+                    let (Some(name), Some(_body)) = (
+                        node.child_by_field_name("name"),
+                        node.child_by_field_name("body"),
+                    ) else {
+                        continue
+                    };
+                    let map = if node.kind() == "struct_specifier" {
+                        &mut types.structs
+                    } else {
+                        &mut types.unions
+                    };
+                    let _prev = map.insert(text(name, code), node);
+                },
+                "type_definition" => {
+                    let Some(ty) = node.child_by_field_name("type") else {
+                        continue
+                    };
+                    let mut cursor = node.walk();
+                    for declarator in node.children_by_field_name("declarator", &mut cursor) {
+                        if let Some(name) = declarator_name(declarator) {
+                            let _prev = types.typedefs.insert(text(name, code), (ty, declarator));
+                        }
+                    }
+                },
+                "preproc_def" => {
+                    if let (Some(name), Some(value)) = (
+                        node.child_by_field_name("name"),
+                        node.child_by_field_name("value"),
+                    ) {
+                        let _prev = types
+                            .defines
+                            .insert(text(name, code), text(value, code).trim());
+                    }
+                },
+                "enumerator_list" => {
+                    let () = enums.push(node);
+                },
+                _ => (),
+            }
+        }
+
+        for list in enums {
+            let mut next = 0;
+            let mut cursor = list.walk();
+            for enumerator in list.named_children(&mut cursor) {
+                let Some(name) = enumerator.child_by_field_name("name") else {
+                    continue
+                };
+                let value = match enumerator.child_by_field_name("value") {
+                    Some(value) => match types.eval(value) {
+                        Some(value) => value,
+                        None => continue,
+                    },
+                    None => next,
+                };
+                let _prev = types.enumerators.insert(text(name, code), value);
+                next = value.wrapping_add(1);
+            }
+        }
+        types
+    }
+
+    /// Evaluate a constant expression, such as an array size.
+    pub fn eval(&self, node: Node<'t>) -> Option<u64> {
+        self.eval_impl(node, 0)
+    }
+
+    fn eval_impl(&self, node: Node<'t>, depth: usize) -> Option<u64> {
+        if depth > MAX_DEPTH {
+            return None
+        }
+
+        match node.kind() {
+            "number_literal" => parse_number(text(node, self.code)),
+            "identifier" => self.constant(text(node, self.code), depth + 1),
+            "parenthesized_expression" => self.eval_impl(node.named_child(0)?, depth + 1),
+            "binary_expression" => {
+                let left = self.eval_impl(node.child_by_field_name("left")?, depth + 1)?;
+                let right = self.eval_impl(node.child_by_field_name("right")?, depth + 1)?;
+                match node.child_by_field_name("operator")?.kind() {
+                    "+" => left.checked_add(right),
+                    "-" => left.checked_sub(right),
+                    "*" => left.checked_mul(right),
+                    "/" => left.checked_div(right),
+                    "<<" => left.checked_shl(u32::try_from(right).ok()?),
+                    ">>" => left.checked_shr(u32::try_from(right).ok()?),
+                    _ => None,
+                }
+            },
+            "sizeof_expression" => {
+                let ty = node.child_by_field_name("type")?;
+                self.layout_impl(ty.child_by_field_name("type")?, ty, depth + 1)
+                    .map(|layout| layout.size)
+            },
+            _ => None,
+        }
+    }
+
+    /// Look up the value of a named constant.
+    fn constant(&self, name: &str, depth: usize) -> Option<u64> {
+        if let Some(value) = self.enumerators.get(name) {
+            return Some(*value)
+        }
+
+        let mut value = *self.defines.get(name)?;
+        while let Some(inner) = value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+            value = inner.trim();
+        }
+        parse_number(value).or_else(|| {
+            let is_ident = value
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_');
+            if is_ident && depth <= MAX_DEPTH {
+                self.constant(value, depth + 1)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Determine the layout of an object declared with type `ty` and
+    /// (possibly abstract or initializing) declarator `declarator`.
+    pub fn layout(&self, ty: Node<'t>, declarator: Node<'t>) -> Option<Layout> {
+        self.layout_impl(ty, declarator, 0)
+    }
+
+    /// Determine the layout of the object pointed to by a pointer
+    /// declared with type `ty` and (possibly abstract or initializing)
+    /// declarator `declarator`.
+    ///
+    /// As for pointer arithmetic in GNU C, `void` is considered to be
+    /// one byte in size.
+    pub fn pointee(&self, ty: Node<'t>, declarator: Node<'t>) -> Option<Layout> {
+        let mut decl = declarator;
+        loop {
+            decl = match decl.kind() {
+                "pointer_declarator" | "abstract_pointer_declarator" => break,
+                "init_declarator" => decl.child_by_field_name("declarator")?,
+                "parenthesized_declarator" | "abstract_parenthesized_declarator" => {
+                    decl.named_child(0)?
+                },
+                _ => return None,
+            };
+        }
+
+        let layout = match decl.child_by_field_name("declarator") {
+            Some(inner) => self.layout(ty, inner),
+            None => self.type_layout(ty, 0),
+        };
+        layout.or_else(|| (text(ty, self.code) == "void").then_some(Layout::new(1, 1)))
+    }
+
+    fn layout_impl(&self, ty: Node<'t>, declarator: Node<'t>, depth: usize) -> Option<Layout> {
+        // Collect the derivations from the outermost declarator
+        // inwards. The derivation closest to the declared name is the
+        // one applied last to the base type.
+        let mut derivations = Vec::new();
+        let mut value = None;
+        let mut node = Some(declarator);
+        while let Some(decl) = node {
+            node = match decl.kind() {
+                "init_declarator" => {
+                    value = decl.child_by_field_name("value");
+                    decl.child_by_field_name("declarator")
+                },
+                "pointer_declarator" | "abstract_pointer_declarator" => {
+                    let () = derivations.push(Derivation::Pointer);
+                    decl.child_by_field_name("declarator")
+                },
+                "array_declarator" | "abstract_array_declarator" => {
+                    let count = match decl.child_by_field_name("size") {
+                        Some(size) => Some(self.eval_impl(size, depth + 1)?),
+                        // An array sized by its initializer.
+                        None => value.and_then(|value| match value.kind() {
+                            "string_literal" | "concatenated_string" => {
+                                string_len(value, self.code)
+                                    .and_then(|len| u64::try_from(len + 1).ok())
+                            },
+                            "initializer_list" => u64::try_from(value.named_child_count()).ok(),
+                            _ => None,
+                        }),
+                    };
+                    let () = derivations.push(Derivation::Array(count));
+                    decl.child_by_field_name("declarator")
+                },
+                "parenthesized_declarator" | "abstract_parenthesized_declarator" => {
+                    decl.named_child(0)
+                },
+                // We don't care about the size of functions.
+                "function_declarator" | "abstract_function_declarator" => return None,
+                _ => None,
+            };
+        }
+
+        let pointer = derivations
+            .iter()
+            .rposition(|derivation| matches!(derivation, Derivation::Pointer));
+        let (mut layout, derivations) = match pointer {
+            // Anything behind a pointer is irrelevant.
+            Some(idx) => (Layout::new(8, 8), &derivations[idx + 1..]),
+            None => (self.type_layout(ty, depth + 1)?, &derivations[..]),
+        };
+
+        for derivation in derivations.iter().rev() {
+            if let Derivation::Array(count) = derivation {
+                // Flexible array members don't occupy any space.
+                layout.size = layout.size.checked_mul(count.unwrap_or(0))?;
+            }
+        }
+        Some(layout)
+    }
+
+    /// Determine the layout of a type specifier.
+    fn type_layout(&self, ty: Node<'t>, depth: usize) -> Option<Layout> {
+        if depth > MAX_DEPTH {
+            return None
+        }
+
+        let name = text(ty, self.code);
+        match ty.kind() {
+            "primitive_type" => builtin(name),
+            "sized_type_specifier" => {
+                let words = name.split_whitespace().collect::<Vec<_>>();
+                let size = if words.contains(&"char") {
+                    1
+                } else if words.contains(&"short") {
+                    2
+                } else if words.contains(&"long") {
+                    8
+                } else {
+                    4
+                };
+                Some(Layout::new(size, size))
+            },
+            "type_identifier" => {
+                if let Some((ty, declarator)) = self.typedefs.get(name) {
+                    self.layout_impl(*ty, *declarator, depth + 1)
+                } else {
+                    builtin(name)
+                }
+            },
+            "struct_specifier" | "union_specifier" => match self.record_def(ty) {
+                Some((def, is_union)) => self
+                    .record_layout(def, is_union, depth + 1)
+                    .map(|(layout, _members)| layout),
+                None => builtin(&name.split_whitespace().collect::<Vec<_>>().join(" ")),
+            },
+            "enum_specifier" => Some(Layout::new(4, 4)),
+            _ => None,
+        }
+    }
+
+    /// Find the definition of the struct or union denoted by the type
+    /// specifier `ty`, if any, along with whether it is a union.
+    fn record_def(&self, mut ty: Node<'t>) -> Option<(Node<'t>, bool)> {
+        for _ in 0..MAX_DEPTH {
+            match ty.kind() {
+                "struct_specifier" | "union_specifier" => {
+                    let is_union = ty.kind() == "union_specifier";
+                    if ty.child_by_field_name("body").is_some() {
+                        return Some((ty, is_union))
+                    }
+                    let name = text(ty.child_by_field_name("name")?, self.code);
+                    let map = if is_union {
+                        &self.unions
+                    } else {
+                        &self.structs
+                    };
+                    return map.get(name).map(|def| (*def, is_union))
+                },
+                "type_identifier" => {
+                    let (def, declarator) = self.typedefs.get(text(ty, self.code))?;
+                    // Only a plain alias denotes the record itself.
+                    if declarator.kind() != "type_identifier" {
+                        return None
+                    }
+                    ty = *def;
+                },
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    /// Determine the layout of a struct or union definition, along with
+    /// the layout of its members.
+    fn record_layout(
+        &self,
+        def: Node<'t>,
+        is_union: bool,
+        depth: usize,
+    ) -> Option<(Layout, Vec<Member<'t>>)> {
+        if depth > MAX_DEPTH {
+            return None
+        }
+
+        let body = def.child_by_field_name("body")?;
+        let packed = {
+            let mut cursor = def.walk();
+            def.children(&mut cursor).any(|child| {
+                child.kind() == "attribute_specifier" && text(child, self.code).contains("packed")
+            })
+        };
+
+        // Offsets are tracked in bits, to accommodate bit fields.
+        let mut members = Vec::new();
+        let mut offset = 0u64;
+        let mut size = 0u64;
+        let mut align = 1u64;
+        let mut cursor = body.walk();
+        for field in body.named_children(&mut cursor) {
+            if field.kind() != "field_declaration" {
+                continue
+            }
+            let ty = field.child_by_field_name("type")?;
+
+            let mut declarators = Vec::new();
+            let mut field_cursor = field.walk();
+            for declarator in field.children_by_field_name("declarator", &mut field_cursor) {
+                let () = declarators.push(Some(declarator));
+            }
+            if declarators.is_empty() {
+                // An anonymous struct or union member.
+                let () = declarators.push(None);
+            }
+
+            let bits = {
+                let mut field_cursor = field.walk();
+                field
+                    .named_children(&mut field_cursor)
+                    .find(|child| child.kind() == "bitfield_clause")
+                    .and_then(|clause| clause.named_child(0))
+            };
+
+            for declarator in declarators {
+                let mut layout = match declarator {
+                    Some(declarator) => self.layout_impl(ty, declarator, depth + 1)?,
+                    None => self.type_layout(ty, depth + 1)?,
+                };
+                if packed {
+                    layout.align = 1;
+                }
+                align = align.max(layout.align);
+
+                let start = if is_union { 0 } else { offset };
+                let (start, end) = match bits {
+                    Some(bits) => {
+                        let bits = self.eval_impl(bits, depth + 1)?;
+                        let unit = layout.size * 8;
+                        // A bit field is placed into the current
+                        // storage unit if it fits.
+                        let start = if unit > 0 && start % unit + bits > unit {
+                            align_up(start, unit)
+                        } else {
+                            start
+                        };
+                        (start, start + bits)
+                    },
+                    None => {
+                        let start = align_up(start, layout.align * 8);
+                        (start, start + layout.size * 8)
+                    },
+                };
+                if !is_union {
+                    offset = end;
+                }
+                size = size.max(end);
+
+                // Only members embedding a record directly (as opposed
+                // to, say, an array of them) are of interest.
+                let record = match declarator {
+                    Some(declarator) if declarator.kind() != "field_identifier" => None,
+                    _ => self.record_def(ty),
+                };
+                let () = members.push(Member {
+                    name: declarator,
+                    decl: field,
+                    offset: start,
+                    size: end - start,
+                    record,
+                });
+            }
+        }
+
+        let size = align_up(size.div_ceil(8), align);
+        Some((Layout::new(size, align), members))
+    }
+
+    /// Find the padding holes in the struct denoted by the type
+    /// specifier `ty`, including those of directly embedded structs.
+    ///
+    /// Holes are reported in bytes; padding bits following bit fields
+    /// are not considered.
+    pub fn holes(&self, ty: Node<'t>) -> Vec<Hole<'t>> {
+        let mut holes = Vec::new();
+        if let Some((def, false)) = self.record_def(ty) {
+            let () = self.collect_holes(def, 0, &mut holes);
+        }
+        holes
+    }
+
+    fn collect_holes(&self, def: Node<'t>, depth: usize, holes: &mut Vec<Hole<'t>>) {
+        let Some((layout, members)) = self.record_layout(def, false, depth + 1) else {
+            return
+        };
+
+        let mut end = 0u64;
+        let mut prev = None::<&Member<'t>>;
+        for member in &members {
+            let gap = (member.offset / 8).saturating_sub(end.div_ceil(8));
+            if let Some(prev) = prev.filter(|_| gap > 0) {
+                let () = holes.push(Hole {
+                    after: prev.name,
+                    node: prev.name.unwrap_or(prev.decl),
+                    size: gap,
+                    trailing: false,
+                });
+            }
+            if let Some((def, false)) = member.record {
+                let () = self.collect_holes(def, depth + 1, holes);
+            }
+            end = end.max(member.offset + member.size);
+            prev = Some(member);
+        }
+
+        let gap = layout.size.saturating_sub(end.div_ceil(8));
+        if let Some(prev) = prev.filter(|_| gap > 0) {
+            let () = holes.push(Hole {
+                after: prev.name,
+                node: prev.name.unwrap_or(prev.decl),
+                size: gap,
+                trailing: true,
+            });
+        }
+    }
+}
+
+
+/// Look up a type in the table of well-known types.
+fn builtin(name: &str) -> Option<Layout> {
+    BUILTIN_TYPES
+        .iter()
+        .find(|(builtin, _size, _align)| *builtin == name)
+        .map(|(_name, size, align)| Layout::new(*size, *align))
+}
+
+
+/// Retrieve the identifier declared by a declarator.
+pub(crate) fn declarator_name(mut declarator: Node<'_>) -> Option<Node<'_>> {
+    loop {
+        match declarator.kind() {
+            "identifier" | "field_identifier" | "type_identifier" => break Some(declarator),
+            "parenthesized_declarator" => declarator = declarator.named_child(0)?,
+            _ => declarator = declarator.child_by_field_name("declarator")?,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+
+    use tree_sitter::Parser;
+    use tree_sitter_bpf_c::LANGUAGE;
+
+
+    /// Compute the layout of the variable declared by the last
+    /// declaration in `code`.
+    fn layout_of(code: &str) -> Option<Layout> {
+        let mut parser = Parser::new();
+        let () = parser.set_language(&LANGUAGE.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let types = Types::new(&tree, code.as_bytes());
+        let decl = descendants(tree.root_node())
+            .filter(|node| node.kind() == "declaration")
+            .last()
+            .unwrap();
+        types.layout(
+            decl.child_by_field_name("type").unwrap(),
+            decl.child_by_field_name("declarator").unwrap(),
+        )
+    }
+
+
+    /// Check that we compute the layouts of basic types and
+    /// derivations correctly.
+    #[test]
+    fn basic() {
+        assert_eq!(layout_of("u32 x;"), Some(Layout::new(4, 4)));
+        assert_eq!(layout_of("unsigned long x;"), Some(Layout::new(8, 8)));
+        assert_eq!(layout_of("char *x;"), Some(Layout::new(8, 8)));
+        assert_eq!(layout_of("char x[16];"), Some(Layout::new(16, 1)));
+        assert_eq!(layout_of("int *x[4];"), Some(Layout::new(32, 8)));
+        assert_eq!(layout_of("int (*x)[4];"), Some(Layout::new(8, 8)));
+        assert_eq!(layout_of("char x[] = \"abc\";"), Some(Layout::new(4, 1)));
+        assert_eq!(
+            layout_of(r#"char x[] = "a\tb\x41\n" "\u00e9";"#),
+            Some(Layout::new(8, 1))
+        );
+        assert_eq!(layout_of("struct unknown x;"), None);
+        assert_eq!(layout_of("struct unknown *x;"), Some(Layout::new(8, 8)));
+    }
+
+    /// Check that we compute the layouts of objects pointed to as
+    /// expected.
+    #[test]
+    fn pointees() {
+        let pointee_of = |code: &str| {
+            let mut parser = Parser::new();
+            let () = parser.set_language(&LANGUAGE.into()).unwrap();
+            let tree = parser.parse(code, None).unwrap();
+            let types = Types::new(&tree, code.as_bytes());
+            let decl = descendants(tree.root_node())
+                .find(|node| node.kind() == "declaration")
+                .unwrap();
+            types.pointee(
+                decl.child_by_field_name("type").unwrap(),
+                decl.child_by_field_name("declarator").unwrap(),
+            )
+        };
+
+        assert_eq!(pointee_of("void *x;"), Some(Layout::new(1, 1)));
+        assert_eq!(pointee_of("u32 *x = y;"), Some(Layout::new(4, 4)));
+        assert_eq!(pointee_of("struct iphdr *x;"), Some(Layout::new(20, 4)));
+        assert_eq!(pointee_of("char **x;"), Some(Layout::new(8, 8)));
+        assert_eq!(pointee_of("u32 x;"), None);
+        assert_eq!(pointee_of("struct unknown *x;"), None);
+    }
+
+    /// Check that we lay out structs and unions as expected.
+    #[test]
+    fn records() {
+        let code = r#"
+            #define LEN (16)
+            enum { COUNT = 3, };
+            struct inner { u8 a; u64 b; };
+            typedef struct { u16 x; u8 y; } pair_t;
+            union u { u32 a; u8 b[6]; };
+            struct outer {
+                struct inner inner;
+                char name[LEN];
+                pair_t pairs[COUNT];
+                union u u;
+                struct outer *next;
+            } x;
+        "#;
+        // 16 (inner) + 16 (name) + 12 (pairs) + 8 (u) + 4 (padding) +
+        // 8 (next)
+        assert_eq!(layout_of(code), Some(Layout::new(64, 8)));
+    }
+
+    /// Check that we find padding holes, including those in embedded
+    /// structs.
+    #[test]
+    fn holes() {
+        let code = indoc! { r#"
+            struct inner { u8 a; u32 b; };
+            struct outer { u16 x; struct inner inner; u64 y; u8 z; } x;
+        "# };
+        let mut parser = Parser::new();
+        let () = parser.set_language(&LANGUAGE.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let types = Types::new(&tree, code.as_bytes());
+        let decl = descendants(tree.root_node())
+            .filter(|node| node.kind() == "declaration")
+            .last()
+            .unwrap();
+        let holes = types
+            .holes(decl.child_by_field_name("type").unwrap())
+            .into_iter()
+            .map(|hole| {
+                (
+                    text(hole.after.unwrap(), code.as_bytes()),
+                    hole.size,
+                    hole.trailing,
+                )
+            })
+            .collect::<Vec<_>>();
+        let expected = [
+            ("x", 2, false),
+            ("a", 3, false),
+            ("inner", 4, false),
+            ("z", 7, true),
+        ];
+        assert_eq!(holes, expected);
+    }
+
+    /// Check that bit fields are packed into their storage units.
+    #[test]
+    fn bit_fields() {
+        let code = r#"
+            struct flags { u32 a: 3; u32 b: 30; u8 c; } x;
+        "#;
+        assert_eq!(layout_of(code), Some(Layout::new(12, 4)));
+    }
+}
@@ -0,0 +1,77 @@
+//! Tests for the `bpf-printk` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use bpflint::LintOpts;
+
+use crate::util::lint_report;
+use crate::util::lint_report_opts;
+
+
+/// Lint options with the `bpf-printk` lint enabled.
+fn printk_opts() -> LintOpts {
+    LintOpts {
+        enabled_lints: vec!["bpf-printk".to_string()],
+        ..Default::default()
+    }
+}
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static void trace(int pid)
+        {
+            bpf_printk("pid: %d\n", pid);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [bpf-printk] bpf_printk() writes to the global trace pipe buffer, which is slow and shared system-wide; it is meant for debugging only and should not be left in production code
+          --> <stdin>:2:4
+          | 
+        2 |     bpf_printk("pid: %d\n", pid);
+          |     ^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report_opts(code, &printk_opts()), expected);
+}
+
+
+/// Check that the lint is disabled by default.
+#[test]
+fn disabled_by_default() {
+    let code = indoc! { r#"
+        static void trace(int pid)
+        {
+            bpf_printk("pid: %d\n", pid);
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
+
+
+/// Check that the lint can be enabled using an inline comment.
+#[test]
+fn inline_enable() {
+    let code = indoc! { r#"
+        static void trace(int pid)
+        {
+            bpf_printk("pid: %d\n", pid);
+            // bpflint: enable=bpf-printk
+            bpf_printk("done\n");
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [bpf-printk] bpf_printk() writes to the global trace pipe buffer, which is slow and shared system-wide; it is meant for debugging only and should not be left in production code
+          --> <stdin>:4:4
+          | 
+        4 |     bpf_printk("done\n");
+          |     ^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
@@ -0,0 +1,87 @@
+//! Tests for the `call-depth` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag call chains exceeding eight frames.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        int f8(int n) { return n; }
+        int f7(int n) { return f8(n); }
+        int f6(int n) { return f7(n); }
+        int f5(int n) { return f6(n); }
+        static __always_inline int inlined(int n) { return f5(n); }
+        int f4(int n) { return inlined(n); }
+        int f3(int n) { return f4(n); }
+        int f2(int n) { return f3(n); }
+        int f1(int n) { return f2(n) + f8(n); }
+
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            return f1(0);
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [call-depth] call chain of program `handle__sched_switch` is 9 stack frames deep, exceeding the kernel limit of 8
+          --> <stdin>:11:4
+           | 
+        11 | int handle__sched_switch(u64 *ctx)
+           |     ^^^^^^^^^^^^^^^^^^^^
+           | 
+          ::: <stdin>:13:11
+           | 
+        13 |     return f1(0);
+           |            ----- `f1` is called here (frame 2)
+           | 
+          ::: <stdin>:1:23
+           | 
+         1 | int f7(int n) { return f8(n); }
+           |                        ----- `f8` is called here (frame 9)
+         2 | int f6(int n) { return f7(n); }
+           |                        ----- `f7` is called here (frame 8)
+         3 | int f5(int n) { return f6(n); }
+           |                        ----- `f6` is called here (frame 7)
+         4 | static __always_inline int inlined(int n) { return f5(n); }
+           |                                                    ----- `f5` is called here (frame 6)
+         5 | int f4(int n) { return inlined(n); }
+           |                        ---------- `inlined` is called (and inlined) here
+         6 | int f3(int n) { return f4(n); }
+           |                        ----- `f4` is called here (frame 5)
+         7 | int f2(int n) { return f3(n); }
+           |                        ----- `f3` is called here (frame 4)
+         8 | int f1(int n) { return f2(n) + f8(n); }
+           |                        ----- `f2` is called here (frame 3)
+           | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that call chains within the limit are not flagged.
+#[test]
+fn no_match_within_limit() {
+    let code = indoc! { r#"
+        static __always_inline int f4(int n) { return n; }
+        static __always_inline int f3(int n) { return f4(n); }
+        int f2(int n) { return f3(n); }
+        int f1(int n) { return f2(n); }
+
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            return f1(0);
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
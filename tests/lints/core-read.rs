@@ -19,6 +19,8 @@ fn basic() {
             int next_prev_pid = BPF_CORE_READ(next, pid);
             return 0;
         }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     let expected = indoc! { r#"
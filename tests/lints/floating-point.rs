@@ -0,0 +1,57 @@
+//! Tests for the `floating-point` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int scale(int value)
+        {
+            double factor = 1.5;
+            return value * factor * 0x1p3;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [floating-point] floating-point types and literals are not supported by BPF: the instruction set has no floating-point operations and the kernel does not preserve FPU state for BPF programs; use integer or fixed-point arithmetic instead
+          --> <stdin>:2:4
+          | 
+        2 |     double factor = 1.5;
+          |     ^^^^^^
+          | 
+        warning: [floating-point] floating-point types and literals are not supported by BPF: the instruction set has no floating-point operations and the kernel does not preserve FPU state for BPF programs; use integer or fixed-point arithmetic instead
+          --> <stdin>:2:20
+          | 
+        2 |     double factor = 1.5;
+          |                     ^^^
+          | 
+        warning: [floating-point] floating-point types and literals are not supported by BPF: the instruction set has no floating-point operations and the kernel does not preserve FPU state for BPF programs; use integer or fixed-point arithmetic instead
+          --> <stdin>:3:28
+          | 
+        3 |     return value * factor * 0x1p3;
+          |                             ^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that we don't flag integer literals that merely look
+/// like floating-point ones.
+#[test]
+fn no_match() {
+    let code = indoc! { r#"
+        static int scale(u64 value)
+        {
+            u64 a = 0x1e3;
+            u64 b = 10UL;
+            return value * a / b;
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
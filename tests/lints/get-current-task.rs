@@ -22,6 +22,8 @@ fn basic() {
 
           return 1;
         }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     let expected = indoc! { r#"
@@ -0,0 +1,110 @@
+//! Tests for the `gpl-only-helper` lint.
+
+use indoc::formatdoc;
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag GPL-only helpers used by a program with a
+/// license that is not GPL-compatible.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            struct task_struct *prev = (struct task_struct *)ctx[1];
+            bpf_probe_read_kernel(&pid, sizeof(pid), &prev->pid);
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "Proprietary";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [gpl-only-helper] GPL-only helpers can only be used by programs with a GPL-compatible license; loading the program will fail
+          --> <stdin>:4:4
+          | 
+        4 |     bpf_probe_read_kernel(&pid, sizeof(pid), &prev->pid);
+          |     ^^^^^^^^^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:8:32
+          | 
+        8 | char LICENSE[] SEC("license") = "Proprietary";
+          |                                 ------------- license `Proprietary` is not GPL-compatible
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that we do not flag anything for GPL-compatible licenses.
+#[test]
+fn gpl_compatible() {
+    for license in ["GPL", "Dual BSD/GPL"] {
+        let code = formatdoc! { r#"
+            SEC("tp_btf/sched_switch")
+            int handle__sched_switch(u64 *ctx)
+            {{
+                struct task_struct *prev = (struct task_struct *)ctx[1];
+                bpf_probe_read_kernel(&pid, sizeof(pid), &prev->pid);
+                return 0;
+            }}
+
+            char LICENSE[] SEC("license") = "{license}";
+        "# };
+        assert_eq!(lint_report(&code), "", "{license}");
+    }
+}
+
+
+/// Make sure that helpers usable by any program are not flagged.
+#[test]
+fn no_match_non_gpl_helper() {
+    let code = indoc! { r#"
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            u64 now = bpf_ktime_get_ns();
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "Proprietary";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
+
+
+/// Check that we report a missing license definition.
+#[test]
+fn missing_license() {
+    let code = indoc! { r#"
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            return 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [gpl-only-helper] no license defined; programs without a GPL-compatible license in a SEC("license") section can't use GPL-only helpers
+          --> <stdin>:0:0
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that files without programs don't need a license.
+#[test]
+fn no_programs() {
+    let code = indoc! { r#"
+        static int helper(int a)
+        {
+            return a * 2;
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
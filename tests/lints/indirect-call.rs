@@ -0,0 +1,63 @@
+//! Tests for the `indirect-call` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int dispatch(struct ops *ops, int (*handler)(int), int arg)
+        {
+            handler(arg);
+            (*handler)(arg);
+            return ops->handle(arg);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [indirect-call] call through function pointer `handler`; BPF has no indirect calls and the verifier requires every call target to be known at load time; call the function directly, dispatch with a `switch`, or use `bpf_tail_call()` with a program array map
+          --> <stdin>:2:4
+          | 
+        2 |     handler(arg);
+          |     ^^^^^^^
+          | 
+        warning: [indirect-call] call through function pointer `(*handler)`; BPF has no indirect calls and the verifier requires every call target to be known at load time; call the function directly, dispatch with a `switch`, or use `bpf_tail_call()` with a program array map
+          --> <stdin>:3:4
+          | 
+        3 |     (*handler)(arg);
+          |     ^^^^^^^^^^
+          | 
+        warning: [indirect-call] call through function pointer `ops->handle`; BPF has no indirect calls and the verifier requires every call target to be known at load time; call the function directly, dispatch with a `switch`, or use `bpf_tail_call()` with a program array map
+          --> <stdin>:4:11
+          | 
+        4 |     return ops->handle(arg);
+          |            ^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that we don't flag direct calls to functions and
+/// (legacy) helper definitions.
+#[test]
+fn no_match() {
+    let code = indoc! { r#"
+        static long (*bpf_trace_printk)(const char *fmt, u32 fmt_size, ...) = (void *) 6;
+
+        static int helper(int arg)
+        {
+            return arg;
+        }
+
+        static int dispatch(int arg)
+        {
+            return helper(arg) + bpf_trace_printk("", 1);
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,30 @@
+//! Tests for the `inline-asm` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int clamp(int value)
+        {
+            asm volatile("%[value] &= 0xff" : [value] "+r"(value));
+            barrier_var(value);
+            return value;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [inline-asm] inline assembly in BPF programs bypasses the compiler's checks and is verified only at load time, where errors are hard to trace back to the source; prefer the macros provided by bpf_helpers.h, such as barrier() and barrier_var(), which wrap well-known patterns
+          --> <stdin>:2:4
+          | 
+        2 |     asm volatile("%[value] &= 0xff" : [value] "+r"(value));
+          |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
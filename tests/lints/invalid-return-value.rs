@@ -0,0 +1,158 @@
+//! Tests for the `invalid-return-value` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag return values meant for a different program
+/// type.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        SEC("tc")
+        int tc_prog(struct __sk_buff *skb)
+        {
+            return XDP_PASS;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [invalid-return-value] `XDP_PASS` is not a valid return value for TC programs; expected a `TC_ACT_*` value, such as `TC_ACT_OK` or `TC_ACT_SHOT`
+          --> <stdin>:3:11
+          | 
+        3 |     return XDP_PASS;
+          |            ^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag out of range numbers, also inside of conditional
+/// expressions.
+#[test]
+fn out_of_range() {
+    let code = indoc! { r#"
+        SEC("lsm/file_open")
+        int BPF_PROG(check_open, struct file *file)
+        {
+            if (!file)
+                return EPERM;
+            return file->f_flags ? 1 : 0;
+        }
+
+        SEC("xdp")
+        int xdp_prog(struct xdp_md *ctx)
+        {
+            return TC_ACT_OK;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [invalid-return-value] `EPERM` is not a valid return value for LSM programs; expected `0` or a negative error code
+          --> <stdin>:4:15
+          | 
+        4 |         return EPERM;
+          |                ^^^^^
+          | 
+        warning: [invalid-return-value] `1` is not a valid return value for LSM programs; expected `0` or a negative error code
+          --> <stdin>:5:27
+          | 
+        5 |     return file->f_flags ? 1 : 0;
+          |                            ^
+          | 
+        warning: [invalid-return-value] `TC_ACT_OK` is not a valid return value for XDP programs; expected one of `XDP_ABORTED`, `XDP_DROP`, `XDP_PASS`, `XDP_TX`, or `XDP_REDIRECT`
+          --> <stdin>:11:11
+           | 
+        11 |     return TC_ACT_OK;
+           |            ^^^^^^^^^
+           | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that cgroup LSM programs are held to their `0` or `1` return
+/// values.
+#[test]
+fn lsm_cgroup() {
+    let code = indoc! { r#"
+        SEC("lsm_cgroup/socket_bind")
+        int BPF_PROG(restrict_bind, struct socket *sock)
+        {
+            if (sock->type == SOCK_RAW)
+                return -EPERM;
+            return 1;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [invalid-return-value] `-EPERM` is not a valid return value for cgroup LSM programs; expected `0` (deny) or `1` (allow)
+          --> <stdin>:4:15
+          | 
+        4 |         return -EPERM;
+          |                ^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that valid and unknown return values are not flagged.
+#[test]
+fn no_match_valid() {
+    let code = indoc! { r#"
+        SEC("cgroup_skb/egress")
+        int egress(struct __sk_buff *skb)
+        {
+            if (skb->len > 1000)
+                return 0;
+            return 3;
+        }
+
+        SEC("lsm.s/bprm_check_security")
+        int BPF_PROG(check, struct linux_binprm *bprm)
+        {
+            if (!bprm)
+                return -EPERM;
+            /* Not an error number. */
+            return ENFORCED;
+        }
+
+        SEC("struct_ops.s/simple_init")
+        s32 BPF_PROG(simple_init)
+        {
+            return -1;
+        }
+
+        SEC("tcx/ingress")
+        int tcx_prog(struct __sk_buff *skb)
+        {
+            return skb->mark ? TCX_DROP : TC_ACT_OK;
+        }
+
+        SEC("socket")
+        int socket_prog(struct __sk_buff *skb)
+        {
+            return skb->len;
+        }
+
+        SEC("tp/syscalls/sys_enter_openat")
+        int tp_prog(void *ctx)
+        {
+            return XDP_PASS;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
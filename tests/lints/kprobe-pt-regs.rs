@@ -0,0 +1,47 @@
+//! Tests for the `kprobe-pt-regs` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        SEC("kprobe/do_unlinkat")
+        int handle_unlinkat(struct pt_regs *ctx)
+        {
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [kprobe-pt-regs] kprobe handlers taking `struct pt_regs *` have to extract arguments manually via PT_REGS_PARM<n>(); replace `int <name>(struct pt_regs *ctx)` with `int BPF_KPROBE(<name>, <type1> <arg1>, ...)` (or `int BPF_KRETPROBE(<name>, <type> <ret>)` for kretprobes) to receive typed arguments directly
+          --> <stdin>:1:20
+          | 
+        1 | int handle_unlinkat(struct pt_regs *ctx)
+          |                     ^^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that we only flag kprobe handlers.
+#[test]
+fn no_match_tracepoint() {
+    let code = indoc! { r#"
+        SEC("tp/syscalls/sys_enter_unlinkat")
+        int handle_unlinkat(struct pt_regs *ctx)
+        {
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,136 @@
+//! Tests for the `legacy-map-definition` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        struct bpf_map_def events SEC("maps") = {
+            .type = BPF_MAP_TYPE_HASH,
+            .key_size = sizeof(u32),
+            .value_size = sizeof(struct event),
+            .max_entries = MAX_ENTRIES,
+            .map_flags = BPF_F_NO_PREALLOC,
+        };
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [legacy-map-definition] legacy map definition `events` using `struct bpf_map_def` in SEC("maps") is no longer supported by libbpf 1.0; replace it with a BTF-defined map: `struct { __uint(type, BPF_MAP_TYPE_HASH); __type(key, u32); __type(value, struct event); __uint(max_entries, MAX_ENTRIES); __uint(map_flags, BPF_F_NO_PREALLOC); } events SEC(".maps");`
+          --> <stdin>:0:0
+          | 
+        0 | struct bpf_map_def events SEC("maps") = {
+          | ^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we can convert positional initializers.
+#[test]
+fn positional() {
+    let code = indoc! { r#"
+        struct bpf_map_def counts = { BPF_MAP_TYPE_ARRAY, sizeof(u32), sizeof(u64), 1 };
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [legacy-map-definition] legacy map definition `counts` using `struct bpf_map_def` is no longer supported by libbpf 1.0; replace it with a BTF-defined map: `struct { __uint(type, BPF_MAP_TYPE_ARRAY); __type(key, u32); __type(value, u64); __uint(max_entries, 1); } counts SEC(".maps");`
+          --> <stdin>:0:0
+          | 
+        0 | struct bpf_map_def counts = { BPF_MAP_TYPE_ARRAY, sizeof(u32), sizeof(u64), 1 };
+          | ^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we can convert a single line definition with both the
+/// legacy type and section.
+#[test]
+fn positional_sec() {
+    let code = indoc! { r#"
+        struct bpf_map_def counts SEC("maps") = { BPF_MAP_TYPE_ARRAY, sizeof(u32), sizeof(u64), 1 };
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [legacy-map-definition] legacy map definition `counts` using `struct bpf_map_def` in SEC("maps") is no longer supported by libbpf 1.0; replace it with a BTF-defined map: `struct { __uint(type, BPF_MAP_TYPE_ARRAY); __type(key, u32); __type(value, u64); __uint(max_entries, 1); } counts SEC(".maps");`
+          --> <stdin>:0:0
+          | 
+        0 | struct bpf_map_def counts SEC("maps") = { BPF_MAP_TYPE_ARRAY, sizeof(u32), sizeof(u64), 1 };
+          | ^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag definitions with the `SEC()` specifier placed
+/// before the variable name.
+#[test]
+fn sec_before_name() {
+    let code = indoc! { r#"
+        struct bpf_map_def SEC("maps") events = {
+            .type = BPF_MAP_TYPE_HASH,
+            .key_size = sizeof(u32),
+            .value_size = sizeof(u64),
+            .max_entries = 1024,
+        };
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [legacy-map-definition] legacy map definition `events` using `struct bpf_map_def` in SEC("maps") is no longer supported by libbpf 1.0; replace it with a BTF-defined map: `struct { __uint(type, BPF_MAP_TYPE_HASH); __type(key, u32); __type(value, u64); __uint(max_entries, 1024); } events SEC(".maps");`
+          --> <stdin>:0:0
+          | 
+        0 | struct bpf_map_def SEC("maps") events = {
+          | ^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag definitions without an initializer.
+#[test]
+fn no_initializer() {
+    let code = indoc! { r#"
+        struct bpf_map_def SEC("maps") events;
+        int counts SEC("maps");
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [legacy-map-definition] legacy map definition `events` using `struct bpf_map_def` in SEC("maps") is no longer supported by libbpf 1.0; replace it with a BTF-defined map in SEC(".maps"), using `__uint()` and `__type()` to describe it
+          --> <stdin>:0:0
+          | 
+        0 | struct bpf_map_def SEC("maps") events;
+          | ^^^^^^^^^^^^^^^^^^
+          | 
+        warning: [legacy-map-definition] legacy map definition `counts` in SEC("maps") is no longer supported by libbpf 1.0; replace it with a BTF-defined map in SEC(".maps"), using `__uint()` and `__type()` to describe it
+          --> <stdin>:1:11
+          | 
+        1 | int counts SEC("maps");
+          |            ^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that we don't flag BTF-defined maps.
+#[test]
+fn no_match_btf_map() {
+    let code = indoc! { r#"
+        struct {
+            __uint(type, BPF_MAP_TYPE_HASH);
+            __type(key, u32);
+            __type(value, struct event);
+            __uint(max_entries, MAX_ENTRIES);
+        } events SEC(".maps");
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,116 @@
+//! Tests for the `lsm-deny` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use bpflint::Category;
+use bpflint::LintOpts;
+
+use crate::util::lint_report_for;
+use crate::util::lint_report_for_opts;
+
+
+/// Lint options with the `security` category enabled.
+fn security_opts() -> LintOpts {
+    LintOpts {
+        enabled_categories: vec![Category::Security],
+        ..Default::default()
+    }
+}
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        SEC("lsm/file_open")
+        int BPF_PROG(restrict_open, struct file *file)
+        {
+            if (file->f_flags & O_CREAT)
+                return -EPERM;
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        note: [lsm-deny] LSM program `restrict_open` attached to `lsm/file_open` may deny the operation by returning `-EPERM`
+          --> <stdin>:4:15
+          | 
+        4 |         return -EPERM;
+          |                ^^^^^^
+          | 
+    "# };
+    assert_eq!(
+        lint_report_for_opts(code, "lsm-deny", &security_opts()),
+        expected
+    );
+}
+
+
+/// Check that we flag denying returns of cgroup LSM programs, for
+/// which zero denies and one allows the operation.
+#[test]
+fn cgroup() {
+    let code = indoc! { r#"
+        SEC("lsm_cgroup/socket_bind")
+        int BPF_PROG(restrict_bind, struct socket *sock)
+        {
+            if (sock->type == SOCK_RAW)
+                return 0;
+            return 1;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        note: [lsm-deny] LSM program `restrict_bind` attached to `lsm_cgroup/socket_bind` may deny the operation by returning `0`
+          --> <stdin>:4:15
+          | 
+        4 |         return 0;
+          |                ^
+          | 
+    "# };
+    assert_eq!(
+        lint_report_for_opts(code, "lsm-deny", &security_opts()),
+        expected
+    );
+}
+
+
+/// Check that the lint is disabled by default.
+#[test]
+fn disabled_by_default() {
+    let code = indoc! { r#"
+        SEC("lsm/file_open")
+        int BPF_PROG(restrict_open, struct file *file)
+        {
+            if (file->f_flags & O_CREAT)
+                return -EPERM;
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report_for(code, "lsm-deny"), "");
+}
+
+
+/// Make sure that we don't flag LSM programs that only ever allow
+/// operations.
+#[test]
+fn no_match() {
+    let code = indoc! { r#"
+        SEC("lsm/file_open")
+        int BPF_PROG(audit_open, struct file *file)
+        {
+            bpf_printk("opening file");
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report_for_opts(code, "lsm-deny", &security_opts()), "");
+}
@@ -0,0 +1,105 @@
+//! Tests for the `missing-null-check` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+use crate::util::lint_report_for;
+
+
+/// Check that we flag a dereference of an unchecked map lookup result.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int helper(u32 *key)
+        {
+            struct value *v = bpf_map_lookup_elem(&values, key);
+            return v->count;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [missing-null-check] `v` may be NULL, as returned by `bpf_map_lookup_elem`, and must be checked before being dereferenced
+          --> <stdin>:3:11
+          | 
+        2 |     struct value *v = bpf_map_lookup_elem(&values, key);
+          |                       --------------------------------- `v` may be NULL after this call
+        3 |     return v->count;
+          |            ^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that we follow pointers through assignments and casts and
+/// report dereferences reachable on only some paths.
+#[test]
+fn assignment_on_some_path() {
+    let code = indoc! { r#"
+        static void helper(u32 key, bool check)
+        {
+            struct event *e;
+            struct event *copy;
+
+            e = (struct event *)bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (check && !e)
+                return;
+            copy = e;
+            copy->pid = 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [missing-null-check] `copy` may be NULL, as returned by `bpf_ringbuf_reserve`, and must be checked before being dereferenced
+          --> <stdin>:9:4
+          | 
+        9 |     copy->pid = 0;
+          |     ^^^^^^^^^
+          | 
+          ::: <stdin>:5:24
+          | 
+        5 |     e = (struct event *)bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+          |                         --------------------------------------- `copy` may be NULL after this call
+          | 
+    "# };
+    // The reservation is leaked as well, but that is none of our
+    // concern here.
+    assert_eq!(lint_report_for(code, "missing-null-check"), expected);
+}
+
+
+/// Make sure that the various forms of NULL checks are understood.
+#[test]
+fn no_match_checked() {
+    let code = indoc! { r#"
+        static int helper(struct task_struct *task, u32 cpu)
+        {
+            struct running_task *t;
+            struct value *v;
+            struct storage *s;
+
+            if (!(t = bpf_map_lookup_elem(&running_tasks, &cpu)))
+                return 0;
+            if (!t->bt_at || t->bt_at < 10)
+                return 0;
+
+            v = bpf_map_lookup_elem(&values, &cpu);
+            if (v == NULL) {
+                return 0;
+            }
+            v->count++;
+
+            s = bpf_task_storage_get(&storage, task, 0, 0);
+            if (s != NULL && s->value)
+                return s->value;
+
+            struct value *w = bpf_map_lookup_elem(&values, &cpu);
+            if (w)
+                return w->count + sizeof(*v);
+            return 0;
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -5,17 +5,73 @@ mod validate;
 
 // Tests for individual lints go below here.
 
+#[path = "bpf-printk.rs"]
+mod bpf_printk;
+#[path = "call-depth.rs"]
+mod call_depth;
 #[path = "core-read.rs"]
 mod core_read;
+#[path = "floating-point.rs"]
+mod floating_point;
 #[path = "get-current-task.rs"]
 mod get_current_task;
+#[path = "gpl-only-helper.rs"]
+mod gpl_only_helper;
+#[path = "indirect-call.rs"]
+mod indirect_call;
+#[path = "inline-asm.rs"]
+mod inline_asm;
+#[path = "invalid-return-value.rs"]
+mod invalid_return_value;
+#[path = "kprobe-pt-regs.rs"]
+mod kprobe_pt_regs;
+#[path = "legacy-map-definition.rs"]
+mod legacy_map_definition;
+#[path = "lsm-deny.rs"]
+mod lsm_deny;
+#[path = "missing-null-check.rs"]
+mod missing_null_check;
+#[path = "network-byte-order.rs"]
+mod network_byte_order;
+#[path = "non-constant-builtin-size.rs"]
+mod non_constant_builtin_size;
 #[path = "perfbuf-usage.rs"]
 mod perfbuf_usage;
 #[path = "pragma-unroll-for-loop-bounded.rs"]
 mod pragma_unroll_for_loop_bounded;
 #[path = "probe-read.rs"]
 mod probe_read;
+#[path = "probe-read-str.rs"]
+mod probe_read_str;
+#[path = "recursion.rs"]
+mod recursion;
+#[path = "section-attribute.rs"]
+mod section_attribute;
+#[path = "security-sensitive-helper.rs"]
+mod security_sensitive_helper;
+#[path = "setjmp-longjmp.rs"]
+mod setjmp_longjmp;
+#[path = "stack-usage.rs"]
+mod stack_usage;
+#[path = "struct-padding.rs"]
+mod struct_padding;
+#[path = "trace-printk.rs"]
+mod trace_printk;
+#[path = "unbalanced-lock.rs"]
+mod unbalanced_lock;
+#[path = "unchecked-packet-access.rs"]
+mod unchecked_packet_access;
+#[path = "unpaired-ringbuf-reserve.rs"]
+mod unpaired_ringbuf_reserve;
+#[path = "unreachable-code.rs"]
+mod unreachable_code;
+#[path = "unreachable-function.rs"]
+mod unreachable_function;
 #[path = "unstable-attach-point.rs"]
 mod unstable_attach_point;
 #[path = "untyped-map-member.rs"]
 mod untyped_map_member;
+#[path = "variable-length-array.rs"]
+mod variable_length_array;
+#[path = "variadic-function.rs"]
+mod variadic_function;
@@ -0,0 +1,81 @@
+//! Tests for the `network-byte-order` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag comparisons against constants in host byte order.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static bool is_http(struct ethhdr *eth, struct tcphdr *tcp)
+        {
+            if (eth->h_proto != ETH_P_IP)
+                return false;
+            return 80 == tcp->dest;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [network-byte-order] `h_proto` of `struct ethhdr` is in network byte order but compared to `ETH_P_IP` in host byte order; use `bpf_htons(ETH_P_IP)` instead
+          --> <stdin>:2:8
+          | 
+        2 |     if (eth->h_proto != ETH_P_IP)
+          |         ^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+        warning: [network-byte-order] `dest` of `struct tcphdr` is in network byte order but compared to `80` in host byte order; use `bpf_htons(80)` instead
+          --> <stdin>:4:11
+          | 
+        4 |     return 80 == tcp->dest;
+          |            ^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag assignments of constants in host byte order and
+/// suggest the right conversion for 32 bit fields.
+#[test]
+fn assignment() {
+    let code = indoc! { r#"
+        static void rewrite(struct iphdr *iph)
+        {
+            iph->daddr = 0x0a000001;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [network-byte-order] `daddr` of `struct iphdr` is in network byte order but assigned `0x0a000001` in host byte order; use `bpf_htonl(0x0a000001)` instead
+          --> <stdin>:2:4
+          | 
+        2 |     iph->daddr = 0x0a000001;
+          |     ^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that converted constants, single byte fields, and zero
+/// are not flagged.
+#[test]
+fn no_match_converted() {
+    let code = indoc! { r#"
+        static bool is_tcp(struct ethhdr *eth, struct iphdr *iph, struct udphdr *udp)
+        {
+            struct tcphdr tcp = {};
+
+            tcp.dest = bpf_htons(443);
+            if (eth->h_proto != bpf_htons(ETH_P_IP))
+                return false;
+            if (udp->dest == 0 || bpf_ntohs(udp->source) == 53)
+                return false;
+            return iph->protocol == IPPROTO_TCP;
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
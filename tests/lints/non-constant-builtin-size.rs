@@ -0,0 +1,50 @@
+//! Tests for the `non-constant-builtin-size` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static void copy(struct event *e, const char *src, u32 len)
+        {
+            __builtin_memcpy(e->data, src, len);
+            __builtin_memset(e->data, 0, len & 0xff);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [non-constant-builtin-size] size `len` of `__builtin_memcpy()` is not a compile-time constant; BPF programs can't call into a C library, so clang has to expand the builtin inline, which requires a constant size; use a constant upper bound or `bpf_probe_read_kernel()` for copies of variable length
+          --> <stdin>:2:35
+          | 
+        2 |     __builtin_memcpy(e->data, src, len);
+          |                                    ^^^
+          | 
+        warning: [non-constant-builtin-size] size `len & 0xff` of `__builtin_memset()` is not a compile-time constant; BPF programs can't call into a C library, so clang has to expand the builtin inline, which requires a constant size; use a constant upper bound or `bpf_probe_read_kernel()` for copies of variable length
+          --> <stdin>:3:33
+          | 
+        3 |     __builtin_memset(e->data, 0, len & 0xff);
+          |                                  ^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that sizes based on `sizeof` and constants are accepted.
+#[test]
+fn no_match() {
+    let code = indoc! { r#"
+        static void copy(struct event *e, const char *src)
+        {
+            __builtin_memcpy(e->data, src, sizeof(e->data));
+            __builtin_memset(e, 0, sizeof(*e));
+            __builtin_memcpy(e->comm, src, TASK_COMM_LEN - 1);
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -21,6 +21,8 @@ fn basic_for_bounded() {
             }
             return XDP_PASS;
         }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     let expected = indoc! { r#"
@@ -50,6 +52,8 @@ fn basic_for_unbounded() {
             }
             return XDP_PASS;
         }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     // No match
@@ -72,6 +76,8 @@ fn basic_while_bounded() {
             }
             return XDP_PASS;
         }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     // Nothing matches
@@ -94,6 +100,8 @@ fn basic_while_unbounded() {
             }
             return XDP_PASS;
         }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     // Nothing matches
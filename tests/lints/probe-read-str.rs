@@ -0,0 +1,28 @@
+//! Tests for the `probe-read-str` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static long read_comm(char *dst, const char *src)
+        {
+            return bpf_probe_read_str(dst, 16, src);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [probe-read-str] bpf_probe_read_str() is deprecated and replaced by bpf_probe_read_user_str() and bpf_probe_read_kernel_str(); replace bpf_probe_read_str(<dst>, <size>, <src>) with bpf_probe_read_kernel_str(<dst>, <size>, <src>) for kernel memory or bpf_probe_read_user_str(<dst>, <size>, <src>) for user memory; refer to bpf-helpers(7)
+          --> <stdin>:2:11
+          | 
+        2 |     return bpf_probe_read_str(dst, 16, src);
+          |            ^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
@@ -18,6 +18,8 @@ fn basic() {
             bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
             return 0;
         }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     let expected = indoc! { r#"
@@ -0,0 +1,89 @@
+//! Tests for the `recursion` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag direct recursion.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int fact(int n)
+        {
+            if (n <= 1)
+                return 1;
+            return n * fact(n - 1);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [recursion] `fact` calls itself recursively
+          --> <stdin>:4:15
+          | 
+        4 |     return n * fact(n - 1);
+          |                ^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag indirect recursion, reporting the cycle only
+/// once.
+#[test]
+fn indirect() {
+    let code = indoc! { r#"
+        static int is_odd(int n);
+
+        static int is_even(int n)
+        {
+            return n == 0 ? 1 : is_odd(n - 1);
+        }
+
+        static int is_odd(int n)
+        {
+            return n == 0 ? 0 : is_even(n - 1);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [recursion] `is_even` calls itself recursively via `is_even` -> `is_odd` -> `is_even`
+          --> <stdin>:4:24
+          | 
+        4 |     return n == 0 ? 1 : is_odd(n - 1);
+          |                         ^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:9:24
+          | 
+        9 |     return n == 0 ? 0 : is_even(n - 1);
+          |                         -------------- `is_odd` calls `is_even` here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that regular call chains are not flagged.
+#[test]
+fn no_match_call_chain() {
+    let code = indoc! { r#"
+        static int leaf(int n)
+        {
+            return n + 1;
+        }
+
+        static int middle(int n)
+        {
+            return leaf(n) + leaf(n + 1);
+        }
+
+        int entry(void)
+        {
+            return middle(1);
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,25 @@
+//! Tests for the `section-attribute` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        __attribute__((section("license"), used)) char LICENSE[] = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [section-attribute] __attribute__((section(...))) is superseded by the SEC() macro from bpf_helpers.h, which additionally marks the definition as used; replace `__attribute__((section("<name>")))` with `SEC("<name>")`
+          --> <stdin>:0:0
+          | 
+        0 | __attribute__((section("license"), used)) char LICENSE[] = "GPL";
+          | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
@@ -0,0 +1,110 @@
+//! Tests for the `security-sensitive-helper` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use bpflint::Category;
+use bpflint::LintOpts;
+
+use crate::util::lint_report_for;
+use crate::util::lint_report_for_opts;
+
+
+/// Lint options with the `security` category enabled.
+fn security_opts() -> LintOpts {
+    LintOpts {
+        enabled_categories: vec![Category::Security],
+        ..Default::default()
+    }
+}
+
+
+/// Check that we flag calls to security sensitive helpers, attributing
+/// them to every program reaching them.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static void notify(void)
+        {
+            bpf_send_signal(SIGKILL);
+        }
+
+        SEC("tp/syscalls/sys_enter_unlinkat")
+        int handle_unlinkat(void *ctx)
+        {
+            notify();
+            return 0;
+        }
+
+        SEC("tp/syscalls/sys_enter_kill")
+        int handle_kill(void *ctx)
+        {
+            bpf_send_signal(SIGKILL);
+            bpf_probe_write_user(ctx, "", 1);
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        note: [security-sensitive-helper] program `handle_unlinkat` attached to `tp/syscalls/sys_enter_unlinkat` calls `bpf_send_signal()`, which sends a signal to the current process
+          --> <stdin>:2:4
+          | 
+        2 |     bpf_send_signal(SIGKILL);
+          |     ^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:6:4
+          | 
+        6 | int handle_unlinkat(void *ctx)
+          |     --------------- `handle_unlinkat` reaches the call via `handle_unlinkat` -> `notify`
+          | 
+        note: [security-sensitive-helper] program `handle_kill` attached to `tp/syscalls/sys_enter_kill` calls `bpf_send_signal()`, which sends a signal to the current process
+          --> <stdin>:15:4
+           | 
+        15 |     bpf_send_signal(SIGKILL);
+           |     ^^^^^^^^^^^^^^^^^^^^^^^^
+           | 
+        note: [security-sensitive-helper] program `handle_kill` attached to `tp/syscalls/sys_enter_kill` calls `bpf_probe_write_user()`, which writes to the memory of the current user space process
+          --> <stdin>:16:4
+           | 
+        16 |     bpf_probe_write_user(ctx, "", 1);
+           |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+           | 
+    "# };
+    assert_eq!(
+        lint_report_for_opts(code, "security-sensitive-helper", &security_opts()),
+        expected
+    );
+}
+
+
+/// Check that the lint is disabled by default.
+#[test]
+fn disabled_by_default() {
+    let code = indoc! { r#"
+        static void notify(void)
+        {
+            bpf_send_signal(SIGKILL);
+        }
+
+        SEC("tp/syscalls/sys_enter_unlinkat")
+        int handle_unlinkat(void *ctx)
+        {
+            notify();
+            return 0;
+        }
+
+        SEC("tp/syscalls/sys_enter_kill")
+        int handle_kill(void *ctx)
+        {
+            bpf_send_signal(SIGKILL);
+            bpf_probe_write_user(ctx, "", 1);
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report_for(code, "security-sensitive-helper"), "");
+}
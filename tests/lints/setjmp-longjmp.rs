@@ -0,0 +1,38 @@
+//! Tests for the `setjmp-longjmp` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static jmp_buf env;
+
+        static int handle(void)
+        {
+            if (setjmp(env))
+                return 1;
+            longjmp(env, 1);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [setjmp-longjmp] setjmp()/longjmp() are not supported by BPF: non-local jumps would have to save and restore the stack and registers behind the verifier's back, which it cannot track; propagate errors via return values instead
+          --> <stdin>:4:8
+          | 
+        4 |     if (setjmp(env))
+          |         ^^^^^^
+          | 
+        warning: [setjmp-longjmp] setjmp()/longjmp() are not supported by BPF: non-local jumps would have to save and restore the stack and registers behind the verifier's back, which it cannot track; propagate errors via return values instead
+          --> <stdin>:6:4
+          | 
+        6 |     longjmp(env, 1);
+          |     ^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
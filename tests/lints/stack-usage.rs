@@ -0,0 +1,185 @@
+//! Tests for the `stack-usage` lint.
+
+use bpflint::LintOpts;
+use bpflint::lint_opts;
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag a program whose locals exceed the stack limit.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        struct event {
+            u32 pid;
+            char comm[16];
+            u64 stack[60];
+        };
+
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            struct event event = {0};
+            char buf[16];
+            bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [stack-usage] estimated stack usage of program `handle__sched_switch` is 520 bytes, exceeding the limit of 512 bytes
+          --> <stdin>:7:4
+           | 
+         7 | int handle__sched_switch(u64 *ctx)
+           |     ^^^^^^^^^^^^^^^^^^^^
+           | 
+          ::: <stdin>:9:17
+           | 
+         9 |     struct event event = {0};
+           |                  ----- `event` uses 504 bytes
+        10 |     char buf[16];
+           |          --- `buf` uses 16 bytes
+           | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that locals of inlined static functions are accounted for.
+#[test]
+fn inlined() {
+    let code = indoc! { r#"
+        static int small(void)
+        {
+            char buf[8];
+            return 0;
+        }
+
+        static int large(void)
+        {
+            char buf[256];
+            return small();
+        }
+
+        SEC("tp/syscalls/sys_enter_nanosleep")
+        int prog(void *ctx)
+        {
+            char scratch[300];
+            small();
+            large();
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [stack-usage] estimated stack usage of program `prog` is 564 bytes, exceeding the limit of 512 bytes
+          --> <stdin>:13:4
+           | 
+        13 | int prog(void *ctx)
+           |     ^^^^
+           | 
+          ::: <stdin>:15:9
+           | 
+        15 |     char scratch[300];
+           |          ------- `scratch` uses 300 bytes
+           | 
+          ::: <stdin>:8:9
+           | 
+         8 |     char buf[256];
+           |          --- `buf` uses 256 bytes (inlined from `large`)
+           | 
+          ::: <stdin>:2:9
+           | 
+         2 |     char buf[8];
+           |          --- `buf` uses 8 bytes (inlined from `small`)
+           | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that locals of functions that are not inlined are not
+/// attributed to the caller's stack frame.
+#[test]
+fn no_match_noinline() {
+    let code = indoc! { r#"
+        static __noinline int large(void)
+        {
+            char buf[256];
+            return 0;
+        }
+
+        static __attribute__((noinline)) int larger(void)
+        {
+            char buf[384];
+            return 0;
+        }
+
+        SEC("tp/syscalls/sys_enter_nanosleep")
+        int prog(void *ctx)
+        {
+            char scratch[300];
+            large();
+            larger();
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
+
+
+/// Check that the stack limit is configurable.
+#[test]
+fn custom_limit() {
+    let code = indoc! { r#"
+        SEC("tp/syscalls/sys_enter_nanosleep")
+        int prog(void *ctx)
+        {
+            char buf[128];
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let opts = LintOpts {
+        stack_limit: 64,
+        ..Default::default()
+    };
+    let matches = lint_opts(code.as_bytes(), &opts).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].lint_name, "stack-usage");
+}
+
+
+/// Make sure that programs within the limit, as well as pointers and
+/// static locals, are not flagged.
+#[test]
+fn no_match_within_limit() {
+    let code = indoc! { r#"
+        struct unknown;
+
+        SEC("tp/syscalls/sys_enter_nanosleep")
+        int prog(void *ctx)
+        {
+            static char table[1024];
+            struct unknown *ptr = NULL;
+            char buf[256];
+            u64 values[16];
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,129 @@
+//! Tests for the `struct-padding` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag a struct with padding holes sent via a perf
+/// buffer.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        struct event {
+            u32 pid;
+            u64 ts;
+            char comm[16];
+            u8 flags;
+        };
+
+        static void emit(void *ctx)
+        {
+            struct event event;
+            event.pid = 1;
+            bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [struct-padding] `event` is sent to user space with 11 bytes of uninitialized padding, leaking stack memory; initialize it with `= {}` or clear it with `__builtin_memset` first
+          --> <stdin>:11:4
+           | 
+        11 |     bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+           |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+           | 
+          ::: <stdin>:9:17
+           | 
+         9 |     struct event event;
+           |                  ----- `event` is declared here
+           | 
+          ::: <stdin>:1:8
+           | 
+         1 |     u32 pid;
+           |         --- 4 bytes of padding after `pid`
+           | 
+          ::: <stdin>:4:7
+           | 
+         4 |     u8 flags;
+           |        ----- 7 bytes of trailing padding after `flags`
+           | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag ring buffer reservations of structs with padding
+/// holes.
+#[test]
+fn ringbuf_reserve() {
+    let code = indoc! { r#"
+        struct event {
+            u32 pid;
+            u64 ts;
+        };
+
+        static void emit(void)
+        {
+            struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (!e)
+                return;
+            e->pid = 1;
+            bpf_ringbuf_submit(e, 0);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [struct-padding] ring buffer reservation `e` has 4 bytes of padding that are never initialized, leaking kernel memory; clear it with `__builtin_memset` first
+          --> <stdin>:7:22
+          | 
+        7 |     struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+          |                       ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:1:8
+          | 
+        1 |     u32 pid;
+          |         --- 4 bytes of padding after `pid`
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that cleared structs as well as those without padding
+/// are not flagged.
+#[test]
+fn no_match_cleared() {
+    let code = indoc! { r#"
+        struct event {
+            u32 pid;
+            u64 ts;
+        };
+
+        struct packed {
+            u32 pid;
+            u32 tid;
+        };
+
+        static void emit(void *ctx)
+        {
+            struct event zeroed = {};
+            struct event cleared;
+            struct packed packed;
+            struct event *e;
+
+            __builtin_memset(&cleared, 0, sizeof(cleared));
+            bpf_ringbuf_output(&rb, &zeroed, sizeof(zeroed), 0);
+            bpf_ringbuf_output(&rb, &cleared, sizeof(cleared), 0);
+            bpf_ringbuf_output(&rb, &packed, sizeof(packed), 0);
+
+            e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (!e)
+                return;
+            __builtin_memset(e, 0, sizeof(*e));
+            bpf_ringbuf_submit(e, 0);
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
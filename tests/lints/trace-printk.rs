@@ -0,0 +1,42 @@
+//! Tests for the `trace-printk` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static void trace(int pid)
+        {
+            char fmt[] = "pid: %d\n";
+            bpf_trace_printk(fmt, sizeof(fmt), pid);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [trace-printk] bpf_trace_printk() with a manually declared format buffer is superseded by bpf_printk() from bpf_helpers.h, which declares the buffer for you; replace `char <fmt>[] = "<format>"; bpf_trace_printk(<fmt>, sizeof(<fmt>), <args>...)` with `bpf_printk("<format>", <args>...)`
+          --> <stdin>:3:4
+          | 
+        3 |     bpf_trace_printk(fmt, sizeof(fmt), pid);
+          |     ^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that we don't flag usage of `bpf_printk()`.
+#[test]
+fn no_match_bpf_printk() {
+    let code = indoc! { r#"
+        static void trace(int pid)
+        {
+            bpf_printk("pid: %d\n", pid);
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,234 @@
+//! Tests for the `unbalanced-lock` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag a return while holding a spin lock.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int helper(struct value *val)
+        {
+            bpf_spin_lock(&val->lock);
+            if (val->count > 10)
+                return 1;
+            val->count++;
+            bpf_spin_unlock(&val->lock);
+            return 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unbalanced-lock] returning while spin lock `&val->lock` is held
+          --> <stdin>:4:8
+          | 
+        4 |         return 1;
+          |         ^^^^^^^^^
+          | 
+          ::: <stdin>:2:4
+          | 
+        2 |     bpf_spin_lock(&val->lock);
+          |     ------------------------- spin lock `&val->lock` acquired here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that a lock held only on some paths into a return is reported
+/// as such.
+#[test]
+fn maybe_held() {
+    let code = indoc! { r#"
+        static int helper(struct value *val, bool lock)
+        {
+            if (lock)
+                bpf_spin_lock(&val->lock);
+            return 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unbalanced-lock] returning while spin lock `&val->lock` may be held
+          --> <stdin>:4:4
+          | 
+        3 |         bpf_spin_lock(&val->lock);
+          |         ------------------------- spin lock `&val->lock` acquired here
+        4 |     return 0;
+          |     ^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag helper calls while holding a spin lock and
+/// unlocks happening only on some paths.
+#[test]
+fn forbidden_call_unbalanced() {
+    let code = indoc! { r#"
+        static void helper(struct value *val, bool lock)
+        {
+            if (lock)
+                bpf_spin_lock(&val->lock);
+            val->ts = bpf_ktime_get_ns();
+            bpf_spin_unlock(&val->lock);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unbalanced-lock] calling `bpf_ktime_get_ns` is not allowed while holding spin lock `&val->lock`
+          --> <stdin>:4:14
+          | 
+        3 |         bpf_spin_lock(&val->lock);
+          |         ------------------------- spin lock `&val->lock` acquired here
+        4 |     val->ts = bpf_ktime_get_ns();
+          |               ^^^^^^^^^^^^^^^^^^
+          | 
+        warning: [unbalanced-lock] spin lock `&val->lock` is released without being held on all paths
+          --> <stdin>:5:4
+          | 
+        5 |     bpf_spin_unlock(&val->lock);
+          |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:3:8
+          | 
+        3 |         bpf_spin_lock(&val->lock);
+          |         ------------------------- spin lock `&val->lock` is only acquired on some paths here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag calls to functions other than BPF helpers while
+/// holding a spin lock.
+#[test]
+fn forbidden_call_non_helper() {
+    let code = indoc! { r#"
+        static void helper(struct value *val)
+        {
+            bpf_spin_lock(&val->lock);
+            WRITE_ONCE(val->count, 0);
+            update(val);
+            bpf_spin_unlock(&val->lock);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unbalanced-lock] calling `update` is not allowed while holding spin lock `&val->lock`
+          --> <stdin>:4:4
+          | 
+        4 |     update(val);
+          |     ^^^^^^^^^^^
+          | 
+          ::: <stdin>:2:4
+          | 
+        2 |     bpf_spin_lock(&val->lock);
+          |     ------------------------- spin lock `&val->lock` acquired here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag sleepable helpers inside of RCU read sections
+/// and sections not being closed.
+#[test]
+fn rcu() {
+    let code = indoc! { r#"
+        static void helper(void *dst, const void *src)
+        {
+            bpf_rcu_read_lock();
+            bpf_copy_from_user(dst, 8, src);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unbalanced-lock] calling `bpf_copy_from_user` is not allowed while holding RCU read lock
+          --> <stdin>:3:4
+          | 
+        2 |     bpf_rcu_read_lock();
+          |     ------------------- RCU read lock acquired here
+        3 |     bpf_copy_from_user(dst, 8, src);
+          |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+        warning: [unbalanced-lock] returning while RCU read lock is held
+          --> <stdin>:4:0
+          | 
+        4 | }
+          | ^
+          | 
+          ::: <stdin>:2:4
+          | 
+        2 |     bpf_rcu_read_lock();
+          |     ------------------- RCU read lock acquired here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that nested RCU read sections have to be closed as often as
+/// they are opened.
+#[test]
+fn rcu_nested() {
+    let code = indoc! { r#"
+        static void helper(struct value *val)
+        {
+            bpf_rcu_read_lock();
+            bpf_rcu_read_lock();
+            val->count = 0;
+            bpf_rcu_read_unlock();
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unbalanced-lock] returning while RCU read lock is held
+          --> <stdin>:6:0
+          | 
+        6 | }
+          | ^
+          | 
+          ::: <stdin>:2:4
+          | 
+        2 |     bpf_rcu_read_lock();
+          |     ------------------- RCU read lock acquired here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that balanced locking is not flagged.
+#[test]
+fn no_match_balanced() {
+    let code = indoc! { r#"
+        static int helper(struct value *val)
+        {
+            struct node *n;
+
+            bpf_spin_lock(&val->lock);
+            if (val->count > 10) {
+                bpf_spin_unlock(&val->lock);
+                return 1;
+            }
+            val->count = bpf_htonl(val->count + 1);
+            bpf_rbtree_add(&val->root, &n->node, less);
+            bpf_spin_unlock(&val->lock);
+
+            bpf_rcu_read_lock();
+            val->ts = bpf_ktime_get_ns();
+            bpf_rcu_read_lock();
+            val->count = 0;
+            bpf_rcu_read_unlock();
+            bpf_rcu_read_unlock();
+            return 0;
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,220 @@
+//! Tests for the `unchecked-packet-access` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag a header access before the corresponding bounds
+/// check.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        SEC("xdp")
+        int xdp_prog(struct xdp_md *ctx)
+        {
+            void *data_end = (void *)(long)ctx->data_end;
+            void *data = (void *)(long)ctx->data;
+            struct ethhdr *eth = data;
+            struct iphdr *iph;
+
+            if (data + sizeof(*eth) > data_end)
+                return XDP_DROP;
+            if (eth->h_proto != bpf_htons(ETH_P_IP))
+                return XDP_PASS;
+
+            iph = (void *)(eth + 1);
+            if (iph->protocol == IPPROTO_TCP)
+                return XDP_DROP;
+            return XDP_PASS;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unchecked-packet-access] packet access via `iph` is not preceded by a bounds check against `data_end`
+          --> <stdin>:14:8
+           | 
+        13 |     iph = (void *)(eth + 1);
+           |           ----------------- `iph` points into the packet here
+        14 |     if (iph->protocol == IPPROTO_TCP)
+           |         ^^^^^^^^^^^^^
+           | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that a bounds check only on some paths is flagged.
+#[test]
+fn check_on_some_path() {
+    let code = indoc! { r#"
+        SEC("tc")
+        int tc_prog(struct __sk_buff *skb)
+        {
+            void *data = (void *)(long)skb->data;
+            void *data_end = (void *)(long)skb->data_end;
+            struct ethhdr *eth = data;
+
+            if (skb->len > 64 && (void *)(eth + 1) > data_end)
+                return TC_ACT_SHOT;
+            return eth->h_proto;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unchecked-packet-access] packet access via `eth` is not preceded by a bounds check against `data_end`
+          --> <stdin>:9:11
+          | 
+        9 |     return eth->h_proto;
+          |            ^^^^^^^^^^^^
+          | 
+          ::: <stdin>:5:25
+          | 
+        5 |     struct ethhdr *eth = data;
+          |                          ---- `eth` points into the packet here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag accesses exceeding the checked number of bytes.
+#[test]
+fn check_too_short() {
+    let code = indoc! { r#"
+        SEC("xdp")
+        int xdp_prog(struct xdp_md *ctx)
+        {
+            void *data_end = (void *)(long)ctx->data_end;
+            void *data = (void *)(long)ctx->data;
+            struct ethhdr *eth = data;
+
+            if (data + 1 > data_end)
+                return XDP_DROP;
+            return eth->h_proto;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unchecked-packet-access] packet access via `eth` covers 14 bytes, but only 1 are bounds checked against `data_end`
+          --> <stdin>:9:11
+          | 
+        9 |     return eth->h_proto;
+          |            ^^^^^^^^^^^^
+          | 
+          ::: <stdin>:5:25
+          | 
+        5 |     struct ethhdr *eth = data;
+          |                          ---- `eth` points into the packet here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that checks of derived pointers and inverted comparisons
+/// are understood.
+#[test]
+fn no_match_checked() {
+    let code = indoc! { r#"
+        SEC("tcx/ingress")
+        int tc_prog(struct __sk_buff *skb)
+        {
+            void *data = (void *)(long)skb->data;
+            void *data_end = (void *)(long)skb->data_end;
+            struct ethhdr *eth = data;
+            struct iphdr *iph = data + sizeof(*eth);
+
+            if ((void *)(iph + 1) > data_end)
+                return TC_ACT_SHOT;
+            if (eth->h_proto == bpf_htons(ETH_P_IP) && iph->protocol == IPPROTO_UDP)
+                return TC_ACT_OK;
+            return TC_ACT_OK;
+        }
+
+        SEC("xdp")
+        int xdp_prog(struct xdp_md *ctx)
+        {
+            struct ethhdr *eth = (void *)(long)ctx->data;
+            struct udphdr *udp;
+
+            if ((void *)(eth + 1) <= (void *)(long)ctx->data_end) {
+                udp = (void *)eth + sizeof(*eth) + sizeof(struct iphdr);
+                if ((void *)udp + sizeof(*udp) > (void *)(long)ctx->data_end)
+                    return XDP_PASS;
+                return eth->h_proto + udp->len;
+            }
+            return XDP_PASS;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
+
+
+/// Make sure that a combined check of multiple headers covers header
+/// pointers derived before as well as after it.
+#[test]
+fn no_match_combined_check() {
+    let code = indoc! { r#"
+        SEC("xdp")
+        int check_then_derive(struct xdp_md *ctx)
+        {
+            void *data = (void *)(long)ctx->data;
+            void *data_end = (void *)(long)ctx->data_end;
+            struct ethhdr *eth = data;
+            struct iphdr *iph;
+
+            if (data + sizeof(*eth) + sizeof(*iph) > data_end)
+                return XDP_PASS;
+            iph = data + sizeof(*eth);
+            if (iph->protocol == IPPROTO_TCP)
+                return XDP_DROP;
+            return XDP_PASS;
+        }
+
+        SEC("xdp")
+        int derive_then_check(struct xdp_md *ctx)
+        {
+            void *data = (void *)(long)ctx->data;
+            void *data_end = (void *)(long)ctx->data_end;
+            struct ethhdr *eth = data;
+            struct iphdr *iph = data + sizeof(*eth);
+
+            if (data + sizeof(*eth) + sizeof(*iph) > data_end)
+                return XDP_PASS;
+            if (iph->protocol == IPPROTO_TCP)
+                return XDP_DROP;
+            return XDP_PASS;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
+
+
+/// Make sure that programs of other types are ignored.
+#[test]
+fn no_match_other_program() {
+    let code = indoc! { r#"
+        SEC("socket")
+        int socket_prog(struct __sk_buff *skb)
+        {
+            struct ethhdr *eth = (void *)(long)skb->data;
+            return eth->h_proto;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,216 @@
+//! Tests for the `unpaired-ringbuf-reserve` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag a reservation that leaks on an early return.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int helper(u32 pid)
+        {
+            struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (!e)
+                return 0;
+            if (pid == 0)
+                return 0;
+            e->pid = pid;
+            bpf_ringbuf_submit(e, 0);
+            return 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unpaired-ringbuf-reserve] ring buffer reservation is not submitted or discarded on all paths; the verifier will reject the program with an unreleased reference error
+          --> <stdin>:2:22
+          | 
+        2 |     struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+          |                       ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:6:8
+          | 
+        6 |         return 0;
+          |         --------- the reservation is leaked if the function returns here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that falling off the end of a function is considered a
+/// leak as well, pointing at the last statement on the leaking path.
+#[test]
+fn fall_through() {
+    let code = indoc! { r#"
+        static void helper(void)
+        {
+            struct event *e;
+            e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (e)
+                e->pid = 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unpaired-ringbuf-reserve] ring buffer reservation is not submitted or discarded on all paths; the verifier will reject the program with an unreleased reference error
+          --> <stdin>:3:8
+          | 
+        3 |     e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+          |         ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:5:8
+          | 
+        5 |         e->pid = 0;
+          |         ----------- the reservation is leaked if the function ends after this
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we point at the branch leaking a reservation.
+#[test]
+fn branch() {
+    let code = indoc! { r#"
+        static void helper(bool done)
+        {
+            struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (!e)
+                return;
+            if (done)
+                bpf_ringbuf_submit(e, 0);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unpaired-ringbuf-reserve] ring buffer reservation is not submitted or discarded on all paths; the verifier will reject the program with an unreleased reference error
+          --> <stdin>:2:22
+          | 
+        2 |     struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+          |                       ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:5:8
+          | 
+        5 |     if (done)
+          |         ---- the reservation is leaked if this condition is false
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that we flag double submits and accesses after a submit.
+#[test]
+fn double_submit_use_after_submit() {
+    let code = indoc! { r#"
+        static void helper(bool discard)
+        {
+            struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (!e)
+                return;
+            if (discard)
+                bpf_ringbuf_discard(e, 0);
+            bpf_ringbuf_submit(e, 0);
+            e->pid = 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unpaired-ringbuf-reserve] ring buffer reservation `e` is released more than once
+          --> <stdin>:7:4
+          | 
+        6 |         bpf_ringbuf_discard(e, 0);
+          |         ------------------------- `e` was already discarded here
+        7 |     bpf_ringbuf_submit(e, 0);
+          |     ^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+        warning: [unpaired-ringbuf-reserve] ring buffer reservation `e` is used after being submitted
+          --> <stdin>:8:4
+          | 
+        7 |     bpf_ringbuf_submit(e, 0);
+          |     ------------------------ `e` was submitted here
+        8 |     e->pid = 0;
+          |     ^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Check that a release following a use after release is still
+/// flagged, while the use is reported only once.
+#[test]
+fn use_then_double_release() {
+    let code = indoc! { r#"
+        static void helper(void)
+        {
+            struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (!e)
+                return;
+            bpf_ringbuf_submit(e, 0);
+            e->pid = 0;
+            e->tid = 0;
+            bpf_ringbuf_discard(e, 0);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unpaired-ringbuf-reserve] ring buffer reservation `e` is used after being submitted
+          --> <stdin>:6:4
+          | 
+        5 |     bpf_ringbuf_submit(e, 0);
+          |     ------------------------ `e` was submitted here
+        6 |     e->pid = 0;
+          |     ^^^^^^
+          | 
+        warning: [unpaired-ringbuf-reserve] ring buffer reservation `e` is released more than once
+          --> <stdin>:8:4
+          | 
+        8 |     bpf_ringbuf_discard(e, 0);
+          |     ^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+          ::: <stdin>:5:4
+          | 
+        5 |     bpf_ringbuf_submit(e, 0);
+          |     ------------------------ `e` was already submitted here
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that properly paired reservations are not flagged.
+#[test]
+fn no_match_paired() {
+    let code = indoc! { r#"
+        static int helper(u32 pid)
+        {
+            struct event *e, *copy;
+
+            e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+            if (e == NULL)
+                return 0;
+            copy = e;
+            if (pid == 0) {
+                bpf_ringbuf_discard(copy, 0);
+                return 0;
+            }
+            e->pid = pid;
+            bpf_ringbuf_submit(e, 0);
+
+            bpf_for(i, 0, 4) {
+                struct event *x = bpf_ringbuf_reserve(&rb, sizeof(*x), 0);
+                if (!x)
+                    break;
+                x->pid = i;
+                bpf_ringbuf_submit(x, 0);
+            }
+            return 0;
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -0,0 +1,93 @@
+//! Tests for the `unreachable-code` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag code following a `return`.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            return 0;
+            bpf_printk("unreachable");
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unreachable-code] unreachable code after an unconditional return statement
+          --> <stdin>:4:4
+          | 
+        3 |     return 0;
+          |     --------- any code following this return is unreachable
+        4 |     bpf_printk("unreachable");
+          |     ^^^^^^^^^^^^^^^^^^^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that we report an unreachable region only once and
+/// attribute loops correctly.
+#[test]
+fn unreachable_loop() {
+    let code = indoc! { r#"
+        static int helper(int a)
+        {
+            if (a) {
+                return 1;
+                while (a--) {
+                    a = a / 2;
+                }
+            }
+            return 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unreachable-code] unreachable code after an unconditional return statement
+          --> <stdin>:4:8
+          | 
+        3 |         return 1;
+          |         --------- any code following this return is unreachable
+        4 |  /         while (a--) {
+        5 |  |             a = a / 2;
+        6 |  |         }
+          |  |_________^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that conditional returns don't cause false positives.
+#[test]
+fn no_match_conditional_return() {
+    let code = indoc! { r#"
+        static int helper(int *a)
+        {
+            if (!a)
+                return 0;
+            switch (*a) {
+            case 1:
+                return 1;
+            default:
+                break;
+            }
+            bpf_for(i, 0, 10) {
+                if (i == *a)
+                    return i;
+            }
+            return -1;
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
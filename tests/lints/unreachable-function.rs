@@ -0,0 +1,88 @@
+//! Tests for the `unreachable-function` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+/// Check that we flag static functions not used by any program.
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int used(void)
+        {
+            return 0;
+        }
+
+        static int unused(void)
+        {
+            return used();
+        }
+
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            return used();
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [unreachable-function] static function `unused` is not reachable from any BPF program
+          --> <stdin>:5:11
+          | 
+        5 | static int unused(void)
+          |            ^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
+
+
+/// Make sure that callbacks and functions used in global definitions
+/// are considered reachable.
+#[test]
+fn no_match_callback() {
+    let code = indoc! { r#"
+        static int callback(u32 idx, void *ctx)
+        {
+            return 0;
+        }
+
+        static s32 init(void)
+        {
+            return 0;
+        }
+
+        SEC(".struct_ops")
+        struct sched_ext_ops ops = {
+            .init = (void *)init,
+        };
+
+        SEC("tp_btf/sched_switch")
+        int handle__sched_switch(u64 *ctx)
+        {
+            bpf_loop(4, callback, NULL, 0);
+            return 0;
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
+}
+
+
+/// Make sure that files without programs are not checked.
+#[test]
+fn no_match_no_programs() {
+    let code = indoc! { r#"
+        static int helper(void)
+        {
+            return 0;
+        }
+    "# };
+    assert_eq!(lint_report(code), "");
+}
@@ -4,7 +4,19 @@ use indoc::indoc;
 
 use pretty_assertions::assert_eq;
 
+use bpflint::LintOpts;
+
 use crate::util::lint_report;
+use crate::util::lint_report_opts;
+
+
+/// Lint options with the `unstable-attach-point` lint enabled.
+fn unstable_opts() -> LintOpts {
+    LintOpts {
+        enabled_lints: vec!["unstable-attach-point".to_string()],
+        ..Default::default()
+    }
+}
 
 
 #[test]
@@ -13,6 +25,8 @@ fn basic() {
         SEC("fentry/do_nanosleep")
         int nanosleep(void *ctx) {
         }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     let expected = indoc! { r#"
@@ -23,7 +37,7 @@ fn basic() {
           |     ^^^^^^^^^^^^^^^^^^^^^
           | 
     "# };
-    assert_eq!(lint_report(code), expected);
+    assert_eq!(lint_report_opts(code, &unstable_opts()), expected);
 }
 
 
@@ -34,6 +48,9 @@ fn basic2() {
 
         int BPF_KPROBE(kprobe__foobar, const struct cred *cred,
                        struct user_namespace *targ_ns, int cap, int cap_opt) {
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
     "# };
 
     let expected = indoc! { r#"
@@ -44,5 +61,19 @@ fn basic2() {
           |     ^^^^^^^^^^^^^^^^^^^^
           | 
     "# };
-    assert_eq!(lint_report(code), expected);
+    assert_eq!(lint_report_opts(code, &unstable_opts()), expected);
+}
+
+
+/// Check that the lint is disabled by default.
+#[test]
+fn disabled_by_default() {
+    let code = indoc! { r#"
+        SEC("fentry/do_nanosleep")
+        int nanosleep(void *ctx) {
+        }
+
+        char LICENSE[] SEC("license") = "GPL";
+    "# };
+    assert_eq!(lint_report(code), "");
 }
@@ -0,0 +1,30 @@
+//! Tests for the `variable-length-array` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int copy(const char *src, u32 len)
+        {
+            char buf[len];
+            char name[TASK_COMM_LEN];
+            return bpf_probe_read_kernel(buf, sizeof(buf), src);
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [variable-length-array] array `buf` has non-constant size `len`; BPF programs have a fixed-size stack frame that the verifier checks at load time, so variable-length arrays are not supported; use a fixed-size array sized for the maximum length instead
+          --> <stdin>:2:9
+          | 
+        2 |     char buf[len];
+          |          ^^^^^^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
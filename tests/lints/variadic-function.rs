@@ -0,0 +1,28 @@
+//! Tests for the `variadic-function` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use crate::util::lint_report;
+
+
+#[test]
+fn basic() {
+    let code = indoc! { r#"
+        static int log(const char *fmt, ...)
+        {
+            return 0;
+        }
+    "# };
+
+    let expected = indoc! { r#"
+        warning: [variadic-function] variadic functions are not supported by BPF: arguments are passed in registers R1-R5 only and there is no stack area from which va_arg() could fetch further ones; use a fixed parameter list or a macro instead
+          --> <stdin>:0:32
+          | 
+        0 | static int log(const char *fmt, ...)
+          |                                 ^^^
+          | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
@@ -2,7 +2,9 @@
 
 use std::path::Path;
 
-use bpflint::lint;
+use bpflint::LintMatch;
+use bpflint::LintOpts;
+use bpflint::lint_opts;
 use bpflint::report_terminal;
 
 
@@ -12,11 +14,49 @@ pub fn lint_report<C>(code: C) -> String
 where
     C: AsRef<[u8]>,
 {
-    let mut report = Vec::new();
-    let () = lint(code.as_ref())
+    lint_report_opts(code, &LintOpts::default())
+}
+
+/// Lint `code` with lints configured as per `opts` and report matches
+/// as a string created using [`report_terminal`].
+pub fn lint_report_opts<C>(code: C, opts: &LintOpts) -> String
+where
+    C: AsRef<[u8]>,
+{
+    report(code.as_ref(), lint_opts(code.as_ref(), opts).unwrap())
+}
+
+/// Lint `code` and report matches of the lint with name `lint` only,
+/// as a string created using [`report_terminal`].
+pub fn lint_report_for<C>(code: C, lint: &str) -> String
+where
+    C: AsRef<[u8]>,
+{
+    lint_report_for_opts(code, lint, &LintOpts::default())
+}
+
+/// Lint `code` with lints configured as per `opts` and report matches
+/// of the lint with name `lint` only, as a string created using
+/// [`report_terminal`].
+pub fn lint_report_for_opts<C>(code: C, lint: &str, opts: &LintOpts) -> String
+where
+    C: AsRef<[u8]>,
+{
+    let matches = lint_opts(code.as_ref(), opts)
         .unwrap()
         .into_iter()
-        .try_for_each(|m| report_terminal(&m, code.as_ref(), Path::new("<stdin>"), &mut report))
+        .filter(|m| m.lint_name == lint)
+        .collect();
+    report(code.as_ref(), matches)
+}
+
+/// Report `matches` in `code` as a string created using
+/// [`report_terminal`].
+fn report(code: &[u8], matches: Vec<LintMatch>) -> String {
+    let mut report = Vec::new();
+    let () = matches
+        .into_iter()
+        .try_for_each(|m| report_terminal(&m, code, Path::new("<stdin>"), &mut report))
         .unwrap();
     let report = String::from_utf8(report).unwrap();
     report